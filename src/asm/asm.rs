@@ -14,15 +14,15 @@ pub enum Register {
     Rsi,
     Rdi,
     Rsp, // Stack Pointer
-    Rbp,
+    Rbp, // Frame Pointer
     R8,
     R9,
     R10,
     R11,
     R12,
     R13,
-    R14, // Scratch 1
-    R15, // Scratch 2
+    R14, // Scratch
+    R15,
 }
 
 /// Registers for floating point (SSE). Registers with special purposes have been annotated.
@@ -48,16 +48,18 @@ pub enum FloatRegister {
 }
 
 /// Operands for instructions.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Operand {
     Reg(Register),
     FloatReg(FloatRegister),
     Imm(i64),
     FloatImm(f64),
     MemOffset(Box<Operand>, Box<Operand>),
+    MemLabel(String), // memory at a label, addressed relative to rip (like the constant pool, see `compiler.rs`)
 }
 
 /// Every instruction, annotated with allowed operand combinations.
+#[derive(Clone)]
 pub enum Instruction {
     Global(String),
     Extern(String),
@@ -77,6 +79,7 @@ pub enum Instruction {
     Div(Operand),          // / (src = rdx:rax) (IDIV)  (reg|mem)
     Mul(Operand, Operand), // * (src = rdx:rax) (IMUL)  (reg, reg|mem|imm)
     Mul3(Operand, Operand, Operand), // a = b * c       (reg, reg|mem, imm)
+    Mul1(Operand),         // rdx:rax = rax * src (IMUL) (reg|mem)
     Neg(Operand),          // negation                  (mem|reg)
     Cqo,                   // sign extend rax into rdx
 
@@ -97,6 +100,11 @@ pub enum Instruction {
     Jnl(String),
     Jg(String),
     Jng(String),
+    Ja(String),  // jump if above (for unsigned and `ucomisd` comparisons)
+    Jae(String), // jump if above or equal
+    Jb(String),  // jump if below
+    Jbe(String), // jump if below or equal
+    Jp(String),  // jump if parity (for unordered `ucomisd` comparisons)
     ComputedJmp(Operand),
     Ret,
     Push(Operand),
@@ -112,6 +120,10 @@ pub enum Instruction {
     Mulsd(Operand, Operand),     // multiply scalar floats       (xmm, xmm/mem)
     Divsd(Operand, Operand),     // divide scalar floats         (xmm, xmm/mem)
     Cmpsd(Operand, Operand, u8), // compare scalar floats        (xmm, xmm) see https://c9x.me/x86/html/file_module_x86_id_39.html
+    Ucomisd(Operand, Operand),   // compare floats into flags   (xmm, xmm/mem)
+
+    SourceFile(String), // The Solis file that the `Line`s are in (for debug info)
+    Line(usize),        // The line of the Solis file that the following instructions were compiled from
 
     Comment(String),                    // Top level comment
     Annotate(Box<Instruction>, String), // Annotated Instruction with comment
@@ -123,4 +135,83 @@ impl Instruction {
     pub fn annotated(self, comment: &str) -> Self {
         Self::Annotate(Box::new(self), comment.to_string())
     }
+
+    /// The (explicit) operands of an Instruction.
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Self::Mov(operand_1, operand_2)
+            | Self::MovByte(operand_1, operand_2)
+            | Self::Add(operand_1, operand_2)
+            | Self::Sub(operand_1, operand_2)
+            | Self::Mul(operand_1, operand_2)
+            | Self::Shl(operand_1, operand_2)
+            | Self::Shr(operand_1, operand_2)
+            | Self::Sar(operand_1, operand_2)
+            | Self::Cmp(operand_1, operand_2)
+            | Self::And(operand_1, operand_2)
+            | Self::Or(operand_1, operand_2)
+            | Self::Movq(operand_1, operand_2)
+            | Self::Cvttsd2si(operand_1, operand_2)
+            | Self::Cvtsi2sd(operand_1, operand_2)
+            | Self::Xorpd(operand_1, operand_2)
+            | Self::Addsd(operand_1, operand_2)
+            | Self::Subsd(operand_1, operand_2)
+            | Self::Mulsd(operand_1, operand_2)
+            | Self::Divsd(operand_1, operand_2)
+            | Self::Ucomisd(operand_1, operand_2)
+            | Self::Cmpsd(operand_1, operand_2, _) => vec![operand_1, operand_2],
+            Self::Mul3(operand_1, operand_2, operand_3) => vec![operand_1, operand_2, operand_3],
+            Self::Div(operand)
+            | Self::Mul1(operand)
+            | Self::Neg(operand)
+            | Self::Setz(operand)
+            | Self::Setnz(operand)
+            | Self::Setl(operand)
+            | Self::Setle(operand)
+            | Self::ComputedJmp(operand)
+            | Self::Push(operand)
+            | Self::Pop(operand)
+            | Self::LeaLabel(operand, _) => vec![operand],
+            Self::Annotate(instruction, _) => instruction.operands(),
+            Self::Global(..)
+            | Self::Extern(..)
+            | Self::Section(..)
+            | Self::Label(..)
+            | Self::DqLabel(..)
+            | Self::DqString(..)
+            | Self::DqInt(..)
+            | Self::Align(..)
+            | Self::Cqo
+            | Self::Jmp(..)
+            | Self::Je(..)
+            | Self::Jne(..)
+            | Self::Jl(..)
+            | Self::Jnl(..)
+            | Self::Jg(..)
+            | Self::Jng(..)
+            | Self::Ja(..)
+            | Self::Jae(..)
+            | Self::Jb(..)
+            | Self::Jbe(..)
+            | Self::Jp(..)
+            | Self::Ret
+            | Self::Call(..)
+            | Self::SourceFile(..)
+            | Self::Line(..)
+            | Self::Comment(..) => vec![],
+        }
+    }
+}
+
+impl Operand {
+    /// Whether an Operand is or contains (as the address of a memory operand) a register.
+    pub fn uses_register(&self, register: Register) -> bool {
+        match self {
+            Self::Reg(operand_register) => *operand_register == register,
+            Self::MemOffset(operand_1, operand_2) => {
+                operand_1.uses_register(register) || operand_2.uses_register(register)
+            }
+            Self::FloatReg(..) | Self::Imm(..) | Self::FloatImm(..) | Self::MemLabel(..) => false,
+        }
+    }
 }
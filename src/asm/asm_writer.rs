@@ -1,32 +1,65 @@
 // Copyright © 2022-2023 Brandon Li. All rights reserved.
 
 //! Transforms the in memory representation of assembly into an actual assembly file, using a buffered writer.
+//!
+//! The assembly is written in NASM syntax (see `instruction_to_string`), or in the Intel syntax of the GNU assembler
+//! (see `gas_writer.rs`), depending on the assembler that the file is assembled with. The built-in assembler (see
+//! `encoder.rs`) doesn't need an assembly file, so its assembly is only written (in NASM syntax) with `--emit`.
 
 use asm::asm::{
     FloatRegister, FloatRegister::*, Instruction, Instruction::*, Operand, Operand::*, Register, Register::*,
 };
+use asm::gas_writer::{gas_header, instruction_to_gas_string};
+use clap::ValueEnum;
 use error_messages::internal_compiler_error;
 use std::fs::{create_dir_all, File};
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
+/// The assemblers that the assembly can be written for. The names of each assembler are used in the CLI
+/// (`--assembler`).
+#[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Assembler {
+    /// The Netwide Assembler (`nasm`), with NASM syntax.
+    Nasm,
+
+    /// The GNU assembler (through `cc`), with Intel syntax.
+    Gas,
+
+    /// The built-in assembler, which creates ELF object files without an external assembler (linux only).
+    Builtin,
+}
+
 /// Writes a vector of instructions to a file, using a buffered writer.
-pub fn write_instructions_to_file(instructions: Vec<Instruction>, file_path: &Path) {
+pub fn write_instructions_to_file(instructions: Vec<Instruction>, file_path: &Path, assembler: Assembler) {
     create_dir_all(file_path.parent().unwrap()).unwrap();
 
     let file = File::create(file_path)
         .unwrap_or_else(|error| internal_compiler_error(&format!("unable to open file {error}")));
-    let mut buf_writer = BufWriter::new(file);
 
-    for instruction in instructions {
-        buf_writer
-            .write_all(format!("{}\n", instruction_to_string(instruction)).as_bytes())
+    write_instructions(instructions, &mut BufWriter::new(file), assembler);
+}
+
+/// Writes a vector of instructions to any writer, like a file or stdout, in the syntax of the assembler.
+pub fn write_instructions<W: Write>(instructions: Vec<Instruction>, writer: &mut W, assembler: Assembler) {
+    let lines: Vec<String> = match assembler {
+        Assembler::Nasm | Assembler::Builtin => instructions.into_iter().map(instruction_to_string).collect(),
+        Assembler::Gas => {
+            let mut lines = gas_header();
+            lines.extend(instructions.into_iter().map(instruction_to_gas_string));
+            lines
+        }
+    };
+
+    for line in lines {
+        writer
+            .write_all(format!("{line}\n").as_bytes())
             .unwrap_or_else(|error| internal_compiler_error(&format!("unable to write to file {error}")));
     }
 }
 
-// Converts a Register to a string
-fn register_to_string(register: Register) -> String {
+/// Converts a Register to a string
+pub fn register_to_string(register: Register) -> String {
     match register {
         Rax => "rax",
         Rsi => "rsi",
@@ -48,8 +81,8 @@ fn register_to_string(register: Register) -> String {
     .to_string()
 }
 
-// Converts a Register to a string, where it must be corresponding to a BYTE
-fn byte_register_to_string(register: Register) -> String {
+/// Converts a Register to a string, where it must be corresponding to a BYTE
+pub fn byte_register_to_string(register: Register) -> String {
     match register {
         Rax => "al",
         Rsi => "sil",
@@ -71,8 +104,8 @@ fn byte_register_to_string(register: Register) -> String {
     .to_string()
 }
 
-// Converts a Float Register to a string
-fn float_register_to_string(register: FloatRegister) -> String {
+/// Converts a Float Register to a string
+pub fn float_register_to_string(register: FloatRegister) -> String {
     match register {
         Xmm0 => "xmm0",
         Xmm1 => "xmm1",
@@ -106,6 +139,7 @@ fn operand_to_string(operand: Operand) -> String {
         ),
         FloatImm(imm) => format!("__?float64?__({imm:#?})"),
         FloatReg(reg) => float_register_to_string(reg),
+        MemLabel(label) => format!("QWORD [rel {}]", label_name(label)),
     }
 }
 
@@ -117,14 +151,15 @@ fn byte_operand_to_string(operand: Operand) -> String {
             &operand_to_string(*operand_1),
             &operand_to_string(*operand_2)
         ),
+        MemLabel(label) => format!("BYTE [rel {}]", label_name(label)),
         Reg(reg) => byte_register_to_string(reg),
         Imm(..) | FloatImm(..) => operand_to_string(operand),
         FloatReg(..) => internal_compiler_error("float register as byte operand"),
     }
 }
 
-// Decorates labels on mac with `_`
-fn label_name(label: String) -> String {
+/// Decorates labels on mac with `_`
+pub fn label_name(label: String) -> String {
     if cfg!(target_os = "macos") && !cfg!(test) {
         format!("_{label}")
     } else {
@@ -132,8 +167,8 @@ fn label_name(label: String) -> String {
     }
 }
 
-// Converts a Instruction to a string
-fn instruction_to_string(instruction: Instruction) -> String {
+/// Converts a Instruction to a string, as it is written in the assembly file.
+pub fn instruction_to_string(instruction: Instruction) -> String {
     #[rustfmt::skip]
     let instruction = match instruction {
         Global(label) =>          format!("global {}", label_name(label)),
@@ -151,6 +186,7 @@ fn instruction_to_string(instruction: Instruction) -> String {
         Div(src) =>               format!("\tidiv {}", operand_to_string(src)),
         Mul(dest, src) =>         format!("\timul {}, {}", operand_to_string(dest), operand_to_string(src)),
         Mul3(dest, src, con) =>   format!("\timul {}, {}, {}", operand_to_string(dest), operand_to_string(src), operand_to_string(con)),
+        Mul1(src) =>              format!("\timul {}", operand_to_string(src)),
         Cqo =>                            "\tcqo".to_string(),
         Neg(operand) =>           format!("\tneg {}", operand_to_string(operand)),
         Shl(dest, src) =>         format!("\tshl {}, {}", operand_to_string(dest), operand_to_string(src)),
@@ -163,7 +199,7 @@ fn instruction_to_string(instruction: Instruction) -> String {
         Setnz(dest) =>            format!("\tsetnz {}", byte_operand_to_string(dest)),
         Setl(dest) =>             format!("\tsetl {}", byte_operand_to_string(dest)),
         Setle(dest) =>            format!("\tsetle {}", byte_operand_to_string(dest)),
-        LeaLabel(dest, label) =>  format!("\tlea {}, [{}]", operand_to_string(dest), label_name(label)),
+        LeaLabel(dest, label) =>  format!("\tlea {}, [rel {}]", operand_to_string(dest), label_name(label)),
         Jmp(dest) =>              format!("\tjmp {}", label_name(dest)),
         Je(dest) =>               format!("\tje {}", label_name(dest)),
         Jne(dest) =>              format!("\tjne {}", label_name(dest)),
@@ -171,6 +207,11 @@ fn instruction_to_string(instruction: Instruction) -> String {
         Jnl(dest) =>              format!("\tjnl {}", label_name(dest)),
         Jg(dest) =>               format!("\tjg {}", label_name(dest)),
         Jng(dest) =>              format!("\tjng {}", label_name(dest)),
+        Ja(dest) =>               format!("\tja {}", label_name(dest)),
+        Jae(dest) =>              format!("\tjae {}", label_name(dest)),
+        Jb(dest) =>               format!("\tjb {}", label_name(dest)),
+        Jbe(dest) =>              format!("\tjbe {}", label_name(dest)),
+        Jp(dest) =>               format!("\tjp {}", label_name(dest)),
         ComputedJmp(dest) =>      format!("\tjmp {}", operand_to_string(dest)),
         Push(operand) =>          format!("\tpush {}", operand_to_string(operand)),
         Pop(operand) =>           format!("\tpop {}", operand_to_string(operand)),
@@ -186,6 +227,10 @@ fn instruction_to_string(instruction: Instruction) -> String {
         Mulsd(dest, src) =>       format!("\tmulsd {}, {}", operand_to_string(dest), operand_to_string(src)),
         Divsd(dest, src) =>       format!("\tdivsd {}, {}", operand_to_string(dest), operand_to_string(src)),
         Cmpsd(dest, src, mode) => format!("\tcmpsd {}, {}, {mode}", operand_to_string(dest), operand_to_string(src)),
+        Ucomisd(dest, src) =>     format!("\tucomisd {}, {}", operand_to_string(dest), operand_to_string(src)),
+
+        SourceFile(file) =>       format!("%line 1+0 {file}"),
+        Line(line) =>             format!("%line {line}+0"),
 
         Comment(comment) =>       format!("\n; {comment}"),
         Annotate(instruction, comment) => {
@@ -0,0 +1,508 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Creates the DWARF debug info of a compiled program (with `-g`).
+//!
+//! Debuggers (like `gdb`) and `addr2line` use the debug info to map the addresses of the program to the lines of the
+//! Solis file, and to show the values of its variables.
+//!
+//! The compiler marks the instructions of each let binding with the line of the binding (see `Instruction::Line`).
+//! The external assemblers create the debug info from the markers (`%line` for `nasm` and `.loc` for the GNU
+//! assembler, see `asm_writer.rs`). For the built-in assembler, the encoder records the offset of each marker (see
+//! `Object`), and this module adds the debug sections to the object (DWARF 4):
+//!   - `.debug_line`: the line program, which maps the offset of each marker to its line.
+//!   - `.debug_info`: the compile unit, with `entry`, and the variables of `entry` (by their `Assignment`). Variables
+//!     in registers are located with `DW_OP_reg`, and spilled variables with an offset from `rsp` (see `stack_address`),
+//!     which doesn't change after the prologue.
+//!   - `.debug_abbrev`: the abbreviations (the tags and attributes) of the entries of `.debug_info`.
+//!   - `.debug_str`: the strings of the compile unit (like the directory), which are referred to by their offset, so
+//!     that the offsets of the entries of `.debug_info` don't depend on them.
+//!
+//! Only the built-in assembler locates variables, since the external assemblers only create the line info.
+
+use asm::asm::Register;
+use asm::encoder::{Object, Relocation, RelocationKind, Section, SectionKind};
+use ir::ir::{Block, Expr, Program, Type};
+use ir::type_inference::ProgramTypes;
+use register_allocation::register_allocator::{Assignment, Map};
+use std::convert::TryFrom;
+
+// Constants of the DWARF format (see `dwarf.h`).
+const DW_TAG_BASE_TYPE: u8 = 0x24;
+const DW_TAG_COMPILE_UNIT: u8 = 0x11;
+const DW_TAG_SUBPROGRAM: u8 = 0x2e;
+const DW_TAG_VARIABLE: u8 = 0x34;
+
+const DW_AT_BYTE_SIZE: u8 = 0x0b;
+const DW_AT_COMP_DIR: u8 = 0x1b;
+const DW_AT_DECL_LINE: u8 = 0x3b;
+const DW_AT_ENCODING: u8 = 0x3e;
+const DW_AT_EXTERNAL: u8 = 0x3f;
+const DW_AT_HIGH_PC: u8 = 0x12;
+const DW_AT_LOCATION: u8 = 0x02;
+const DW_AT_LOW_PC: u8 = 0x11;
+const DW_AT_NAME: u8 = 0x03;
+const DW_AT_PRODUCER: u8 = 0x25;
+const DW_AT_STMT_LIST: u8 = 0x10;
+const DW_AT_TYPE: u8 = 0x49;
+
+const DW_FORM_ADDR: u8 = 0x01;
+const DW_FORM_DATA1: u8 = 0x0b;
+const DW_FORM_DATA8: u8 = 0x07;
+const DW_FORM_EXPRLOC: u8 = 0x18;
+const DW_FORM_FLAG_PRESENT: u8 = 0x19;
+const DW_FORM_REF4: u8 = 0x13;
+const DW_FORM_SEC_OFFSET: u8 = 0x17;
+const DW_FORM_STRING: u8 = 0x08;
+const DW_FORM_STRP: u8 = 0x0e;
+const DW_FORM_UDATA: u8 = 0x0f;
+
+const DW_ATE_BOOLEAN: u8 = 0x02;
+const DW_ATE_FLOAT: u8 = 0x04;
+const DW_ATE_SIGNED: u8 = 0x05;
+
+const DW_OP_BREG7: u8 = 0x77;
+const DW_OP_REG0: u8 = 0x50;
+const DW_OP_REGX: u8 = 0x90;
+
+const DW_LNS_COPY: u8 = 0x01;
+const DW_LNS_ADVANCE_PC: u8 = 0x02;
+const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+const DW_LNE_END_SEQUENCE: u8 = 0x01;
+const DW_LNE_SET_ADDRESS: u8 = 0x02;
+
+// The codes of the abbreviations of `.debug_abbrev`.
+const COMPILE_UNIT_ABBREV: u8 = 1;
+const SUBPROGRAM_ABBREV: u8 = 2;
+const BASE_TYPE_ABBREV: u8 = 3;
+const VARIABLE_ABBREV: u8 = 4;
+const VARIABLE_WITHOUT_LOCATION_ABBREV: u8 = 5;
+
+// An abbreviation of `.debug_abbrev`, which is its code, tag, whether it has children, and its attributes (with their
+// forms).
+type Abbrev = (u8, u8, bool, &'static [(u8, u8)]);
+
+// The types of the variables, in the order of their base type entries.
+const BASE_TYPES: [(&str, Type, u8, u8); 3] = [
+    ("int", Type::Int, DW_ATE_SIGNED, 8),
+    ("bool", Type::Bool, DW_ATE_BOOLEAN, 1),
+    ("float", Type::Float, DW_ATE_FLOAT, 8),
+];
+
+/// The parts of a program that its debug info describes.
+pub struct DebugInfo {
+    /// The name of the Solis file (as it was given to the compiler).
+    pub file_name: String,
+
+    /// The directory that the compiler was run in, which `file_name` is relative to.
+    pub directory: String,
+
+    /// The variables of the program, in the order that they are bound.
+    pub variables: Vec<Variable>,
+}
+
+/// A variable of a program, with where it is stored.
+pub struct Variable {
+    pub name: String,
+    pub line: Option<usize>,
+    pub variable_type: Type,
+    pub assignment: Assignment,
+}
+
+impl DebugInfo {
+    /// Creates the debug info of a program, where `variable_assignment` is the result of the register allocator on the
+    /// body of the program. Temporary variables of the translator (like `@temp0`) are left out.
+    pub fn new(file_name: &str, program: &Program, variable_assignment: &Map<&String, Assignment>) -> Self {
+        let mut variables = vec![];
+        add_variables(
+            &program.body,
+            &ProgramTypes::new(program),
+            variable_assignment,
+            &mut variables,
+        );
+
+        Self {
+            file_name: file_name.to_string(),
+            directory: std::env::current_dir()
+                .map(|directory| directory.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            variables,
+        }
+    }
+}
+
+// Adds the variables that are bound in a block (and its nested blocks), in order. Variables in sibling blocks can have
+// the same name, but they have the same assignment, so only the first is added.
+fn add_variables(
+    block: &Block,
+    types: &ProgramTypes,
+    variable_assignment: &Map<&String, Assignment>,
+    variables: &mut Vec<Variable>,
+) {
+    for expr in &block.exprs {
+        let (init_expr, binding) = match expr {
+            Expr::Let { id, init_expr, line } => (&**init_expr, Some((id, line))),
+            _ => (expr, None),
+        };
+
+        if let Some((id, line)) = binding {
+            let is_added = variables.iter().any(|variable| &variable.name == id);
+            let variable_type = types.expr_type(init_expr);
+
+            if !id.starts_with('@') && !is_added && variable_type != Type::Unit {
+                variables.push(Variable {
+                    name: id.clone(),
+                    line: *line,
+                    variable_type,
+                    assignment: variable_assignment.get(id).cloned().unwrap_or(Assignment::None),
+                });
+            }
+        }
+
+        if let Expr::If { then_block, else_block, .. } = init_expr {
+            add_variables(then_block, types, variable_assignment, variables);
+            if let Some(else_block) = else_block {
+                add_variables(else_block, types, variable_assignment, variables);
+            }
+        }
+    }
+}
+
+/// Adds the debug sections (`.debug_abbrev`, `.debug_info`, `.debug_line` and `.debug_str`) of a program to the object
+/// of the built-in assembler (see `encode`).
+pub fn add_debug_sections(object: &mut Object, debug_info: &DebugInfo) {
+    let text_size = object
+        .sections
+        .iter()
+        .find(|section| section.kind == SectionKind::Text)
+        .map_or(0, |section| section.bytes.len() as u64);
+    let entry_offset = object
+        .symbols
+        .iter()
+        .find(|symbol| symbol.name == "entry")
+        .and_then(|symbol| symbol.definition)
+        .map_or(0, |(_, offset)| offset);
+
+    let debug_line = debug_line_section(&object.lines, text_size, &debug_info.file_name);
+    let mut debug_str = vec![];
+    object.sections.push(debug_abbrev_section());
+    object
+        .sections
+        .push(debug_info_section(debug_info, text_size, entry_offset, &mut debug_str));
+    object.sections.push(debug_line);
+    object
+        .sections
+        .push(debug_section(SectionKind::DebugStr, debug_str, vec![]));
+}
+
+// The abbreviations of the entries of `.debug_info`.
+fn debug_abbrev_section() -> Section {
+    let abbrevs: [Abbrev; 5] = [
+        (
+            COMPILE_UNIT_ABBREV,
+            DW_TAG_COMPILE_UNIT,
+            true,
+            &[
+                (DW_AT_PRODUCER, DW_FORM_STRP),
+                (DW_AT_NAME, DW_FORM_STRP),
+                (DW_AT_COMP_DIR, DW_FORM_STRP),
+                (DW_AT_STMT_LIST, DW_FORM_SEC_OFFSET),
+                (DW_AT_LOW_PC, DW_FORM_ADDR),
+                (DW_AT_HIGH_PC, DW_FORM_DATA8),
+            ],
+        ),
+        (
+            SUBPROGRAM_ABBREV,
+            DW_TAG_SUBPROGRAM,
+            true,
+            &[
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_EXTERNAL, DW_FORM_FLAG_PRESENT),
+                (DW_AT_LOW_PC, DW_FORM_ADDR),
+                (DW_AT_HIGH_PC, DW_FORM_DATA8),
+            ],
+        ),
+        (
+            BASE_TYPE_ABBREV,
+            DW_TAG_BASE_TYPE,
+            false,
+            &[
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_ENCODING, DW_FORM_DATA1),
+                (DW_AT_BYTE_SIZE, DW_FORM_DATA1),
+            ],
+        ),
+        (
+            VARIABLE_ABBREV,
+            DW_TAG_VARIABLE,
+            false,
+            &[
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_DECL_LINE, DW_FORM_UDATA),
+                (DW_AT_TYPE, DW_FORM_REF4),
+                (DW_AT_LOCATION, DW_FORM_EXPRLOC),
+            ],
+        ),
+        (
+            VARIABLE_WITHOUT_LOCATION_ABBREV,
+            DW_TAG_VARIABLE,
+            false,
+            &[
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_DECL_LINE, DW_FORM_UDATA),
+                (DW_AT_TYPE, DW_FORM_REF4),
+            ],
+        ),
+    ];
+
+    let mut bytes = vec![];
+    for (code, tag, has_children, attributes) in abbrevs {
+        bytes.extend([code, tag, u8::from(has_children)]);
+        for (attribute, form) in attributes {
+            bytes.extend([*attribute, *form]);
+        }
+        bytes.extend([0, 0]);
+    }
+    bytes.push(0);
+
+    debug_section(SectionKind::DebugAbbrev, bytes, vec![])
+}
+
+// The compile unit of the program, with the base types and `entry` (with its variables), where the strings of the
+// compile unit are pushed to `debug_str` (the bytes of `.debug_str`).
+fn debug_info_section(debug_info: &DebugInfo, text_size: u64, entry_offset: u64, debug_str: &mut Vec<u8>) -> Section {
+    let mut relocations = vec![];
+
+    // The header (after the length), where the offset of the abbreviations is relocated.
+    let mut bytes = vec![];
+    bytes.extend(4_u16.to_le_bytes()); // Version
+    relocations.push(absolute_relocation(
+        bytes.len(),
+        RelocationKind::Absolute32,
+        ".debug_abbrev",
+        0,
+    ));
+    bytes.extend(0_u32.to_le_bytes());
+    bytes.push(8); // Address size
+
+    bytes.push(COMPILE_UNIT_ABBREV);
+    let producer = format!("solis {}", env!("CARGO_PKG_VERSION"));
+    for string in [&producer, &debug_info.file_name, &debug_info.directory] {
+        relocations.push(absolute_relocation(
+            bytes.len(),
+            RelocationKind::Absolute32,
+            ".debug_str",
+            debug_str.len() as u64,
+        ));
+        bytes.extend(0_u32.to_le_bytes());
+        push_string(debug_str, string);
+    }
+    relocations.push(absolute_relocation(
+        bytes.len(),
+        RelocationKind::Absolute32,
+        ".debug_line",
+        0,
+    ));
+    bytes.extend(0_u32.to_le_bytes());
+    relocations.push(absolute_relocation(bytes.len(), RelocationKind::Absolute64, ".text", 0));
+    bytes.extend(0_u64.to_le_bytes());
+    bytes.extend(text_size.to_le_bytes());
+
+    // The offset of each base type entry, from the start of the compile unit (including the length).
+    let mut type_offsets = vec![];
+    for (name, _, encoding, byte_size) in &BASE_TYPES {
+        type_offsets.push(u32::try_from(bytes.len() + 4).unwrap());
+        bytes.push(BASE_TYPE_ABBREV);
+        push_string(&mut bytes, name);
+        bytes.extend([*encoding, *byte_size]);
+    }
+
+    bytes.push(SUBPROGRAM_ABBREV);
+    push_string(&mut bytes, "entry");
+    relocations.push(absolute_relocation(
+        bytes.len(),
+        RelocationKind::Absolute64,
+        ".text",
+        entry_offset,
+    ));
+    bytes.extend(0_u64.to_le_bytes());
+    bytes.extend((text_size - entry_offset).to_le_bytes());
+
+    for variable in &debug_info.variables {
+        let location = location(&variable.assignment);
+        bytes.push(if location.is_some() { VARIABLE_ABBREV } else { VARIABLE_WITHOUT_LOCATION_ABBREV });
+        push_string(&mut bytes, &variable.name);
+        push_uleb128(&mut bytes, variable.line.unwrap_or(0) as u64);
+
+        let type_index = BASE_TYPES
+            .iter()
+            .position(|(_, base_type, ..)| *base_type == variable.variable_type)
+            .unwrap();
+        bytes.extend(type_offsets[type_index].to_le_bytes());
+
+        if let Some(location) = location {
+            push_uleb128(&mut bytes, location.len() as u64);
+            bytes.extend(location);
+        }
+    }
+    bytes.push(0); // The end of the children of `entry`
+    bytes.push(0); // The end of the children of the compile unit
+
+    // The length is before the header, so each relocation is moved after it.
+    for relocation in &mut relocations {
+        relocation.offset += 4;
+    }
+    debug_section(SectionKind::DebugInfo, with_length(bytes), relocations)
+}
+
+// The line program, which has a row for each marker (by its offset in the text section), and ends after the text
+// section.
+fn debug_line_section(lines: &[(u64, usize)], text_size: u64, file_name: &str) -> Section {
+    // The header, after the header length.
+    let mut header = vec![
+        1,   // Minimum instruction length
+        1,   // Maximum operations per instruction
+        1,   // Default `is_stmt`
+        251, // Line base (-5)
+        14,  // Line range
+        13,  // Opcode base
+    ];
+    header.extend([0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1]); // The number of operands of each standard opcode
+    header.push(0); // No include directories
+    push_string(&mut header, file_name);
+    header.extend([0, 0, 0]); // The directory, modification time and length of the file
+    header.push(0);
+
+    let mut bytes = vec![];
+    bytes.extend(4_u16.to_le_bytes()); // Version
+    bytes.extend(u32::try_from(header.len()).unwrap().to_le_bytes());
+    bytes.extend(header);
+
+    // The rows start at the start of the text section.
+    bytes.extend([0, 9, DW_LNE_SET_ADDRESS]);
+    let relocation = absolute_relocation(bytes.len() + 4, RelocationKind::Absolute64, ".text", 0);
+    bytes.extend(0_u64.to_le_bytes());
+
+    let (mut address, mut line) = (0, 1);
+    for (offset, row_line) in lines {
+        if *offset != address {
+            bytes.push(DW_LNS_ADVANCE_PC);
+            push_uleb128(&mut bytes, offset - address);
+            address = *offset;
+        }
+        if *row_line != line {
+            bytes.push(DW_LNS_ADVANCE_LINE);
+            push_sleb128(
+                &mut bytes,
+                i64::try_from(*row_line).unwrap() - i64::try_from(line).unwrap(),
+            );
+            line = *row_line;
+        }
+        bytes.push(DW_LNS_COPY);
+    }
+
+    if text_size != address {
+        bytes.push(DW_LNS_ADVANCE_PC);
+        push_uleb128(&mut bytes, text_size - address);
+    }
+    bytes.extend([0, 1, DW_LNE_END_SEQUENCE]);
+
+    debug_section(SectionKind::DebugLine, with_length(bytes), vec![relocation])
+}
+
+// The location expression of a variable, or None if the variable is not stored.
+fn location(assignment: &Assignment) -> Option<Vec<u8>> {
+    let register_location = |number: u8| {
+        if number < 32 {
+            vec![DW_OP_REG0 + number]
+        } else {
+            vec![DW_OP_REGX, number]
+        }
+    };
+
+    match assignment {
+        Assignment::Register(register) => Some(register_location(register_number(*register))),
+        Assignment::FloatRegister(register) => Some(register_location(17 + *register as u8)),
+        Assignment::Spill(slot) => {
+            let mut location = vec![DW_OP_BREG7];
+            push_sleb128(&mut location, 8 * i64::try_from(*slot).unwrap());
+            Some(location)
+        }
+        Assignment::None => None,
+    }
+}
+
+// The DWARF number of a register (see the System V ABI for x86-64), which is different from the encoding.
+const fn register_number(register: Register) -> u8 {
+    match register {
+        Register::Rax => 0,
+        Register::Rdx => 1,
+        Register::Rcx => 2,
+        Register::Rbx => 3,
+        Register::Rsi => 4,
+        Register::Rdi => 5,
+        Register::Rbp => 6,
+        Register::Rsp => 7,
+        Register::R8 => 8,
+        Register::R9 => 9,
+        Register::R10 => 10,
+        Register::R11 => 11,
+        Register::R12 => 12,
+        Register::R13 => 13,
+        Register::R14 => 14,
+        Register::R15 => 15,
+    }
+}
+
+// A debug section, which isn't loaded into memory.
+const fn debug_section(kind: SectionKind, bytes: Vec<u8>, relocations: Vec<Relocation>) -> Section {
+    Section { kind, bytes, alignment: 1, relocations }
+}
+
+// A relocation to the start of a section (plus an offset).
+fn absolute_relocation(offset: usize, kind: RelocationKind, section: &str, addend: u64) -> Relocation {
+    Relocation {
+        offset: offset as u64,
+        symbol: section.to_string(),
+        kind,
+        addend: i64::try_from(addend).unwrap(),
+    }
+}
+
+// Prefixes the bytes of a unit with its (32 bit) length.
+fn with_length(bytes: Vec<u8>) -> Vec<u8> {
+    let mut unit = u32::try_from(bytes.len()).unwrap().to_le_bytes().to_vec();
+    unit.extend(bytes);
+    unit
+}
+
+// Pushes a null terminated string.
+fn push_string(bytes: &mut Vec<u8>, string: &str) {
+    bytes.extend(string.bytes());
+    bytes.push(0);
+}
+
+// Pushes an unsigned LEB128 number, which is 7 bits per byte, where the high bit is set on every byte but the last.
+fn push_uleb128(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+// Pushes a signed LEB128 number, which ends once the rest of the bits are the sign bit of the last byte.
+fn push_sleb128(bytes: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = u8::try_from(value & 0x7f).unwrap();
+        value >>= 7;
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
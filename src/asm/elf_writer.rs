@@ -0,0 +1,293 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Writes the `Object` of the built-in assembler (see `encoder.rs`) as an ELF64 relocatable object file for x86-64.
+//!
+//! The object file is linked like the object files of an external assembler (see `bootstrapper.rs`).
+//!
+//! The object file has, in order:
+//!   - the sections of the object (`.text`, `.data` and `.rodata`, and the debug sections with `-g`).
+//!   - a relocation section (like `.rela.text`) for each section with relocations.
+//!   - an empty `.note.GNU-stack` section, which marks the stack as non executable.
+//!   - the symbol table (`.symtab`) and the names of its symbols (`.strtab`). Like every ELF symbol table, local symbols
+//!     (a symbol for each section, and labels that are not `Global`) are before the global symbols. Relocations to the
+//!     start of a section (like in `.debug_info`) are relative to the symbol of the section.
+//!   - the names of the sections (`.shstrtab`).
+
+use asm::encoder::{Object, RelocationKind, SectionKind};
+use error_messages::internal_compiler_error;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+
+// Constants of the ELF format (see `elf.h`).
+const ELF_HEADER_SIZE: u16 = 64;
+const SECTION_HEADER_SIZE: u16 = 64;
+const SYMBOL_SIZE: u64 = 24;
+const RELOCATION_SIZE: u64 = 24;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STT_NOTYPE: u8 = 0;
+const STT_SECTION: u8 = 3;
+const SHN_UNDEF: u16 = 0;
+
+const R_X86_64_64: u64 = 1;
+const R_X86_64_PC32: u64 = 2;
+const R_X86_64_PLT32: u64 = 4;
+const R_X86_64_32: u64 = 10;
+
+// A section of the object file, before the offset of its contents is known.
+struct SectionHeader {
+    name: u32,
+    kind: u32,
+    flags: u64,
+    link: u32,
+    info: u32,
+    alignment: u64,
+    entry_size: u64,
+    contents: Vec<u8>,
+}
+
+// The names of a string table, which are referenced by their offset.
+struct StringTable {
+    bytes: Vec<u8>,
+}
+
+impl StringTable {
+    fn new() -> Self {
+        Self { bytes: vec![0] }
+    }
+
+    // Adds a name to the table.
+    // * return - the offset of the name.
+    fn add(&mut self, name: &str) -> u32 {
+        let offset = u32::try_from(self.bytes.len()).unwrap();
+        self.bytes.extend(name.bytes());
+        self.bytes.push(0);
+        offset
+    }
+}
+
+/// Writes an object to an ELF object file.
+pub fn write_object_to_file(object: &Object, file_path: &Path) {
+    create_dir_all(file_path.parent().unwrap()).unwrap();
+
+    write(file_path, elf_bytes(object))
+        .unwrap_or_else(|error| internal_compiler_error(&format!("unable to write to file {error}")));
+}
+
+/// Converts an object to the bytes of an ELF object file.
+pub fn elf_bytes(object: &Object) -> Vec<u8> {
+    let mut section_names = StringTable::new();
+    let mut symbol_names = StringTable::new();
+
+    // The index of the section header of each section of the object, after the null section header.
+    let section_indices: HashMap<SectionKind, u16> = object
+        .sections
+        .iter()
+        .enumerate()
+        .map(|(index, section)| (section.kind, u16::try_from(index + 1).unwrap()))
+        .collect();
+
+    let relocated_sections: Vec<_> = object
+        .sections
+        .iter()
+        .filter(|section| !section.relocations.is_empty())
+        .collect();
+    let symbol_table_index = u32::try_from(1 + object.sections.len() + relocated_sections.len() + 1).unwrap();
+
+    // The symbol table, starting with the null symbol and the symbol of each section.
+    let mut symbols = vec![0; usize::try_from(SYMBOL_SIZE).unwrap()];
+    let mut symbol_indices: HashMap<&str, u64> = HashMap::new();
+    let mut symbol_count = 1;
+
+    for section in &object.sections {
+        symbols.extend(symbol(0, STB_LOCAL, STT_SECTION, section_indices[&section.kind], 0));
+        symbol_indices.insert(section.kind.name(), u64::try_from(symbol_count).unwrap());
+        symbol_count += 1;
+    }
+
+    let (global_symbols, local_symbols): (Vec<_>, Vec<_>) = object.symbols.iter().partition(|symbol| symbol.is_global);
+    let first_global_index = symbol_count + local_symbols.len();
+
+    for object_symbol in local_symbols.into_iter().chain(global_symbols) {
+        let (section_index, value) = match object_symbol.definition {
+            Some((kind, offset)) => (section_indices[&kind], offset),
+            None => (SHN_UNDEF, 0),
+        };
+        let binding = if object_symbol.is_global { STB_GLOBAL } else { STB_LOCAL };
+
+        symbols.extend(symbol(
+            symbol_names.add(&object_symbol.name),
+            binding,
+            STT_NOTYPE,
+            section_index,
+            value,
+        ));
+        symbol_indices.insert(&object_symbol.name, u64::try_from(symbol_count).unwrap());
+        symbol_count += 1;
+    }
+
+    // The section headers, in order.
+    let mut headers = vec![];
+    for section in &object.sections {
+        let flags = match section.kind {
+            SectionKind::Text => SHF_ALLOC | SHF_EXECINSTR,
+            SectionKind::Data => SHF_ALLOC | SHF_WRITE,
+            SectionKind::Rodata => SHF_ALLOC,
+            SectionKind::DebugAbbrev | SectionKind::DebugInfo | SectionKind::DebugLine | SectionKind::DebugStr => 0,
+        };
+        headers.push(SectionHeader {
+            name: section_names.add(section.kind.name()),
+            kind: SHT_PROGBITS,
+            flags,
+            link: 0,
+            info: 0,
+            alignment: section.alignment,
+            entry_size: 0,
+            contents: section.bytes.clone(),
+        });
+    }
+
+    for section in relocated_sections {
+        let mut contents = vec![];
+        for relocation in &section.relocations {
+            let kind = match relocation.kind {
+                RelocationKind::Pc32 => R_X86_64_PC32,
+                RelocationKind::Plt32 => R_X86_64_PLT32,
+                RelocationKind::Absolute64 => R_X86_64_64,
+                RelocationKind::Absolute32 => R_X86_64_32,
+            };
+            contents.extend(relocation.offset.to_le_bytes());
+            contents.extend((symbol_indices[relocation.symbol.as_str()] << 32 | kind).to_le_bytes());
+            contents.extend(relocation.addend.to_le_bytes());
+        }
+
+        headers.push(SectionHeader {
+            name: section_names.add(&format!(".rela{}", section.kind.name())),
+            kind: SHT_RELA,
+            flags: SHF_INFO_LINK,
+            link: symbol_table_index,
+            info: u32::from(section_indices[&section.kind]),
+            alignment: 8,
+            entry_size: RELOCATION_SIZE,
+            contents,
+        });
+    }
+
+    headers.push(SectionHeader {
+        name: section_names.add(".note.GNU-stack"),
+        kind: SHT_PROGBITS,
+        flags: 0,
+        link: 0,
+        info: 0,
+        alignment: 1,
+        entry_size: 0,
+        contents: vec![],
+    });
+    headers.push(SectionHeader {
+        name: section_names.add(".symtab"),
+        kind: SHT_SYMTAB,
+        flags: 0,
+        link: symbol_table_index + 1,
+        info: u32::try_from(first_global_index).unwrap(),
+        alignment: 8,
+        entry_size: SYMBOL_SIZE,
+        contents: symbols,
+    });
+    headers.push(SectionHeader {
+        name: section_names.add(".strtab"),
+        kind: SHT_STRTAB,
+        flags: 0,
+        link: 0,
+        info: 0,
+        alignment: 1,
+        entry_size: 0,
+        contents: symbol_names.bytes,
+    });
+    headers.push(SectionHeader {
+        name: section_names.add(".shstrtab"),
+        kind: SHT_STRTAB,
+        flags: 0,
+        link: 0,
+        info: 0,
+        alignment: 1,
+        entry_size: 0,
+        contents: vec![],
+    });
+    let section_names_index = headers.len();
+    headers.last_mut().unwrap().contents = section_names.bytes;
+
+    // The contents of each section, after the ELF header.
+    let mut bytes = vec![0; usize::from(ELF_HEADER_SIZE)];
+    let mut offsets = vec![];
+    for header in &headers {
+        align(&mut bytes, header.alignment);
+        offsets.push(bytes.len() as u64);
+        bytes.extend(&header.contents);
+    }
+
+    // The section headers, starting with the null section header.
+    align(&mut bytes, 8);
+    let section_headers_offset = bytes.len() as u64;
+    bytes.extend([0; SECTION_HEADER_SIZE as usize]);
+    for (header, offset) in headers.iter().zip(offsets) {
+        bytes.extend(header.name.to_le_bytes());
+        bytes.extend(header.kind.to_le_bytes());
+        bytes.extend(header.flags.to_le_bytes());
+        bytes.extend(0_u64.to_le_bytes()); // Address
+        bytes.extend(offset.to_le_bytes());
+        bytes.extend((header.contents.len() as u64).to_le_bytes());
+        bytes.extend(header.link.to_le_bytes());
+        bytes.extend(header.info.to_le_bytes());
+        bytes.extend(header.alignment.to_le_bytes());
+        bytes.extend(header.entry_size.to_le_bytes());
+    }
+
+    let mut elf_header = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]; // 64 bit, little endian
+    elf_header.extend(1_u16.to_le_bytes()); // Relocatable file
+    elf_header.extend(62_u16.to_le_bytes()); // x86-64
+    elf_header.extend(1_u32.to_le_bytes()); // Version
+    elf_header.extend(0_u64.to_le_bytes()); // Entry point
+    elf_header.extend(0_u64.to_le_bytes()); // Program headers
+    elf_header.extend(section_headers_offset.to_le_bytes());
+    elf_header.extend(0_u32.to_le_bytes()); // Flags
+    elf_header.extend(ELF_HEADER_SIZE.to_le_bytes());
+    elf_header.extend(0_u16.to_le_bytes()); // Program header size
+    elf_header.extend(0_u16.to_le_bytes()); // Program header count
+    elf_header.extend(SECTION_HEADER_SIZE.to_le_bytes());
+    elf_header.extend(u16::try_from(headers.len() + 1).unwrap().to_le_bytes());
+    elf_header.extend(u16::try_from(section_names_index).unwrap().to_le_bytes());
+
+    bytes[..usize::from(ELF_HEADER_SIZE)].copy_from_slice(&elf_header);
+    bytes
+}
+
+// An entry of the symbol table.
+fn symbol(name: u32, binding: u8, kind: u8, section_index: u16, value: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+    bytes.extend(name.to_le_bytes());
+    bytes.push(binding << 4 | kind);
+    bytes.push(0); // Visibility
+    bytes.extend(section_index.to_le_bytes());
+    bytes.extend(value.to_le_bytes());
+    bytes.extend(0_u64.to_le_bytes()); // Size
+    bytes
+}
+
+// Pads the bytes with zeros to a multiple of the alignment.
+fn align(bytes: &mut Vec<u8>, alignment: u64) {
+    let alignment = usize::try_from(alignment).unwrap();
+    bytes.resize((bytes.len() + alignment - 1) / alignment * alignment, 0);
+}
@@ -0,0 +1,714 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! An emulator for the in memory representation of assembly (see `asm.rs`), which executes instructions directly
+//! without an assembler, linker, or process.
+//!
+//! It is used to test the output of the compiler at the instruction level.
+//!
+//! The emulator models the general purpose registers, the (lower 64 bits of the) xmm registers, the flags, a stack, and
+//! labels. To catch code generation bugs precisely, the emulator is stricter than the hardware:
+//!   - the operands of each instruction are validated against the allowed combinations that are annotated in `asm.rs`
+//!     (like `mov` with two `MemOffset`s, or immediates that don't fit in 32 bits).
+//!   - registers, flags, and memory are tracked as undefined until they are written to, and reading an undefined value
+//!     is an error. Registers that are callee saved in the System V ABI start with arbitrary (defined) values, since
+//!     they belong to the caller, and must have the same values after returning from `entry`.
+//!   - instructions that are not modeled (like data directives) are an error. The data of a `dq` can only be read, as
+//!     the memory operand of the label before it (like the constant pool of the compiler).
+//!
+//! Labels are given "addresses" (for `lea` and computed jumps) after `CODE_BASE`, based on their index.
+
+use asm::asm::{FloatRegister, Instruction, Operand, Register};
+use asm::asm_writer::instruction_to_string;
+use register_allocation::register_allocator::Map;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+// The address of the first instruction. Each instruction has an address of `CODE_BASE + index`.
+const CODE_BASE: i64 = 0x1000;
+
+// The return address of the label that is run, where returning to it halts the emulator.
+const HALT_ADDRESS: i64 = 0;
+
+// The initial value of the stack pointer.
+const STACK_BASE: i64 = 0x7fff_0000;
+
+// The maximum number of instructions to execute, to detect infinite loops.
+const MAX_STEPS: usize = 10_000_000;
+
+// Registers that are callee saved in the System V ABI, which start as defined.
+const CALLEE_SAVED_REGISTERS: [Register; 6] = [
+    Register::Rbx,
+    Register::Rbp,
+    Register::R12,
+    Register::R13,
+    Register::R14,
+    Register::R15,
+];
+
+/// Errors that the emulator detects while executing instructions.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EmulatorError {
+    /// The operands of the instruction are not an allowed combination.
+    InvalidOperands(String),
+
+    /// The instruction is not supported by the emulator.
+    Unsupported(String),
+
+    /// A jump, call, or lea to a label that doesn't exist.
+    UnknownLabel(String),
+
+    /// A jump (or return) to an address that is not an instruction.
+    InvalidJump(i64),
+
+    /// Reading a register before it was written to.
+    UndefinedRegister(Register),
+
+    /// Reading a float register before it was written to.
+    UndefinedFloatRegister(FloatRegister),
+
+    /// Reading memory (at an address) before it was written to.
+    UndefinedMemory(i64),
+
+    /// Reading flags that are undefined, either because no instruction has set them or the last instruction that
+    /// modified them left them undefined (like `imul`).
+    UndefinedFlags,
+
+    /// `idiv` by zero, or where the quotient overflows.
+    DivideError,
+
+    /// More than `MAX_STEPS` instructions were executed.
+    StepLimitExceeded,
+
+    /// A callee saved register (or the stack pointer) has a different value after returning from `entry`, which
+    /// breaks the System V ABI.
+    ClobberedRegister(Register),
+}
+
+/// The flags that are used by conditional instructions.
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+struct Flags {
+    zero: bool,
+    sign: bool,
+    overflow: bool,
+    carry: bool,
+    parity: bool,
+}
+
+impl Flags {
+    // Flags for the result of an operation, where overflow and carry are given.
+    const fn from_result(result: i64, overflow: bool, carry: bool) -> Self {
+        Self {
+            zero: result == 0,
+            sign: result < 0,
+            overflow,
+            carry,
+            // Set if the lowest byte of the result has an even number of bits set.
+            parity: result.to_le_bytes()[0].count_ones() % 2 == 0,
+        }
+    }
+}
+
+/// Emulates the instructions of a program from `entry` (the output of the compiler), which must restore the callee
+/// saved registers.
+/// * return - the value of `rax` after returning from `entry`.
+///
+/// # Errors
+/// Returns a `EmulatorError` if the instructions are invalid or if a runtime error occurs.
+pub fn emulate(instructions: &[Instruction]) -> Result<i64, EmulatorError> {
+    let mut emulator = Emulator::new(instructions);
+    emulator.run("entry")?;
+
+    let initial_emulator = Emulator::new(&[]);
+    for register in CALLEE_SAVED_REGISTERS.iter().chain(&[Register::Rsp]) {
+        if emulator.register(*register) != initial_emulator.register(*register) {
+            return Err(EmulatorError::ClobberedRegister(*register));
+        }
+    }
+
+    emulator
+        .register(Register::Rax)
+        .ok_or(EmulatorError::UndefinedRegister(Register::Rax))
+}
+
+/// The state of the machine while emulating instructions.
+pub struct Emulator<'a> {
+    instructions: &'a [Instruction],
+    labels: Map<&'a str, usize>,
+
+    registers: [Option<i64>; 16],
+    float_registers: [Option<u64>; 16],
+    flags: Option<Flags>,
+
+    // Memory that has been written to, by byte.
+    memory: Map<i64, u8>,
+
+    // Index of the instruction that is executing, for error messages.
+    current_instruction: usize,
+}
+
+impl<'a> Emulator<'a> {
+    /// `Emulator` constructor.
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        let mut labels = Map::new();
+        for (i, instruction) in instructions.iter().enumerate() {
+            if let Instruction::Label(label) = instruction {
+                labels.insert(label.as_str(), i);
+            }
+        }
+
+        let mut registers = [None; 16];
+        registers[Register::Rsp as usize] = Some(STACK_BASE);
+
+        for (i, register) in CALLEE_SAVED_REGISTERS.iter().enumerate() {
+            registers[*register as usize] = Some(0x5a5a_0000 + i64::try_from(i).unwrap_or_default());
+        }
+
+        Emulator {
+            instructions,
+            labels,
+            registers,
+            float_registers: [None; 16],
+            flags: None,
+            memory: Map::new(),
+            current_instruction: 0,
+        }
+    }
+
+    /// The value of a register, if it is defined.
+    pub const fn register(&self, register: Register) -> Option<i64> {
+        self.registers[register as usize]
+    }
+
+    /// The value of (the lower 64 bits of) a float register, if it is defined.
+    pub fn float_register(&self, register: FloatRegister) -> Option<f64> {
+        self.float_registers[register as usize].map(f64::from_bits)
+    }
+
+    /// Runs the instructions from `label`, as if it was called, until it returns.
+    ///
+    /// # Errors
+    /// Returns a `EmulatorError` if the instructions are invalid or if a runtime error occurs.
+    pub fn run(&mut self, label: &str) -> Result<(), EmulatorError> {
+        let mut index = self.label_index(label)?;
+        self.push(HALT_ADDRESS)?;
+
+        for _ in 0..MAX_STEPS {
+            if index >= self.instructions.len() {
+                return Err(EmulatorError::InvalidJump(to_address(index)));
+            }
+
+            let instructions = self.instructions;
+            self.current_instruction = index;
+
+            match self.execute(&instructions[index])? {
+                None => index += 1,
+                Some(HALT_ADDRESS) => return Ok(()),
+                Some(address) => index = self.address_index(address)?,
+            }
+        }
+
+        Err(EmulatorError::StepLimitExceeded)
+    }
+
+    // Executes a single instruction.
+    // * return - the address to jump to, if the instruction changes the control flow.
+    fn execute(&mut self, instruction: &'a Instruction) -> Result<Option<i64>, EmulatorError> {
+        match instruction {
+            Instruction::Global(..)
+            | Instruction::Extern(..)
+            | Instruction::Section(..)
+            | Instruction::Label(..)
+            | Instruction::SourceFile(..)
+            | Instruction::Line(..)
+            | Instruction::Comment(..) => (),
+            Instruction::Annotate(instruction, _) => return self.execute(instruction),
+
+            Instruction::DqLabel(..) | Instruction::DqString(..) | Instruction::DqInt(..) | Instruction::Align(..) => {
+                return Err(self.unsupported())
+            }
+
+            Instruction::LeaLabel(dest, label) => {
+                self.validate(is_reg(dest))?;
+                let address = to_address(self.label_index(label)?);
+                self.write(dest, address)?;
+            }
+            Instruction::Mov(dest, src) => {
+                self.validate(
+                    (is_reg(dest) || is_mem(dest))
+                        && (is_reg(src) || is_mem(src) || is_imm(src) || (is_float_imm(src) && is_reg(dest)))
+                        && !(is_mem(dest) && (is_mem(src) || (is_imm(src) && !is_imm32(src)))),
+                )?;
+                let value = self.read(src)?;
+                self.write(dest, value)?;
+            }
+            Instruction::MovByte(dest, src) => {
+                self.validate(is_rm_rmi(dest, src))?;
+                let value = self.read_byte(src)?;
+                self.write_byte(dest, value)?;
+            }
+            Instruction::Add(dest, src) | Instruction::Sub(dest, src) => {
+                self.validate(is_rm_rmi(dest, src))?;
+                let (value_1, value_2) = (self.read(dest)?, self.read(src)?);
+
+                let (result, flags) = if let Instruction::Add(..) = instruction {
+                    add(value_1, value_2)
+                } else {
+                    subtract(value_1, value_2)
+                };
+                self.write(dest, result)?;
+                self.flags = Some(flags);
+            }
+            Instruction::Cmp(dest, src) => {
+                self.validate(is_rm_rmi(dest, src))?;
+                let (_, flags) = subtract(self.read(dest)?, self.read(src)?);
+                self.flags = Some(flags);
+            }
+            Instruction::And(dest, src) | Instruction::Or(dest, src) => {
+                self.validate(is_rm_rmi(dest, src))?;
+                let (value_1, value_2) = (self.read(dest)?, self.read(src)?);
+
+                let result = if let Instruction::And(..) = instruction { value_1 & value_2 } else { value_1 | value_2 };
+                self.write(dest, result)?;
+                self.flags = Some(Flags::from_result(result, false, false));
+            }
+            Instruction::Mul(dest, src) => {
+                self.validate(is_reg(dest) && (is_reg(src) || is_mem(src) || is_imm32(src)))?;
+                let result = self.read(dest)?.wrapping_mul(self.read(src)?);
+                self.write(dest, result)?;
+                self.flags = None;
+            }
+            Instruction::Mul3(dest, src, constant) => {
+                self.validate(is_reg(dest) && (is_reg(src) || is_mem(src)) && is_imm32(constant))?;
+                let result = self.read(src)?.wrapping_mul(self.read(constant)?);
+                self.write(dest, result)?;
+                self.flags = None;
+            }
+            Instruction::Mul1(src) => {
+                self.validate(is_reg(src) || is_mem(src))?;
+                let product = i128::from(self.read(&Operand::Reg(Register::Rax))?) * i128::from(self.read(src)?);
+
+                let high = i64::try_from(product >> 64).unwrap_or_default();
+                let low = to_signed(u64::try_from(product & i128::from(u64::MAX)).unwrap_or_default());
+                self.write(&Operand::Reg(Register::Rax), low)?;
+                self.write(&Operand::Reg(Register::Rdx), high)?;
+                self.flags = None;
+            }
+            Instruction::Div(src) => {
+                self.validate(is_reg(src) || is_mem(src))?;
+                let divisor = i128::from(self.read(src)?);
+                let dividend = (i128::from(self.read(&Operand::Reg(Register::Rdx))?) << 64)
+                    | i128::from(to_unsigned(self.read(&Operand::Reg(Register::Rax))?));
+
+                if divisor == 0 {
+                    return Err(EmulatorError::DivideError);
+                }
+                let quotient = i64::try_from(dividend / divisor).map_err(|_| EmulatorError::DivideError)?;
+                let remainder = i64::try_from(dividend % divisor).map_err(|_| EmulatorError::DivideError)?;
+
+                self.write(&Operand::Reg(Register::Rax), quotient)?;
+                self.write(&Operand::Reg(Register::Rdx), remainder)?;
+                self.flags = None;
+            }
+            Instruction::Neg(operand) => {
+                self.validate(is_reg(operand) || is_mem(operand))?;
+                let (result, flags) = subtract(0, self.read(operand)?);
+                self.write(operand, result)?;
+                self.flags = Some(flags);
+            }
+            Instruction::Cqo => {
+                let rax = self.read(&Operand::Reg(Register::Rax))?;
+                self.write(&Operand::Reg(Register::Rdx), if rax < 0 { -1 } else { 0 })?;
+            }
+            Instruction::Shl(dest, src) | Instruction::Shr(dest, src) | Instruction::Sar(dest, src) => {
+                self.validate(
+                    (is_reg(dest) || is_mem(dest))
+                        && (matches!(src, Operand::Imm(0..=255)) || matches!(src, Operand::Reg(Register::Rcx))),
+                )?;
+                let value = self.read(dest)?;
+                let count = u32::try_from(self.read(src)? & 63).unwrap_or_default();
+
+                if count != 0 {
+                    // The carry is the last bit that was shifted out.
+                    let (result, carry) = match instruction {
+                        Instruction::Shl(..) => (value << count, (to_unsigned(value) >> (64 - count)) & 1 == 1),
+                        Instruction::Shr(..) => {
+                            (to_signed(to_unsigned(value) >> count), (value >> (count - 1)) & 1 == 1)
+                        }
+                        _ => (value >> count, (value >> (count - 1)) & 1 == 1),
+                    };
+                    self.write(dest, result)?;
+                    self.flags = Some(Flags::from_result(result, false, carry));
+                }
+            }
+            Instruction::Setz(dest) | Instruction::Setnz(dest) | Instruction::Setl(dest) | Instruction::Setle(dest) => {
+                self.validate(is_reg(dest) || is_mem(dest))?;
+                let flags = self.flags.ok_or(EmulatorError::UndefinedFlags)?;
+
+                let condition = match instruction {
+                    Instruction::Setz(..) => flags.zero,
+                    Instruction::Setnz(..) => !flags.zero,
+                    Instruction::Setl(..) => flags.sign != flags.overflow,
+                    _ => flags.zero || flags.sign != flags.overflow,
+                };
+                self.write_byte(dest, u8::from(condition))?;
+            }
+            Instruction::Jmp(label) => return Ok(Some(to_address(self.label_index(label)?))),
+            Instruction::Je(label)
+            | Instruction::Jne(label)
+            | Instruction::Jl(label)
+            | Instruction::Jnl(label)
+            | Instruction::Jg(label)
+            | Instruction::Jng(label)
+            | Instruction::Ja(label)
+            | Instruction::Jae(label)
+            | Instruction::Jb(label)
+            | Instruction::Jbe(label)
+            | Instruction::Jp(label) => {
+                let flags = self.flags.ok_or(EmulatorError::UndefinedFlags)?;
+
+                let condition = match instruction {
+                    Instruction::Je(..) => flags.zero,
+                    Instruction::Jne(..) => !flags.zero,
+                    Instruction::Jl(..) => flags.sign != flags.overflow,
+                    Instruction::Jnl(..) => flags.sign == flags.overflow,
+                    Instruction::Jg(..) => !flags.zero && flags.sign == flags.overflow,
+                    Instruction::Ja(..) => !flags.carry && !flags.zero,
+                    Instruction::Jae(..) => !flags.carry,
+                    Instruction::Jb(..) => flags.carry,
+                    Instruction::Jbe(..) => flags.carry || flags.zero,
+                    Instruction::Jp(..) => flags.parity,
+                    _ => flags.zero || flags.sign != flags.overflow,
+                };
+
+                if condition {
+                    return Ok(Some(to_address(self.label_index(label)?)));
+                }
+            }
+            Instruction::ComputedJmp(dest) => {
+                self.validate(is_reg(dest) || is_mem(dest))?;
+                return Ok(Some(self.read(dest)?));
+            }
+            Instruction::Push(src) => {
+                self.validate(is_reg(src) || is_mem(src) || is_imm32(src))?;
+                let value = self.read(src)?;
+                self.push(value)?;
+            }
+            Instruction::Pop(dest) => {
+                self.validate(is_reg(dest) || is_mem(dest))?;
+                let value = self.pop()?;
+                self.write(dest, value)?;
+            }
+            Instruction::Call(label) => {
+                let address = to_address(self.label_index(label)?);
+                self.push(to_address(self.current_instruction + 1))?;
+                return Ok(Some(address));
+            }
+            Instruction::Ret => return Ok(Some(self.pop()?)),
+
+            Instruction::Movq(dest, src) => {
+                self.validate(
+                    (is_xmm(dest) && (is_reg(src) || is_mem(src) || is_xmm(src)))
+                        || ((is_reg(dest) || is_mem(dest)) && is_xmm(src)),
+                )?;
+                let value = self.read(src)?;
+                self.write(dest, value)?;
+            }
+            Instruction::Cvttsd2si(dest, src) => {
+                self.validate(is_reg(dest) && (is_xmm(src) || is_mem(src)))?;
+                let value = f64::from_bits(to_unsigned(self.read(src)?));
+
+                // Values that can't be converted become the "integer indefinite" value.
+                #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+                let result = if value.is_nan() || value >= i64::MAX as f64 || value < i64::MIN as f64 {
+                    i64::MIN
+                } else {
+                    value.trunc() as i64
+                };
+                self.write(dest, result)?;
+            }
+            Instruction::Cvtsi2sd(dest, src) => {
+                self.validate(is_xmm(dest) && (is_reg(src) || is_mem(src)))?;
+
+                #[allow(clippy::cast_precision_loss)]
+                let result = self.read(src)? as f64;
+                self.write(dest, to_signed(result.to_bits()))?;
+            }
+            Instruction::Xorpd(dest, src) => {
+                self.validate(is_xmm(dest) && (is_xmm(src) || is_mem(src)))?;
+
+                // Xor of a register with itself is zero, even if the register is undefined.
+                let result = if let (Operand::FloatReg(dest_register), Operand::FloatReg(src_register)) = (dest, src) {
+                    if dest_register == src_register {
+                        0
+                    } else {
+                        self.read(dest)? ^ self.read(src)?
+                    }
+                } else {
+                    self.read(dest)? ^ self.read(src)?
+                };
+                self.write(dest, result)?;
+            }
+            Instruction::Addsd(dest, src)
+            | Instruction::Subsd(dest, src)
+            | Instruction::Mulsd(dest, src)
+            | Instruction::Divsd(dest, src) => {
+                self.validate(is_xmm(dest) && (is_xmm(src) || is_mem(src)))?;
+                let value_1 = f64::from_bits(to_unsigned(self.read(dest)?));
+                let value_2 = f64::from_bits(to_unsigned(self.read(src)?));
+
+                let result = match instruction {
+                    Instruction::Addsd(..) => value_1 + value_2,
+                    Instruction::Subsd(..) => value_1 - value_2,
+                    Instruction::Mulsd(..) => value_1 * value_2,
+                    _ => value_1 / value_2,
+                };
+                self.write(dest, to_signed(result.to_bits()))?;
+            }
+            Instruction::Cmpsd(dest, src, predicate) => {
+                self.validate(is_xmm(dest) && (is_xmm(src) || is_mem(src)) && *predicate < 8)?;
+                let value_1 = f64::from_bits(to_unsigned(self.read(dest)?));
+                let value_2 = f64::from_bits(to_unsigned(self.read(src)?));
+
+                let ordering = value_1.partial_cmp(&value_2);
+                let result = match predicate {
+                    0 => ordering == Some(Ordering::Equal),
+                    1 => ordering == Some(Ordering::Less),
+                    2 => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    3 => ordering.is_none(),
+                    4 => ordering != Some(Ordering::Equal),
+                    5 => ordering != Some(Ordering::Less),
+                    6 => !matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    _ => ordering.is_some(),
+                };
+                self.write(dest, if result { -1 } else { 0 })?;
+            }
+            Instruction::Ucomisd(dest, src) => {
+                self.validate(is_xmm(dest) && (is_xmm(src) || is_mem(src)))?;
+                let value_1 = f64::from_bits(to_unsigned(self.read(dest)?));
+                let value_2 = f64::from_bits(to_unsigned(self.read(src)?));
+
+                // Unordered operands (NaN) set the zero, parity, and carry flags.
+                let ordering = value_1.partial_cmp(&value_2);
+                self.flags = Some(Flags {
+                    zero: matches!(ordering, None | Some(Ordering::Equal)),
+                    sign: false,
+                    overflow: false,
+                    carry: matches!(ordering, None | Some(Ordering::Less)),
+                    parity: ordering.is_none(),
+                });
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Reads the 64 bit value of an operand.
+    fn read(&self, operand: &Operand) -> Result<i64, EmulatorError> {
+        match operand {
+            Operand::Reg(register) => {
+                self.registers[*register as usize].ok_or(EmulatorError::UndefinedRegister(*register))
+            }
+            Operand::FloatReg(register) => self.float_registers[*register as usize]
+                .map(to_signed)
+                .ok_or(EmulatorError::UndefinedFloatRegister(*register)),
+            Operand::Imm(value) => Ok(*value),
+            Operand::FloatImm(value) => Ok(to_signed(value.to_bits())),
+            Operand::MemOffset(..) => {
+                let address = self.address(operand)?;
+                let mut bytes = [0; 8];
+
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    *byte = self.load_byte(address + i64::try_from(i).unwrap_or_default())?;
+                }
+                Ok(i64::from_le_bytes(bytes))
+            }
+
+            // The data of a label is a `dq` that follows it (like in the constant pool).
+            Operand::MemLabel(label) => match self.instructions.get(self.label_index(label)? + 1) {
+                Some(Instruction::DqInt(value)) => Ok(*value),
+                _ => Err(self.unsupported()),
+            },
+        }
+    }
+
+    // Writes a 64 bit value to an operand.
+    fn write(&mut self, operand: &Operand, value: i64) -> Result<(), EmulatorError> {
+        match operand {
+            Operand::Reg(register) => self.registers[*register as usize] = Some(value),
+            Operand::FloatReg(register) => self.float_registers[*register as usize] = Some(to_unsigned(value)),
+            Operand::MemOffset(..) => {
+                let address = self.address(operand)?;
+
+                for (i, byte) in value.to_le_bytes().iter().enumerate() {
+                    self.memory
+                        .insert(address + i64::try_from(i).unwrap_or_default(), *byte);
+                }
+            }
+            // Labels are read-only.
+            Operand::Imm(..) | Operand::FloatImm(..) | Operand::MemLabel(..) => return Err(self.invalid_operands()),
+        }
+        Ok(())
+    }
+
+    // Reads the least significant byte of an operand.
+    fn read_byte(&self, operand: &Operand) -> Result<u8, EmulatorError> {
+        match operand {
+            Operand::MemOffset(..) => self.load_byte(self.address(operand)?),
+            _ => Ok(self.read(operand)?.to_le_bytes()[0]),
+        }
+    }
+
+    // Writes the least significant byte of an operand, leaving the rest of the operand unchanged.
+    fn write_byte(&mut self, operand: &Operand, value: u8) -> Result<(), EmulatorError> {
+        if let Operand::MemOffset(..) = operand {
+            let address = self.address(operand)?;
+            self.memory.insert(address, value);
+            Ok(())
+        } else {
+            let mut bytes = self.read(operand)?.to_le_bytes();
+            bytes[0] = value;
+            self.write(operand, i64::from_le_bytes(bytes))
+        }
+    }
+
+    // Loads a byte of memory.
+    fn load_byte(&self, address: i64) -> Result<u8, EmulatorError> {
+        self.memory
+            .get(&address)
+            .copied()
+            .ok_or(EmulatorError::UndefinedMemory(address))
+    }
+
+    // Computes the address of a `MemOffset` operand.
+    fn address(&self, operand: &Operand) -> Result<i64, EmulatorError> {
+        match operand {
+            Operand::MemOffset(base, offset) if is_reg(base) && is_imm32(offset) => {
+                Ok(self.read(base)?.wrapping_add(self.read(offset)?))
+            }
+            _ => Err(self.invalid_operands()),
+        }
+    }
+
+    // Pushes a value onto the stack.
+    fn push(&mut self, value: i64) -> Result<(), EmulatorError> {
+        let rsp = self.read(&Operand::Reg(Register::Rsp))? - 8;
+        self.write(&Operand::Reg(Register::Rsp), rsp)?;
+        self.write(&stack_top(), value)
+    }
+
+    // Pops a value off of the stack.
+    fn pop(&mut self) -> Result<i64, EmulatorError> {
+        let value = self.read(&stack_top())?;
+        let rsp = self.read(&Operand::Reg(Register::Rsp))? + 8;
+        self.write(&Operand::Reg(Register::Rsp), rsp)?;
+        Ok(value)
+    }
+
+    // Gets the index of the instruction of a label.
+    fn label_index(&self, label: &str) -> Result<usize, EmulatorError> {
+        self.labels
+            .get(label)
+            .copied()
+            .ok_or_else(|| EmulatorError::UnknownLabel(label.to_string()))
+    }
+
+    // Gets the index of the instruction at an address.
+    fn address_index(&self, address: i64) -> Result<usize, EmulatorError> {
+        usize::try_from(address - CODE_BASE)
+            .ok()
+            .filter(|index| *index < self.instructions.len())
+            .ok_or(EmulatorError::InvalidJump(address))
+    }
+
+    // Ensures that the operands of the current instruction are valid.
+    fn validate(&self, is_valid: bool) -> Result<(), EmulatorError> {
+        if is_valid {
+            Ok(())
+        } else {
+            Err(self.invalid_operands())
+        }
+    }
+
+    // Error for invalid operands of the current instruction.
+    fn invalid_operands(&self) -> EmulatorError {
+        EmulatorError::InvalidOperands(self.current_instruction_string())
+    }
+
+    // Error for the current instruction being unsupported.
+    fn unsupported(&self) -> EmulatorError {
+        EmulatorError::Unsupported(self.current_instruction_string())
+    }
+
+    // The current instruction as it would be written in an assembly file.
+    fn current_instruction_string(&self) -> String {
+        instruction_to_string(self.instructions[self.current_instruction].clone())
+            .trim()
+            .to_string()
+    }
+}
+
+// The `MemOffset` of the top of the stack.
+fn stack_top() -> Operand {
+    Operand::MemOffset(Box::new(Operand::Reg(Register::Rsp)), Box::new(Operand::Imm(0)))
+}
+
+// Adds two values, returning the result and the flags.
+const fn add(value_1: i64, value_2: i64) -> (i64, Flags) {
+    let (result, overflow) = value_1.overflowing_add(value_2);
+    let (_, carry) = to_unsigned(value_1).overflowing_add(to_unsigned(value_2));
+    (result, Flags::from_result(result, overflow, carry))
+}
+
+// Subtracts two values, returning the result and the flags.
+const fn subtract(value_1: i64, value_2: i64) -> (i64, Flags) {
+    let (result, overflow) = value_1.overflowing_sub(value_2);
+    let (_, carry) = to_unsigned(value_1).overflowing_sub(to_unsigned(value_2));
+    (result, Flags::from_result(result, overflow, carry))
+}
+
+// The address of an instruction index.
+fn to_address(index: usize) -> i64 {
+    CODE_BASE + i64::try_from(index).unwrap_or(i64::MAX - CODE_BASE)
+}
+
+// Reinterprets the bits of a signed integer as unsigned.
+const fn to_unsigned(value: i64) -> u64 {
+    u64::from_ne_bytes(value.to_ne_bytes())
+}
+
+// Reinterprets the bits of a unsigned integer as signed.
+const fn to_signed(value: u64) -> i64 {
+    i64::from_ne_bytes(value.to_ne_bytes())
+}
+
+// Operand classification helpers.
+const fn is_reg(operand: &Operand) -> bool {
+    matches!(operand, Operand::Reg(..))
+}
+
+const fn is_xmm(operand: &Operand) -> bool {
+    matches!(operand, Operand::FloatReg(..))
+}
+
+const fn is_mem(operand: &Operand) -> bool {
+    matches!(operand, Operand::MemOffset(..) | Operand::MemLabel(..))
+}
+
+const fn is_imm(operand: &Operand) -> bool {
+    matches!(operand, Operand::Imm(..))
+}
+
+const fn is_float_imm(operand: &Operand) -> bool {
+    matches!(operand, Operand::FloatImm(..))
+}
+
+// Immediates that fit in 32 bits (which are sign extended to 64 bits).
+fn is_imm32(operand: &Operand) -> bool {
+    matches!(operand, Operand::Imm(value) if i32::try_from(*value).is_ok())
+}
+
+// The most common operand combination: (reg|mem, reg|mem|imm32) but not (mem, mem).
+fn is_rm_rmi(dest: &Operand, src: &Operand) -> bool {
+    (is_reg(dest) || is_mem(dest)) && (is_reg(src) || is_mem(src) || is_imm32(src)) && !(is_mem(dest) && is_mem(src))
+}
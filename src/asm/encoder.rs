@@ -0,0 +1,769 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! A built-in assembler, which encodes the in memory representation of assembly (see `asm.rs`) into x86-64 machine code
+//! without an external assembler.
+//!
+//! The result is an `Object`, which is the contents of each section, with its symbols and relocations. It is written as
+//! an ELF relocatable object file by `elf_writer.rs`.
+//!
+//! The encoder supports the instructions and operands that the compiler creates, with the same encodings as `nasm`:
+//!   - immediates use the shortest form, like an 8 bit immediate for `add rsp, 8`, and `mov r32, imm32` (which zero
+//!     extends) for moving an immediate that fits in 32 bits (unsigned) to a 64 bit register.
+//!   - memory operands use the shortest displacement, like no displacement for `[rsp + 0]`.
+//!   - jumps are short (with an 8 bit displacement) when the label is close enough. Every jump starts short, and jumps
+//!     that don't fit are made near (with a 32 bit displacement), until every jump fits.
+//!
+//! References to labels in the same section are resolved by the encoder. Other references (to labels in another
+//! section, `Extern` labels, and the addresses of `DqLabel`) are left as relocations for the linker.
+
+use asm::asm::{Instruction, Instruction::*, Operand, Operand::*, Register, Register::*};
+use asm::asm_writer::instruction_to_string;
+use error_messages::internal_compiler_error;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// The sections of an object file that instructions can be in, and the sections of the debug info (see
+/// `debug_info.rs`).
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum SectionKind {
+    Text,
+    Data,
+    Rodata,
+    DebugAbbrev,
+    DebugInfo,
+    DebugLine,
+    DebugStr,
+}
+
+/// A section of an object file.
+#[derive(Debug)]
+pub struct Section {
+    pub kind: SectionKind,
+    pub bytes: Vec<u8>,
+    pub alignment: u64,
+    pub relocations: Vec<Relocation>,
+}
+
+/// The kinds of relocations (see the System V ABI for x86-64), where `S` is the address of the symbol, `A` is the
+/// addend, and `P` is the address of the relocation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RelocationKind {
+    /// A 32 bit relative address (`S + A - P`).
+    Pc32,
+
+    /// A 32 bit relative address of the procedure linkage table entry of the symbol, for calls to `Extern` functions.
+    Plt32,
+
+    /// A 64 bit absolute address (`S + A`).
+    Absolute64,
+
+    /// A 32 bit absolute address (`S + A`), for offsets into the debug sections.
+    Absolute32,
+}
+
+/// A reference to a symbol from the contents of a section, which is filled in by the linker. The symbol is a label, or
+/// the name of a section (like `.text`) for references to the start of a section.
+#[derive(Debug)]
+pub struct Relocation {
+    pub offset: u64,
+    pub symbol: String,
+    pub kind: RelocationKind,
+    pub addend: i64,
+}
+
+/// A label of an object file.
+#[derive(Debug)]
+pub struct Symbol {
+    pub name: String,
+
+    /// The section and offset of the label, or None for labels that are defined in another object file (`Extern`).
+    pub definition: Option<(SectionKind, u64)>,
+    pub is_global: bool,
+}
+
+/// The sections and symbols of an object file, in the order that they first appear in the instructions.
+#[derive(Debug)]
+pub struct Object {
+    pub sections: Vec<Section>,
+    pub symbols: Vec<Symbol>,
+
+    /// The offset (in the text section) and the line of each `Line` marker, which are used for debug info (see
+    /// `debug_info.rs`).
+    pub lines: Vec<(u64, usize)>,
+}
+
+impl SectionKind {
+    /// The name of the section in an object file.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Text => ".text",
+            Self::Data => ".data",
+            Self::Rodata => ".rodata",
+            Self::DebugAbbrev => ".debug_abbrev",
+            Self::DebugInfo => ".debug_info",
+            Self::DebugLine => ".debug_line",
+            Self::DebugStr => ".debug_str",
+        }
+    }
+}
+
+// A reference to a label from an encoded instruction, which is either resolved by the encoder, or becomes a relocation.
+struct Fixup {
+    offset: usize,
+    label: String,
+    kind: RelocationKind,
+    addend: i64,
+}
+
+// The parts of a section, before the offset of each part is known.
+enum Item {
+    Bytes(Vec<u8>, Option<Fixup>),
+    Label(String),
+    Align(u64),
+    Line(usize),
+
+    // A jump to a label in the same section, where the condition code is None for `jmp`.
+    Jump { condition: Option<u8>, label: String, is_near: bool },
+}
+
+/// Encodes instructions into an object file, starting in the text section.
+pub fn encode(instructions: &[Instruction]) -> Object {
+    let mut sections: Vec<(SectionKind, Vec<Item>)> = vec![(SectionKind::Text, vec![])];
+    let mut current_section = 0;
+    let mut globals = vec![];
+    let mut externs = vec![];
+
+    for instruction in instructions {
+        match instruction {
+            Section(name) => {
+                let kind = match name.as_str() {
+                    "text" => SectionKind::Text,
+                    "data" => SectionKind::Data,
+                    "rodata" => SectionKind::Rodata,
+                    _ => internal_compiler_error(&format!("unable to encode section `{name}`")),
+                };
+                current_section = sections
+                    .iter()
+                    .position(|(section_kind, _)| *section_kind == kind)
+                    .unwrap_or_else(|| {
+                        sections.push((kind, vec![]));
+                        sections.len() - 1
+                    });
+            }
+            Global(label) => globals.push(label.clone()),
+            Extern(label) => externs.push(label.clone()),
+            _ => sections[current_section].1.extend(encode_instruction(instruction)),
+        }
+    }
+
+    // The offset of each item, and the section and offset of each label.
+    let mut layouts = vec![];
+    let mut labels = HashMap::new();
+    let mut lines = vec![];
+    for (kind, items) in &mut sections {
+        let offsets = layout(items);
+        for (item, offset) in items.iter().zip(&offsets) {
+            match item {
+                Item::Label(label) => {
+                    labels.insert(label.clone(), (*kind, *offset));
+                }
+                Item::Line(line) if *kind == SectionKind::Text => lines.push((*offset, *line)),
+                _ => (),
+            }
+        }
+        layouts.push(offsets);
+    }
+
+    let sections = sections
+        .into_iter()
+        .zip(layouts)
+        .map(|((kind, items), offsets)| assemble_section(kind, items, &offsets, &labels, &externs))
+        .collect();
+
+    // Symbols are in the order that they are defined, followed by the `Extern` labels.
+    let mut symbols: Vec<Symbol> = instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Label(label) => Some(label),
+            Annotate(instruction, _) => match &**instruction {
+                Label(label) => Some(label),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|label| Symbol {
+            name: label.clone(),
+            definition: Some(labels[label]),
+            is_global: globals.contains(label),
+        })
+        .collect();
+
+    symbols.extend(
+        externs
+            .into_iter()
+            .map(|label| Symbol { name: label, definition: None, is_global: true }),
+    );
+
+    Object { sections, symbols, lines }
+}
+
+// Computes the offset of each item of a section. Jumps are made near until every short jump fits.
+fn layout(items: &mut [Item]) -> Vec<u64> {
+    loop {
+        let offsets = item_offsets(items);
+        let labels: HashMap<&String, u64> = items
+            .iter()
+            .zip(&offsets)
+            .filter_map(|(item, offset)| match item {
+                Item::Label(label) => Some((label, *offset)),
+                _ => None,
+            })
+            .collect();
+
+        let mut far_jumps = vec![];
+        for (index, (item, offset)) in items.iter().zip(&offsets).enumerate() {
+            if let Item::Jump { label, is_near: false, .. } = item {
+                let target = labels.get(label).copied().unwrap_or_else(|| {
+                    internal_compiler_error(&format!("unable to encode a jump to `{label}` from another section"))
+                });
+
+                if i8::try_from(displacement(target, offset + 2)).is_err() {
+                    far_jumps.push(index);
+                }
+            }
+        }
+
+        if far_jumps.is_empty() {
+            return offsets;
+        }
+        for index in far_jumps {
+            if let Item::Jump { is_near, .. } = &mut items[index] {
+                *is_near = true;
+            }
+        }
+    }
+}
+
+// The offset of each item, from the size of the items before it.
+fn item_offsets(items: &[Item]) -> Vec<u64> {
+    let mut offset = 0;
+    items
+        .iter()
+        .map(|item| {
+            let item_offset = offset;
+            offset += match item {
+                Item::Bytes(bytes, _) => bytes.len() as u64,
+                Item::Label(..) | Item::Line(..) => 0,
+                Item::Align(alignment) => padding(offset, *alignment),
+                Item::Jump { condition: None, is_near: true, .. } => 5,
+                Item::Jump { condition: Some(..), is_near: true, .. } => 6,
+                Item::Jump { is_near: false, .. } => 2,
+            };
+            item_offset
+        })
+        .collect()
+}
+
+// Creates the contents of a section from its items, resolving references to labels in the section.
+fn assemble_section(
+    kind: SectionKind,
+    items: Vec<Item>,
+    offsets: &[u64],
+    labels: &HashMap<String, (SectionKind, u64)>,
+    externs: &[String],
+) -> Section {
+    let mut bytes = vec![];
+    let mut relocations = vec![];
+    let mut alignment = 1;
+
+    for (item, offset) in items.into_iter().zip(offsets) {
+        match item {
+            Item::Bytes(mut item_bytes, fixup) => {
+                if let Some(fixup) = fixup {
+                    match labels.get(&fixup.label) {
+                        Some((label_kind, target))
+                            if *label_kind == kind && fixup.kind != RelocationKind::Absolute64 =>
+                        {
+                            let position = offset + fixup.offset as u64;
+                            let value = i32::try_from(displacement(*target, position) + fixup.addend).unwrap();
+                            item_bytes[fixup.offset..fixup.offset + 4].copy_from_slice(&value.to_le_bytes());
+                        }
+                        None if !externs.contains(&fixup.label) => {
+                            internal_compiler_error(&format!("unable to encode a reference to `{}`", fixup.label))
+                        }
+                        _ => relocations.push(Relocation {
+                            offset: offset + fixup.offset as u64,
+                            symbol: fixup.label,
+                            kind: fixup.kind,
+                            addend: fixup.addend,
+                        }),
+                    }
+                }
+                bytes.extend(item_bytes);
+            }
+            Item::Label(..) | Item::Line(..) => (),
+            Item::Align(item_alignment) => {
+                let fill = if kind == SectionKind::Text { 0x90 } else { 0 };
+                bytes.resize(
+                    usize::try_from(offset + padding(*offset, item_alignment)).unwrap(),
+                    fill,
+                );
+                alignment = alignment.max(item_alignment);
+            }
+            Item::Jump { condition, label, is_near } => {
+                let opcode = match (condition, is_near) {
+                    (None, false) => vec![0xeb],
+                    (None, true) => vec![0xe9],
+                    (Some(condition), false) => vec![0x70 | condition],
+                    (Some(condition), true) => vec![0x0f, 0x80 | condition],
+                };
+                let end = offset + opcode.len() as u64 + if is_near { 4 } else { 1 };
+                let value = displacement(labels[&label].1, end);
+
+                bytes.extend(opcode);
+                if is_near {
+                    bytes.extend(i32::try_from(value).unwrap().to_le_bytes());
+                } else {
+                    bytes.extend(i8::try_from(value).unwrap().to_le_bytes());
+                }
+            }
+        }
+    }
+
+    Section { kind, bytes, alignment, relocations }
+}
+
+// The distance from an offset to a target offset.
+fn displacement(target: u64, offset: u64) -> i64 {
+    i64::try_from(target).unwrap() - i64::try_from(offset).unwrap()
+}
+
+// The number of bytes after an offset until the next multiple of the alignment.
+const fn padding(offset: u64, alignment: u64) -> u64 {
+    (alignment - offset % alignment) % alignment
+}
+
+// Encodes an instruction into an item of a section, or None for instructions without contents (like `Comment`).
+fn encode_instruction(instruction: &Instruction) -> Option<Item> {
+    let encoding = match instruction {
+        Global(..) | Extern(..) | Section(..) | SourceFile(..) | Comment(..) => return None,
+        Annotate(instruction, _) => return encode_instruction(instruction),
+        Label(label) => return Some(Item::Label(label.clone())),
+        Line(line) => return Some(Item::Line(*line)),
+        Align(alignment) => return Some(Item::Align(u64::try_from(*alignment).unwrap())),
+
+        Jmp(label) => return Some(jump(None, label)),
+        Je(label) => return Some(jump(Some(0x4), label)),
+        Jne(label) => return Some(jump(Some(0x5), label)),
+        Jl(label) => return Some(jump(Some(0xc), label)),
+        Jnl(label) => return Some(jump(Some(0xd), label)),
+        Jg(label) => return Some(jump(Some(0xf), label)),
+        Jng(label) => return Some(jump(Some(0xe), label)),
+        Ja(label) => return Some(jump(Some(0x7), label)),
+        Jae(label) => return Some(jump(Some(0x3), label)),
+        Jb(label) => return Some(jump(Some(0x2), label)),
+        Jbe(label) => return Some(jump(Some(0x6), label)),
+        Jp(label) => return Some(jump(Some(0xa), label)),
+
+        DqInt(value) => Encoding::bytes(&value.to_le_bytes()),
+        DqLabel(label) => Encoding::bytes(&[]).label(label, RelocationKind::Absolute64, 8),
+        DqString(string) => {
+            // Like `dq`, the string is padded to a multiple of 8 bytes, and then followed by 8 zero bytes.
+            let mut bytes = unescape(string);
+            bytes.resize((bytes.len() + 7) / 8 * 8 + 8, 0);
+            Encoding::bytes(&bytes)
+        }
+
+        Call(label) => Encoding::bytes(&[0xe8]).label(label, RelocationKind::Plt32, 4),
+        Ret => Encoding::bytes(&[0xc3]),
+        Cqo => Encoding::bytes(&[0x48, 0x99]),
+        LeaLabel(Reg(dest), label) => Encoding::modrm(None, true, &[0x8d], *dest, &MemLabel(label.clone())),
+        ComputedJmp(operand @ (Reg(..) | MemOffset(..))) => Encoding::modrm(None, false, &[0xff], 4, operand),
+
+        Push(Reg(register)) => Encoding::short_register(0x50, *register),
+        Push(Imm(value)) => match i8::try_from(*value) {
+            Ok(value) => Encoding::bytes(&[0x6a]).imm8(value),
+            Err(..) => Encoding::bytes(&[0x68]).imm32(imm32(instruction, *value)),
+        },
+        Push(operand @ MemOffset(..)) => Encoding::modrm(None, false, &[0xff], 6, operand),
+        Pop(Reg(register)) => Encoding::short_register(0x58, *register),
+        Pop(operand @ MemOffset(..)) => Encoding::modrm(None, false, &[0x8f], 0, operand),
+
+        Mov(Reg(dest), src @ (Imm(..) | FloatImm(..))) => {
+            // Float immediates are moved as their bits.
+            let value = match src {
+                FloatImm(value) => i64::from_ne_bytes(value.to_bits().to_ne_bytes()),
+                Imm(value) => *value,
+                _ => unsupported(instruction),
+            };
+            if let Ok(value) = u32::try_from(value) {
+                Encoding::short_register(0xb8, *dest).imm32_bytes(value.to_le_bytes())
+            } else if let Ok(value) = i32::try_from(value) {
+                Encoding::modrm(None, true, &[0xc7], 0, &Reg(*dest)).imm32(value)
+            } else {
+                Encoding::short_register(0xb8, *dest).wide().imm64(value)
+            }
+        }
+        Mov(dest @ MemOffset(..), Imm(value)) => {
+            Encoding::modrm(None, true, &[0xc7], 0, dest).imm32(imm32(instruction, *value))
+        }
+        Mov(dest @ (Reg(..) | MemOffset(..)), Reg(src)) => Encoding::modrm(None, true, &[0x89], *src, dest),
+        Mov(Reg(dest), src @ (MemOffset(..) | MemLabel(..))) => Encoding::modrm(None, true, &[0x8b], *dest, src),
+
+        MovByte(dest @ (Reg(..) | MemOffset(..)), Reg(src)) => {
+            Encoding::modrm(None, false, &[0x88], *src, dest).byte_registers(&[dest, &Reg(*src)])
+        }
+        MovByte(Reg(dest), src @ (MemOffset(..) | MemLabel(..))) => {
+            Encoding::modrm(None, false, &[0x8a], *dest, src).byte_registers(&[&Reg(*dest)])
+        }
+        MovByte(dest @ (Reg(..) | MemOffset(..)), Imm(value)) => Encoding::modrm(None, false, &[0xc6], 0, dest)
+            .byte_registers(&[dest])
+            .imm8(i8::try_from(*value).unwrap_or_else(|_| unsupported(instruction))),
+
+        Add(dest, src) => arithmetic(instruction, 0, dest, src),
+        Or(dest, src) => arithmetic(instruction, 1, dest, src),
+        And(dest, src) => arithmetic(instruction, 4, dest, src),
+        Sub(dest, src) => arithmetic(instruction, 5, dest, src),
+        Cmp(dest, src) => arithmetic(instruction, 7, dest, src),
+
+        Mul(Reg(dest), Imm(value)) => multiply_immediate(instruction, *dest, &Reg(*dest), *value),
+        Mul(Reg(dest), src @ (Reg(..) | MemOffset(..) | MemLabel(..))) => {
+            Encoding::modrm(None, true, &[0x0f, 0xaf], *dest, src)
+        }
+        Mul3(Reg(dest), src @ (Reg(..) | MemOffset(..) | MemLabel(..)), Imm(value)) => {
+            multiply_immediate(instruction, *dest, src, *value)
+        }
+        Mul1(operand @ (Reg(..) | MemOffset(..))) => Encoding::modrm(None, true, &[0xf7], 5, operand),
+        Div(operand @ (Reg(..) | MemOffset(..))) => Encoding::modrm(None, true, &[0xf7], 7, operand),
+        Neg(operand @ (Reg(..) | MemOffset(..))) => Encoding::modrm(None, true, &[0xf7], 3, operand),
+
+        Shl(dest, Imm(shift)) => shift_immediate(instruction, 4, dest, *shift),
+        Shr(dest, Imm(shift)) => shift_immediate(instruction, 5, dest, *shift),
+        Sar(dest, Imm(shift)) => shift_immediate(instruction, 7, dest, *shift),
+
+        Setz(dest) => set_condition(instruction, 0x94, dest),
+        Setnz(dest) => set_condition(instruction, 0x95, dest),
+        Setl(dest) => set_condition(instruction, 0x9c, dest),
+        Setle(dest) => set_condition(instruction, 0x9e, dest),
+
+        Movq(FloatReg(dest), src @ (FloatReg(..) | MemOffset(..) | MemLabel(..))) => {
+            Encoding::modrm(Some(0xf3), false, &[0x0f, 0x7e], *dest as u8, src)
+        }
+        Movq(FloatReg(dest), src @ Reg(..)) => Encoding::modrm(Some(0x66), true, &[0x0f, 0x6e], *dest as u8, src),
+        Movq(dest @ Reg(..), FloatReg(src)) => Encoding::modrm(Some(0x66), true, &[0x0f, 0x7e], *src as u8, dest),
+        Movq(dest @ MemOffset(..), FloatReg(src)) => {
+            Encoding::modrm(Some(0x66), false, &[0x0f, 0xd6], *src as u8, dest)
+        }
+        Cvttsd2si(Reg(dest), src @ (FloatReg(..) | MemOffset(..) | MemLabel(..))) => {
+            Encoding::modrm(Some(0xf2), true, &[0x0f, 0x2c], *dest, src)
+        }
+        Cvtsi2sd(FloatReg(dest), src @ (Reg(..) | MemOffset(..))) => {
+            Encoding::modrm(Some(0xf2), true, &[0x0f, 0x2a], *dest as u8, src)
+        }
+        Xorpd(dest, src) => float_arithmetic(instruction, 0x66, 0x57, dest, src),
+        Addsd(dest, src) => float_arithmetic(instruction, 0xf2, 0x58, dest, src),
+        Subsd(dest, src) => float_arithmetic(instruction, 0xf2, 0x5c, dest, src),
+        Mulsd(dest, src) => float_arithmetic(instruction, 0xf2, 0x59, dest, src),
+        Divsd(dest, src) => float_arithmetic(instruction, 0xf2, 0x5e, dest, src),
+        Ucomisd(dest, src) => float_arithmetic(instruction, 0x66, 0x2e, dest, src),
+        Cmpsd(dest, src, predicate) => {
+            float_arithmetic(instruction, 0xf2, 0xc2, dest, src).imm8(i8::from_le_bytes([*predicate]))
+        }
+
+        _ => unsupported(instruction),
+    };
+
+    Some(encoding.into_item())
+}
+
+// Encodes `add`, `or`, `and`, `sub` and `cmp`, where the operation is the opcode extension of the immediate forms.
+fn arithmetic(instruction: &Instruction, operation: u8, dest: &Operand, src: &Operand) -> Encoding {
+    match (dest, src) {
+        (Reg(..) | MemOffset(..), Imm(value)) => match i8::try_from(*value) {
+            Ok(value) => Encoding::modrm(None, true, &[0x83], operation, dest).imm8(value),
+
+            // The accumulator has a shorter form, without a ModRM byte.
+            Err(..) if *dest == Reg(Rax) => {
+                Encoding::bytes(&[0x48, operation << 3 | 0x05]).imm32(imm32(instruction, *value))
+            }
+            Err(..) => Encoding::modrm(None, true, &[0x81], operation, dest).imm32(imm32(instruction, *value)),
+        },
+        (Reg(..) | MemOffset(..), Reg(src)) => Encoding::modrm(None, true, &[operation << 3 | 0x01], *src, dest),
+        (Reg(dest), MemOffset(..) | MemLabel(..)) => Encoding::modrm(None, true, &[operation << 3 | 0x03], *dest, src),
+        _ => unsupported(instruction),
+    }
+}
+
+// Encodes `imul dest, src, value`.
+fn multiply_immediate(instruction: &Instruction, dest: Register, src: &Operand, value: i64) -> Encoding {
+    match i8::try_from(value) {
+        Ok(value) => Encoding::modrm(None, true, &[0x6b], dest, src).imm8(value),
+        Err(..) => Encoding::modrm(None, true, &[0x69], dest, src).imm32(imm32(instruction, value)),
+    }
+}
+
+// Encodes `shl`, `shr` and `sar` by an immediate, where the operation is the opcode extension.
+fn shift_immediate(instruction: &Instruction, operation: u8, dest: &Operand, shift: i64) -> Encoding {
+    match (dest, shift) {
+        (Reg(..) | MemOffset(..), 1) => Encoding::modrm(None, true, &[0xd1], operation, dest),
+        (Reg(..) | MemOffset(..), 0..=63) => {
+            Encoding::modrm(None, true, &[0xc1], operation, dest).imm8(i8::try_from(shift).unwrap())
+        }
+        _ => unsupported(instruction),
+    }
+}
+
+// Encodes a `set<cc>`, where the opcode is the second byte of the instruction.
+fn set_condition(instruction: &Instruction, opcode: u8, dest: &Operand) -> Encoding {
+    match dest {
+        Reg(..) | MemOffset(..) => Encoding::modrm(None, false, &[0x0f, opcode], 0, dest).byte_registers(&[dest]),
+        _ => unsupported(instruction),
+    }
+}
+
+// Encodes a scalar float instruction, with its mandatory prefix and (second) opcode byte.
+fn float_arithmetic(instruction: &Instruction, prefix: u8, opcode: u8, dest: &Operand, src: &Operand) -> Encoding {
+    match (dest, src) {
+        (FloatReg(dest), FloatReg(..) | MemOffset(..) | MemLabel(..)) => {
+            Encoding::modrm(Some(prefix), false, &[0x0f, opcode], *dest as u8, src)
+        }
+        _ => unsupported(instruction),
+    }
+}
+
+// Creates a jump that starts short.
+fn jump(condition: Option<u8>, label: &str) -> Item {
+    Item::Jump { condition, label: label.to_string(), is_near: false }
+}
+
+// Converts an immediate of an instruction to 32 bits, which is sign extended by the instruction.
+fn imm32(instruction: &Instruction, value: i64) -> i32 {
+    i32::try_from(value).unwrap_or_else(|_| unsupported(instruction))
+}
+
+fn unsupported(instruction: &Instruction) -> ! {
+    internal_compiler_error(&format!(
+        "unable to encode `{}`",
+        instruction_to_string(instruction.clone()).trim()
+    ))
+}
+
+// The number of a register in the encoding of an instruction.
+const fn register_number(register: Register) -> u8 {
+    match register {
+        Rax => 0,
+        Rcx => 1,
+        Rdx => 2,
+        Rbx => 3,
+        Rsp => 4,
+        Rbp => 5,
+        Rsi => 6,
+        Rdi => 7,
+        R8 => 8,
+        R9 => 9,
+        R10 => 10,
+        R11 => 11,
+        R12 => 12,
+        R13 => 13,
+        R14 => 14,
+        R15 => 15,
+    }
+}
+
+// Registers (and opcode extensions) of the reg field of a ModRM byte, which are either integer registers, float
+// registers, or opcode extensions (which are numbers).
+trait RegField {
+    fn number(self) -> u8;
+}
+
+impl RegField for Register {
+    fn number(self) -> u8 {
+        register_number(self)
+    }
+}
+
+impl RegField for u8 {
+    fn number(self) -> u8 {
+        self
+    }
+}
+
+// An encoded instruction, which is built from its parts in order: the mandatory prefix, the REX prefix, the opcode,
+// the ModRM byte (and SIB byte and displacement), and the immediate.
+struct Encoding {
+    prefix: Option<u8>,
+    rex: u8,
+    bytes: Vec<u8>,
+    fixup: Option<Fixup>,
+}
+
+impl Encoding {
+    // An encoding of raw bytes.
+    fn bytes(bytes: &[u8]) -> Self {
+        Self { prefix: None, rex: 0, bytes: bytes.to_vec(), fixup: None }
+    }
+
+    // An encoding where the register is added to the opcode (like `push r64`).
+    fn short_register(opcode: u8, register: Register) -> Self {
+        let number = register_number(register);
+        Self {
+            prefix: None,
+            rex: (number >> 3),
+            bytes: vec![opcode | (number & 7)],
+            fixup: None,
+        }
+    }
+
+    // An encoding with a ModRM byte, where `reg` is the reg field, and `rm` is the register or memory operand.
+    // * prefix - the mandatory prefix (like `0xf2` for scalar float instructions).
+    // * is_wide - whether the instruction has 64 bit operands, which is needed by most instructions (but not `push`).
+    fn modrm<R: RegField>(prefix: Option<u8>, is_wide: bool, opcode: &[u8], reg: R, rm: &Operand) -> Self {
+        let reg = reg.number();
+        let mut encoding = Self { prefix, rex: (reg >> 3) << 2, bytes: opcode.to_vec(), fixup: None };
+        if is_wide {
+            encoding = encoding.wide();
+        }
+
+        let reg = (reg & 7) << 3;
+        match rm {
+            Reg(register) => {
+                let number = register_number(*register);
+                encoding.rex |= number >> 3;
+                encoding.bytes.push(0xc0 | reg | (number & 7));
+            }
+            FloatReg(register) => {
+                let number = *register as u8;
+                encoding.rex |= number >> 3;
+                encoding.bytes.push(0xc0 | reg | (number & 7));
+            }
+            MemOffset(base, offset) => {
+                let (Reg(base), Imm(offset)) = (&**base, &**offset) else {
+                    internal_compiler_error("unable to encode a memory operand without a base register and offset")
+                };
+                let number = register_number(*base);
+                encoding.rex |= number >> 3;
+
+                // `rbp` and `r13` (without a displacement) mean rip relative, so they need a displacement of 0.
+                let displacement = i32::try_from(*offset).unwrap();
+                let mode = if displacement == 0 && number & 7 != 5 {
+                    0x00
+                } else if i8::try_from(displacement).is_ok() {
+                    0x40
+                } else {
+                    0x80
+                };
+                encoding.bytes.push(mode | reg | (number & 7));
+
+                // `rsp` and `r12` mean that there is a SIB byte, so they need a SIB byte without an index.
+                if number & 7 == 4 {
+                    encoding.bytes.push(0x24);
+                }
+                match mode {
+                    0x40 => encoding.bytes.extend(i8::try_from(displacement).unwrap().to_le_bytes()),
+                    0x80 => encoding.bytes.extend(displacement.to_le_bytes()),
+                    _ => (),
+                }
+            }
+            MemLabel(label) => {
+                encoding.bytes.push(reg | 0x05);
+                encoding = encoding.label(label, RelocationKind::Pc32, 4);
+            }
+            Imm(..) | FloatImm(..) => internal_compiler_error("unable to encode an immediate as a register or memory"),
+        }
+        encoding
+    }
+
+    // Sets REX.W, for 64 bit operands.
+    const fn wide(mut self) -> Self {
+        self.rex |= 0x08;
+        self
+    }
+
+    // Adds an empty REX prefix if any of the operands are `spl`, `bpl`, `sil` or `dil`, which are `ah`, `ch`, `dh` and
+    // `bh` without it.
+    fn byte_registers(mut self, operands: &[&Operand]) -> Self {
+        if operands
+            .iter()
+            .any(|operand| matches!(operand, Reg(register) if (4..8).contains(&register_number(*register))))
+        {
+            self.rex |= 0x40;
+        }
+        self
+    }
+
+    // Adds a reference to a label, with the given number of bytes (which are filled in later).
+    fn label(mut self, label: &str, kind: RelocationKind, size: usize) -> Self {
+        self.fixup = Some(Fixup { offset: self.bytes.len(), label: label.to_string(), kind, addend: 0 });
+        self.bytes.resize(self.bytes.len() + size, 0);
+        self
+    }
+
+    fn imm8(mut self, value: i8) -> Self {
+        self.bytes.extend(value.to_le_bytes());
+        self
+    }
+
+    fn imm32(self, value: i32) -> Self {
+        self.imm32_bytes(value.to_le_bytes())
+    }
+
+    fn imm32_bytes(mut self, bytes: [u8; 4]) -> Self {
+        self.bytes.extend(bytes);
+        self
+    }
+
+    fn imm64(mut self, value: i64) -> Self {
+        self.bytes.extend(value.to_le_bytes());
+        self
+    }
+
+    // Creates the item of the encoding, with the prefixes. Relative references to labels are relative to the end of
+    // the instruction (after the immediate), so their addend is the distance from the reference to the end.
+    fn into_item(self) -> Item {
+        let mut bytes: Vec<u8> = self.prefix.into_iter().collect();
+        if self.rex != 0 {
+            bytes.push(0x40 | self.rex);
+        }
+        let prefix_size = bytes.len();
+        let size = prefix_size + self.bytes.len();
+        bytes.extend(self.bytes);
+
+        let fixup = self.fixup.map(|fixup| {
+            let offset = prefix_size + fixup.offset;
+            let addend = match fixup.kind {
+                RelocationKind::Absolute64 | RelocationKind::Absolute32 => 0,
+                RelocationKind::Pc32 | RelocationKind::Plt32 => -i64::try_from(size - offset).unwrap(),
+            };
+            Fixup { offset, addend, ..fixup }
+        });
+        Item::Bytes(bytes, fixup)
+    }
+}
+
+// Converts the escape sequences of a string (like NASM's backquoted strings) into bytes.
+fn unescape(string: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    let mut characters = string.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            bytes.extend(character.to_string().bytes());
+            continue;
+        }
+        match characters.next() {
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('r') => bytes.push(b'\r'),
+            Some('0') => bytes.push(0),
+            Some('x') => {
+                let digits: String = characters.by_ref().take(2).collect();
+                bytes.push(u8::from_str_radix(&digits, 16).unwrap_or_else(|_| {
+                    internal_compiler_error(&format!("unable to encode the escape sequence `\\x{digits}`"))
+                }));
+            }
+            Some(character @ ('\\' | '"' | '\'' | '`')) => bytes.push(u8::try_from(character).unwrap()),
+            character => internal_compiler_error(&format!("unable to encode the escape sequence `\\{character:?}`")),
+        }
+    }
+    bytes
+}
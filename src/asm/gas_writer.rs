@@ -0,0 +1,143 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Converts the in memory representation of assembly into the Intel syntax of the GNU assembler (`as`), which is
+//! assembled through `cc` (see `bootstrapper.rs`).
+//!
+//! This is an alternative to the NASM syntax of `asm_writer.rs`, for systems without `nasm`.
+//!
+//! The Intel syntax of the GNU assembler (with `.intel_syntax noprefix`) mostly matches NASM, except for:
+//!   - directives, which start with a `.` (like `.globl` and `.quad`).
+//!   - the size of memory operands, which is followed by `PTR` (like `QWORD PTR [rsp + 8]`).
+//!   - rip relative addressing, which is written as `[rip + label]`.
+//!   - float immediates, which don't exist, so their bits are written as an integer instead.
+//!   - comments, which start with a `#`.
+
+use asm::asm::{Instruction, Instruction::*, Operand, Operand::*};
+use asm::asm_writer::{byte_register_to_string, float_register_to_string, label_name, register_to_string};
+use error_messages::internal_compiler_error;
+
+/// The lines that are written before the instructions, which switch the GNU assembler to Intel syntax.
+///
+/// On linux, the stack is also marked as non executable (otherwise, the linker warns that the stack is executable).
+pub fn gas_header() -> Vec<String> {
+    let mut header = vec![".intel_syntax noprefix".to_string()];
+    if !cfg!(target_os = "macos") {
+        header.push("\t.section .note.GNU-stack,\"\",@progbits".to_string());
+    }
+    header
+}
+
+// Converts a Operand to a string
+fn operand_to_string(operand: Operand) -> String {
+    match operand {
+        Reg(reg) => register_to_string(reg),
+        Imm(imm) => imm.to_string(),
+        MemOffset(operand_1, operand_2) => format!(
+            "QWORD PTR [{} + {}]",
+            &operand_to_string(*operand_1),
+            &operand_to_string(*operand_2)
+        ),
+        FloatImm(imm) => i64::from_ne_bytes(imm.to_bits().to_ne_bytes()).to_string(),
+        FloatReg(reg) => float_register_to_string(reg),
+        MemLabel(label) => format!("QWORD PTR [rip + {}]", label_name(label)),
+    }
+}
+
+// Converts a Operand to a string, where it must be corresponding to a BYTE
+fn byte_operand_to_string(operand: Operand) -> String {
+    match operand {
+        MemOffset(operand_1, operand_2) => format!(
+            "BYTE PTR [{} + {}]",
+            &operand_to_string(*operand_1),
+            &operand_to_string(*operand_2)
+        ),
+        MemLabel(label) => format!("BYTE PTR [rip + {}]", label_name(label)),
+        Reg(reg) => byte_register_to_string(reg),
+        Imm(..) | FloatImm(..) => operand_to_string(operand),
+        FloatReg(..) => internal_compiler_error("float register as byte operand"),
+    }
+}
+
+// Converts the name of a section to a directive. Mac doesn't have a `.rodata` section, where read only data is in
+// `__TEXT,__const` instead.
+fn section_to_string(section: &str) -> String {
+    match section {
+        "text" | "data" => format!("\t.{section}"),
+        "rodata" if cfg!(target_os = "macos") => "\t.const".to_string(),
+        _ => format!("\t.section .{section}"),
+    }
+}
+
+/// Converts a Instruction to a string, as it is written in the assembly file for the GNU assembler.
+pub fn instruction_to_gas_string(instruction: Instruction) -> String {
+    #[rustfmt::skip]
+    let instruction = match instruction {
+        Global(label) =>          format!("\t.globl {}", label_name(label)),
+        Extern(label) =>          format!("\t.extern {}", label_name(label)),
+        Section(label) =>         section_to_string(&label),
+        Label(label) =>           format!("{}:", label_name(label)),
+        DqLabel(label) =>         format!("\t.quad {}", label_name(label)),
+        // Like `dq` in NASM, the string is padded with zeros to a multiple of 8 bytes, followed by a zero quad.
+        DqString(label) =>        format!("1:\n\t.ascii \"{label}\"\n\t.skip (8 - (. - 1b) % 8) % 8\n\t.quad 0"),
+        DqInt(src) =>             format!("\t.quad {src}"),
+        Align(src) =>             format!("\t.balign {src}"),
+        Mov(dest, src) =>         format!("\tmov {}, {}", operand_to_string(dest), operand_to_string(src)),
+        MovByte(dest, src) =>     format!("\tmov {}, {}", byte_operand_to_string(dest), byte_operand_to_string(src)),
+        Add(dest, src) =>         format!("\tadd {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Sub(dest, src) =>         format!("\tsub {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Div(src) =>               format!("\tidiv {}", operand_to_string(src)),
+        Mul(dest, src) =>         format!("\timul {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Mul3(dest, src, con) =>   format!("\timul {}, {}, {}", operand_to_string(dest), operand_to_string(src), operand_to_string(con)),
+        Mul1(src) =>              format!("\timul {}", operand_to_string(src)),
+        Cqo =>                            "\tcqo".to_string(),
+        Neg(operand) =>           format!("\tneg {}", operand_to_string(operand)),
+        Shl(dest, src) =>         format!("\tshl {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Shr(dest, src) =>         format!("\tshr {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Sar(dest, src) =>         format!("\tsar {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Cmp(dest, src) =>         format!("\tcmp {}, {}", operand_to_string(dest), operand_to_string(src)),
+        And(dest, src) =>         format!("\tand {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Or(dest, src) =>          format!("\tor {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Setz(dest) =>             format!("\tsetz {}", byte_operand_to_string(dest)),
+        Setnz(dest) =>            format!("\tsetnz {}", byte_operand_to_string(dest)),
+        Setl(dest) =>             format!("\tsetl {}", byte_operand_to_string(dest)),
+        Setle(dest) =>            format!("\tsetle {}", byte_operand_to_string(dest)),
+        LeaLabel(dest, label) =>  format!("\tlea {}, [rip + {}]", operand_to_string(dest), label_name(label)),
+        Jmp(dest) =>              format!("\tjmp {}", label_name(dest)),
+        Je(dest) =>               format!("\tje {}", label_name(dest)),
+        Jne(dest) =>              format!("\tjne {}", label_name(dest)),
+        Jl(dest) =>               format!("\tjl {}", label_name(dest)),
+        Jnl(dest) =>              format!("\tjnl {}", label_name(dest)),
+        Jg(dest) =>               format!("\tjg {}", label_name(dest)),
+        Jng(dest) =>              format!("\tjng {}", label_name(dest)),
+        Ja(dest) =>               format!("\tja {}", label_name(dest)),
+        Jae(dest) =>              format!("\tjae {}", label_name(dest)),
+        Jb(dest) =>               format!("\tjb {}", label_name(dest)),
+        Jbe(dest) =>              format!("\tjbe {}", label_name(dest)),
+        Jp(dest) =>               format!("\tjp {}", label_name(dest)),
+        ComputedJmp(dest) =>      format!("\tjmp {}", operand_to_string(dest)),
+        Push(operand) =>          format!("\tpush {}", operand_to_string(operand)),
+        Pop(operand) =>           format!("\tpop {}", operand_to_string(operand)),
+        Call(dest) =>             format!("\tcall {}", label_name(dest)),
+        Ret =>                            "\tret".to_string(),
+
+        Movq(dest, src) =>        format!("\tmovq {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Cvttsd2si(dest, src) =>   format!("\tcvttsd2si {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Cvtsi2sd(dest, src) =>    format!("\tcvtsi2sd {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Xorpd(dest, src) =>       format!("\txorpd {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Addsd(dest, src) =>       format!("\taddsd {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Subsd(dest, src) =>       format!("\tsubsd {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Mulsd(dest, src) =>       format!("\tmulsd {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Divsd(dest, src) =>       format!("\tdivsd {}, {}", operand_to_string(dest), operand_to_string(src)),
+        Cmpsd(dest, src, mode) => format!("\tcmpsd {}, {}, {mode}", operand_to_string(dest), operand_to_string(src)),
+        Ucomisd(dest, src) =>     format!("\tucomisd {}, {}", operand_to_string(dest), operand_to_string(src)),
+
+        SourceFile(file) =>       format!("\t.file 1 \"{file}\""),
+        Line(line) =>             format!("\t.loc 1 {line}"),
+
+        Comment(comment) =>       format!("\n# {comment}"),
+        Annotate(instruction, comment) => {
+            format!("{: <40} # {comment}", instruction_to_gas_string(*instruction))
+        }
+    };
+    instruction
+}
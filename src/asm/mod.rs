@@ -5,3 +5,9 @@
 
 pub mod asm;
 pub mod asm_writer;
+pub mod debug_info;
+pub mod elf_writer;
+pub mod emulator;
+pub mod encoder;
+pub mod gas_writer;
+pub mod peephole;
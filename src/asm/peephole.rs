@@ -0,0 +1,230 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! A peephole optimizer, which runs on the instructions of the compiler (see `compiler.rs`) before they are written to
+//! the assembly file.
+//!
+//! The compiler compiles each expression separately, which leaves behind redundant sequences of instructions between
+//! expressions, like moving a value through a scratch register when a direct `mov` is legal.
+//!
+//! The optimizer is a set of rules (see `PeepholeRule`), where each rule matches a short window of instructions and
+//! replaces them with equivalent (but cheaper) instructions. The rules are applied until none of them match.
+//!
+//! `Comment`s (and `Line` markers) are kept, and are skipped over when matching a window, so they don't prevent a rule
+//! from matching. The comment of an `Annotate` instruction is moved to the first replacement instruction (or to a
+//! `Comment`, if the instructions were removed).
+//!
+//! The rules rely on the following invariants of the compiler:
+//!   - the scratch register (`R14`) never holds a value across a label or a jump.
+//!   - the flags are never read after arithmetic instructions, or after a jump (they are always set with a `cmp` first).
+//!
+//! Like the IR optimizer, the peephole optimizer is skipped with `--no-optimize`.
+
+use asm::asm::{Instruction, Instruction::*, Operand, Operand::*, Register, Register::*};
+use std::convert::TryFrom;
+
+/// The rules of the peephole optimizer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeepholeRule {
+    /// Removes moves of an operand to itself, like `mov r8, r8`.
+    SelfMove,
+
+    /// Replaces moving a value to a location through a scratch register (`mov r14, x; mov loc, r14`) with a direct
+    /// move (`mov loc, x`), if the direct move is legal and the scratch register is not read afterwards.
+    MoveThroughScratch,
+
+    /// Replaces checking the result of a `set<cc>` for a jump (`setl r8; cmp r8, 1; jne label`) with a jump on the
+    /// flags that were used to set the result (`setl r8; jnl label`).
+    JumpAfterSet,
+
+    /// Replaces multiplying by a power of two (`imul r8, 8`) with a shift (`shl r8, 3`).
+    MultiplyPowerOfTwo,
+}
+
+/// Every rule of the peephole optimizer, in the order that they are tried.
+pub const PEEPHOLE_RULES: [PeepholeRule; 4] = [
+    PeepholeRule::SelfMove,
+    PeepholeRule::MoveThroughScratch,
+    PeepholeRule::JumpAfterSet,
+    PeepholeRule::MultiplyPowerOfTwo,
+];
+
+/// Applies every rule of the peephole optimizer to the instructions.
+pub fn peephole_optimize(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    peephole_optimize_with(instructions, &PEEPHOLE_RULES)
+}
+
+/// Applies the given rules to the instructions, until none of them match.
+pub fn peephole_optimize_with(mut instructions: Vec<Instruction>, rules: &[PeepholeRule]) -> Vec<Instruction> {
+    loop {
+        let (optimized_instructions, is_changed) = peephole_pass(instructions, rules);
+        instructions = optimized_instructions;
+
+        if !is_changed {
+            return instructions;
+        }
+    }
+}
+
+// Applies the rules to the instructions once, from the start to the end.
+// * return - the instructions, and whether any rule was applied.
+fn peephole_pass(instructions: Vec<Instruction>, rules: &[PeepholeRule]) -> (Vec<Instruction>, bool) {
+    // The instructions that rules match on, which are the instructions that are not comments or line markers (without
+    // annotations).
+    let (indices, windows): (Vec<usize>, Vec<&Instruction>) = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, instruction)| match instruction {
+            Comment(..) | SourceFile(..) | Line(..) => None,
+            Annotate(instruction, _) => Some((index, &**instruction)),
+            _ => Some((index, instruction)),
+        })
+        .unzip();
+
+    // For each instruction (by index) that starts a match: the index after the matched instructions, and the
+    // replacement.
+    let mut replacements = vec![];
+    let mut window_start = 0;
+
+    while window_start < windows.len() {
+        let replacement = rules
+            .iter()
+            .find_map(|rule| apply_rule(*rule, &windows[window_start..]));
+
+        if let Some((count, replacement)) = replacement {
+            replacements.push((
+                indices[window_start],
+                indices[window_start + count - 1] + 1,
+                replacement,
+            ));
+            window_start += count;
+        } else {
+            window_start += 1;
+        }
+    }
+
+    if replacements.is_empty() {
+        return (instructions, false);
+    }
+
+    let mut optimized_instructions = vec![];
+    let mut instructions = instructions.into_iter().enumerate().peekable();
+
+    for (start, end, replacement) in replacements {
+        while let Some((_, instruction)) = instructions.next_if(|(index, _)| *index < start) {
+            optimized_instructions.push(instruction);
+        }
+
+        // Keep the comments of the replaced instructions.
+        let mut annotations = vec![];
+        while let Some((_, instruction)) = instructions.next_if(|(index, _)| *index < end) {
+            match instruction {
+                Comment(..) | SourceFile(..) | Line(..) => optimized_instructions.push(instruction),
+                Annotate(_, comment) => annotations.push(comment),
+                _ => (),
+            }
+        }
+
+        if annotations.is_empty() {
+            optimized_instructions.extend(replacement);
+        } else if replacement.is_empty() {
+            optimized_instructions.extend(annotations.into_iter().map(Comment));
+        } else {
+            let mut replacement = replacement.into_iter();
+            optimized_instructions.extend(replacement.next().map(|first| first.annotated(&annotations.join(", "))));
+            optimized_instructions.extend(replacement);
+        }
+    }
+
+    optimized_instructions.extend(instructions.map(|(_, instruction)| instruction));
+    (optimized_instructions, true)
+}
+
+// Applies a rule to the start of a window of instructions.
+// * return - the number of instructions that were matched, and their replacement (or None if the rule doesn't match).
+fn apply_rule(rule: PeepholeRule, window: &[&Instruction]) -> Option<(usize, Vec<Instruction>)> {
+    match (rule, window) {
+        (PeepholeRule::SelfMove, [Mov(dest, src) | Movq(dest, src), ..]) if dest == src => Some((1, vec![])),
+
+        (PeepholeRule::MoveThroughScratch, [Mov(Reg(scratch), src), Mov(dest, Reg(scratch_2)), rest @ ..])
+            if scratch == scratch_2
+                && is_scratch_register(*scratch)
+                && !src.uses_register(*scratch)
+                && !dest.uses_register(*scratch)
+                && is_legal_mov(dest, src)
+                && is_dead(*scratch, rest) =>
+        {
+            Some((2, vec![Mov(dest.clone(), src.clone())]))
+        }
+
+        (PeepholeRule::JumpAfterSet, [set, Cmp(operand, Imm(1)), Jne(label), ..]) => {
+            let (set_operand, jump): (&Operand, fn(String) -> Instruction) = match set {
+                Setz(set_operand) => (set_operand, Jne),
+                Setnz(set_operand) => (set_operand, Je),
+                Setl(set_operand) => (set_operand, Jnl),
+                Setle(set_operand) => (set_operand, Jg),
+                _ => return None,
+            };
+
+            (set_operand == operand).then(|| (3, vec![(*set).clone(), jump(label.clone())]))
+        }
+
+        (PeepholeRule::MultiplyPowerOfTwo, [Mul(dest, Imm(value)), ..]) => {
+            let shift = power_of_two(*value)?;
+            Some((1, if shift == 0 { vec![] } else { vec![Shl(dest.clone(), Imm(shift))] }))
+        }
+        (PeepholeRule::MultiplyPowerOfTwo, [Mul3(dest, src, Imm(value)), ..]) => {
+            let shift = power_of_two(*value)?;
+            let mut replacement = if dest == src { vec![] } else { vec![Mov(dest.clone(), src.clone())] };
+            if shift > 0 {
+                replacement.push(Shl(dest.clone(), Imm(shift)));
+            }
+            Some((1, replacement))
+        }
+
+        _ => None,
+    }
+}
+
+// The scratch register of the compiler, which never holds a value across a label or jump.
+const fn is_scratch_register(register: Register) -> bool {
+    matches!(register, R14)
+}
+
+// Whether `mov dest, src` is a legal instruction. Both operands can't be memory, and only a register can be the
+// destination of an immediate that doesn't fit in 32 bits (or a float immediate).
+fn is_legal_mov(dest: &Operand, src: &Operand) -> bool {
+    match (dest, src) {
+        (Reg(..), Reg(..) | MemOffset(..) | MemLabel(..) | Imm(..) | FloatImm(..)) | (MemOffset(..), Reg(..)) => true,
+        (MemOffset(..), Imm(value)) => i32::try_from(*value).is_ok(),
+        _ => false,
+    }
+}
+
+// Whether the value of a scratch register is never read by the instructions that follow.
+fn is_dead(register: Register, instructions: &[&Instruction]) -> bool {
+    for instruction in instructions {
+        match instruction {
+            // The register is written to without being read.
+            Mov(Reg(dest), src) | Movq(Reg(dest), src) if *dest == register => return !src.uses_register(register),
+
+            // Scratch registers never hold a value across a label or jump.
+            Label(..) | Jmp(..) | Je(..) | Jne(..) | Jl(..) | Jnl(..) | Jg(..) | Jng(..) | Ja(..) | Jae(..)
+            | Jb(..) | Jbe(..) | Jp(..) | ComputedJmp(..) | Ret | Call(..) => return true,
+
+            _ if instruction
+                .operands()
+                .iter()
+                .any(|operand| operand.uses_register(register)) =>
+            {
+                return false
+            }
+            _ => (),
+        }
+    }
+    true
+}
+
+// The exponent of a (positive) power of two, like 3 for 8.
+fn power_of_two(value: i64) -> Option<i64> {
+    (value > 0 && value.count_ones() == 1).then(|| i64::from(value.trailing_zeros()))
+}
@@ -3,48 +3,127 @@
 //! The bootstrapper is responsible for the entire process to create an executable for the input Solis program after
 //! compilation.  Specifically, after compiling the Solis program, we:
 //!  1. write the assembly to a file
-//!  2. assemble the file to an object file, using `nasm`.
+//!  2. assemble the file to an object file, using `nasm` (or the GNU assembler through `cc`, see `Assembler`). The
+//!     built-in assembler skips the assembly file, and writes the object file directly (see `encoder.rs`). With `-g`,
+//!     the object file also has the debug info of the program (see `debug_info.rs`).
 //!  3. link the object file with our runtime (runtime/runtime.c)
 //!  4. Optionally run (load) the executable
+//!
+//! With the C backend, the program is written to a C file instead (see `c_writer.rs`), which is compiled to an object
+//! file with `cc`. The object file is then linked and run the same way.
 
 use asm::asm::Instruction;
-use asm::asm_writer::write_instructions_to_file;
+use asm::asm_writer::{write_instructions_to_file, Assembler};
+use asm::debug_info::{add_debug_sections, DebugInfo};
+use asm::elf_writer::write_object_to_file;
+use asm::encoder::encode;
+use c_backend::c_writer::write_program_to_file;
+use clap::ValueEnum;
 use colored::Colorize;
 use error_messages::internal_compiler_error;
+use ir::ir::Program;
 use std::io::{self, Write};
 use std::path::Path;
 use std::process::Command;
 
+/// The backends that can create the object file of the program.
+#[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Backend {
+    /// The compiler, which creates x86-64 assembly (see `--assembler`).
+    Asm,
+
+    /// The C backend, which creates C99 source that is compiled with `cc`.
+    C,
+}
+
 /// Bootstrap the compiler output to an executable, and optionally run it.
 /// * instructions - compiler output
 /// * directory - the directory to create the intermediate files and resulting executable
 /// * name - the name of the executable, within `directory`
 /// * run - indicates if we should run the executable after creating it.
-/// * clean - indicates if we should remove the contents of the directory before creating executable
-pub fn bootstrap(instructions: Vec<Instruction>, directory: &Path, name: &str, run: bool, clean: bool) {
+/// * assembler - the assembler to assemble the instructions with.
+/// * `debug_info` - the debug info of the program (with `-g`), where the instructions have the line markers of the
+///   file (see `Instruction::SourceFile`).
+pub fn bootstrap(
+    instructions: Vec<Instruction>,
+    directory: &Path,
+    name: &str,
+    run: bool,
+    assembler: Assembler,
+    debug_info: Option<&DebugInfo>,
+) {
     let assembly_file_path = &directory.join(format!("{name}.s"));
     let object_file_path = &directory.join(format!("{name}.o"));
-    let runtime_object_file_path = &directory.join("runtime.o");
-    let executable_file_path = &directory.join(name);
 
-    // Clean the output directory
-    if clean {
-        ensure_success(Command::new("rm").arg("-rf").arg(directory));
+    // Write the instructions to an assembly file, and run the assembler to create an object file.
+    match assembler {
+        Assembler::Builtin => {
+            let mut object = encode(&instructions);
+            if let Some(debug_info) = debug_info {
+                add_debug_sections(&mut object, debug_info);
+            }
+            write_object_to_file(&object, object_file_path);
+        }
+        Assembler::Nasm => {
+            write_instructions_to_file(instructions, assembly_file_path, assembler);
+
+            // The GNU assembler creates the line info from the line markers on its own, but `nasm` needs `-g`.
+            let mut command = Command::new("nasm");
+            if debug_info.is_some() {
+                command.arg("-g").arg("-F").arg("dwarf");
+            }
+            ensure_success(
+                command
+                    .arg(assembly_file_path)
+                    .arg("-f")
+                    .arg(if cfg!(target_os = "macos") { "macho64" } else { "elf64" })
+                    .arg("-o")
+                    .arg(object_file_path),
+            );
+        }
+        Assembler::Gas => {
+            write_instructions_to_file(instructions, assembly_file_path, assembler);
+            ensure_success(
+                Command::new("cc")
+                    .arg("-c")
+                    .arg(assembly_file_path)
+                    .arg("-o")
+                    .arg(object_file_path),
+            );
+        }
     }
 
-    // Write the instructions to an assembly file.
-    write_instructions_to_file(instructions, assembly_file_path);
+    link(object_file_path, directory, name, run);
+}
 
-    // Run the assembler to create an object file.
+/// Bootstrap the program with the C backend to an executable, and optionally run it.
+/// * program - the IR of the program, which is lowered into C
+/// * directory - the directory to create the intermediate files and resulting executable
+/// * name - the name of the executable, within `directory`
+/// * run - indicates if we should run the executable after creating it.
+pub fn bootstrap_c(program: &Program, directory: &Path, name: &str, run: bool) {
+    let c_file_path = &directory.join(format!("{name}.c"));
+    let object_file_path = &directory.join(format!("{name}.o"));
+
+    // Write the program to a C file, and compile it to an object file.
+    write_program_to_file(program, c_file_path);
     ensure_success(
-        Command::new("nasm")
-            .arg(assembly_file_path)
-            .arg("-f")
-            .arg(if cfg!(target_os = "macos") { "macho64" } else { "elf64" })
+        Command::new("cc")
+            .arg("-std=c99")
+            .arg("-c")
+            .arg(c_file_path)
             .arg("-o")
             .arg(object_file_path),
     );
 
+    link(object_file_path, directory, name, run);
+}
+
+// Links the object file of the program with the runtime, and optionally runs (loads) the executable.
+fn link(object_file_path: &Path, directory: &Path, name: &str, run: bool) {
+    let runtime_object_file_path = &directory.join("runtime.o");
+    let executable_file_path = &directory.join(name);
+
     // Compile the Solis runtime.
     ensure_success(
         Command::new("cc")
@@ -74,6 +153,11 @@ pub fn bootstrap(instructions: Vec<Instruction>, directory: &Path, name: &str, r
     }
 }
 
+/// Removes the contents of the directory, where the intermediate files and executable are created.
+pub fn clean(directory: &Path) {
+    ensure_success(Command::new("rm").arg("-rf").arg(directory));
+}
+
 // Ensures that a command runs, finishes, and succeeds.
 fn ensure_success(command: &mut Command) {
     let output = command
@@ -0,0 +1,326 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The C backend lowers the IR into readable C99 source (with `--backend=c`), which is compiled with `cc` instead of
+//! our compiler and assembler (see `bootstrapper.rs`).
+//!
+//! This is a second, independent code path to cross-check the x86 backend against, and a way to run Solis programs on
+//! machines that our assembly doesn't support.
+//!
+//! The IR maps closely to C:
+//!   - each function is a `static` C function, which are declared before they are defined (so that functions can call
+//!     each other).
+//!   - the body of the program is the `body` function, and `entry` returns its result in the format of the runtime
+//!     (see `runtime.c`), where floats are returned as their bits.
+//!   - variables (including temporary variables) are local variables, which are declared where they are bound, with the
+//!     type of their init expression (see `type_inference.rs`).
+//!   - if expressions are if statements, where the result of each branch is assigned to the variable of the let (or is
+//!     returned, if the if expression is the result of a function).
+//!   - type coercions are casts.
+//!
+//! Identifiers are prefixed so that they can't collide with C keywords or the helpers: functions are `f_<id>`,
+//! variables are `v_<id>`, and temporary variables of the translator (`@temp0`) are `temp0`. Types are `int64_t`,
+//! `bool` and `double`, and unit is an `int64_t` that is always 0.
+//!
+//! The semantics match the interpreter (see `interpreter.rs`), instead of C, which has undefined behavior for some of
+//! these cases:
+//!   - int arithmetic wraps on overflow, so it is done with `uint64_t` and converted back to `int64_t`.
+//!   - integer division (and modulo) by zero or of `INT64_MIN` by `-1` raises `SIGFPE`, like the `idiv` instruction.
+//!   - float comparisons follow the predicates of `cmpsd`, so `>` and `>=` are written as the negation of `<=` and `<`
+//!     (which are true if either operand is `NaN`).
+//!   - floats that are not finite are written as their bits, to keep the exact bits of `NaN`s.
+
+use error_messages::internal_compiler_error;
+use ir::ir::{BinaryExprKind, Block, DirectExpr, Expr, Program, Type, UnaryExprKind};
+use ir::type_inference::ProgramTypes;
+use std::convert::TryFrom;
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+
+// Number of spaces that each nested block is indented by.
+const INDENT_SIZE: usize = 2;
+
+/// The includes and helper functions at the start of every C file.
+pub const PRELUDE: &str = r"// Generated by the Solis compiler.
+
+#include <signal.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <string.h>
+
+static int64_t divide(int64_t dividend, int64_t divisor) {
+  if (divisor == 0 || (dividend == INT64_MIN && divisor == -1)) {
+    raise(SIGFPE);
+  }
+  return dividend / divisor;
+}
+
+static int64_t modulo(int64_t dividend, int64_t divisor) {
+  if (divisor == 0 || (dividend == INT64_MIN && divisor == -1)) {
+    raise(SIGFPE);
+  }
+  return dividend % divisor;
+}
+
+static double float_from_bits(uint64_t bits) {
+  double value;
+  memcpy(&value, &bits, sizeof value);
+  return value;
+}
+
+static int64_t float_to_bits(double value) {
+  int64_t bits;
+  memcpy(&bits, &value, sizeof bits);
+  return bits;
+}
+";
+
+// Where the result of a block (or expression) goes.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    // The result is not used.
+    Discard,
+
+    // The result is assigned to a variable, with the name of the variable in C.
+    Assign(&'a str),
+
+    // The result is returned from the function.
+    Return,
+}
+
+/// Writes a program to a C file.
+pub fn write_program_to_file(program: &Program, file_path: &Path) {
+    create_dir_all(file_path.parent().unwrap()).unwrap();
+
+    write(file_path, write_program(program))
+        .unwrap_or_else(|error| internal_compiler_error(&format!("unable to write to file {error}")));
+}
+
+/// Lowers a program into C source.
+pub fn write_program(program: &Program) -> String {
+    let mut writer = CWriter { types: ProgramTypes::new(program), source: String::new() };
+
+    // Declare every function before any function is defined.
+    let signatures: Vec<String> = program
+        .functions
+        .iter()
+        .map(|function| {
+            let params: Vec<String> = function
+                .params
+                .iter()
+                .zip(&function.param_types)
+                .map(|(param, param_type)| format!("{} {}", c_type(param_type), c_id(param)))
+                .collect();
+
+            format!(
+                "static {} f_{}({})",
+                c_type(&function.return_type),
+                function.id,
+                if params.is_empty() { "void".to_string() } else { params.join(", ") }
+            )
+        })
+        .collect();
+
+    writer.source += PRELUDE;
+    if !signatures.is_empty() {
+        writer.write_line("", 0);
+        for signature in &signatures {
+            writer.write_line(&format!("{signature};"), 0);
+        }
+    }
+
+    for (function, signature) in program.functions.iter().zip(&signatures) {
+        writer.write_line("", 0);
+        writer.write_line(&format!("{signature} {{"), 0);
+        writer.write_block(&function.body, Target::Return, 1);
+        writer.write_line("}", 0);
+    }
+
+    let body_type = writer.types.block_type(&program.body);
+    writer.write_line("", 0);
+    writer.write_line(&format!("static {} body(void) {{", c_type(&body_type)), 0);
+    writer.write_block(&program.body, Target::Return, 1);
+    writer.write_line("}", 0);
+
+    let result = if body_type == Type::Float { "float_to_bits(body())" } else { "body()" };
+    writer.write_line("", 0);
+    writer.write_line("long entry(void) {", 0);
+    writer.write_line(&format!("return {result};"), 1);
+    writer.write_line("}", 0);
+
+    writer.source
+}
+
+// State that is kept while writing the C source.
+struct CWriter<'a> {
+    types: ProgramTypes<'a>,
+    source: String,
+}
+
+impl CWriter<'_> {
+    // Writes a line of C, indented by `indent` levels.
+    fn write_line(&mut self, line: &str, indent: usize) {
+        if !line.is_empty() {
+            self.source += &" ".repeat(indent * INDENT_SIZE);
+            self.source += line;
+        }
+        self.source += "\n";
+    }
+
+    // Writes the statements of a block, where the result of the block goes to the target.
+    fn write_block(&mut self, block: &Block, target: Target, indent: usize) {
+        if block.exprs.is_empty() {
+            self.write_unit(target, indent);
+        }
+
+        for (index, expr) in block.exprs.iter().enumerate() {
+            let expr_target = if index == block.exprs.len() - 1 { target } else { Target::Discard };
+            self.write_expr(expr, expr_target, indent);
+        }
+    }
+
+    // Writes the statements of an expression, where the result of the expression goes to the target.
+    fn write_expr(&mut self, expr: &Expr, target: Target, indent: usize) {
+        match expr {
+            Expr::Let { id, init_expr, .. } => {
+                let declaration = format!("{} {}", c_type(&self.types.expr_type(init_expr)), c_id(id));
+
+                if let Expr::If { .. } = **init_expr {
+                    self.write_line(&format!("{declaration};"), indent);
+                    self.write_expr(init_expr, Target::Assign(&c_id(id)), indent);
+                } else {
+                    self.write_line(&format!("{declaration} = {};", c_value(init_expr)), indent);
+                }
+                self.write_unit(target, indent);
+            }
+            Expr::If { condition, then_block, else_block } => {
+                self.write_line(&format!("if ({}) {{", c_direct(condition)), indent);
+
+                if let Some(else_block) = else_block {
+                    self.write_block(then_block, target, indent + 1);
+                    self.write_line("} else {", indent);
+                    self.write_block(else_block, target, indent + 1);
+                    self.write_line("}", indent);
+                } else {
+                    self.write_block(then_block, Target::Discard, indent + 1);
+                    self.write_line("}", indent);
+                    self.write_unit(target, indent);
+                }
+            }
+            Expr::Direct { .. } if matches!(target, Target::Discard) => {}
+            Expr::Call { .. } if matches!(target, Target::Discard) => {
+                self.write_line(&format!("{};", c_value(expr)), indent);
+            }
+            _ => self.write_result(&c_value(expr), target, indent),
+        }
+    }
+
+    // Writes a C expression to the target.
+    fn write_result(&mut self, value: &str, target: Target, indent: usize) {
+        match target {
+            Target::Discard => self.write_line(&format!("(void) ({value});"), indent),
+            Target::Assign(name) => self.write_line(&format!("{name} = {value};"), indent),
+            Target::Return => self.write_line(&format!("return {value};"), indent),
+        }
+    }
+
+    // Writes unit (0) to the target, if the result is used.
+    fn write_unit(&mut self, target: Target, indent: usize) {
+        if !matches!(target, Target::Discard) {
+            self.write_result("0", target, indent);
+        }
+    }
+}
+
+// The C expression of an expression that isn't a let or an if expression.
+fn c_value(expr: &Expr) -> String {
+    match expr {
+        Expr::Direct { expr } => c_direct(expr),
+        Expr::UnaryExpr { kind, operand, operand_type } => {
+            let operand = c_direct(operand);
+            match (kind, operand_type) {
+                (UnaryExprKind::Not, _) => format!("!{operand}"),
+                (UnaryExprKind::Negative, Type::Float) => format!("0.0 - {operand}"),
+                (UnaryExprKind::Negative, _) => format!("(int64_t) (0 - (uint64_t) {operand})"),
+            }
+        }
+        Expr::BinaryExpr { kind, operand_1, operand_2, operand_type } => {
+            c_binary_expr(kind, &c_direct(operand_1), &c_direct(operand_2), operand_type)
+        }
+        Expr::Call { id, args, .. } => {
+            let args: Vec<String> = args.iter().map(c_direct).collect();
+            format!("f_{id}({})", args.join(", "))
+        }
+        Expr::TypeCoercion { expr, to_type, .. } => format!("({}) {}", c_type(to_type), c_direct(expr)),
+        Expr::Let { .. } | Expr::If { .. } => internal_compiler_error("let and if expressions are not C expressions"),
+    }
+}
+
+// The C expression of a binary expression, where the operands are C expressions of type `operand_type`.
+fn c_binary_expr(kind: &BinaryExprKind, operand_1: &str, operand_2: &str, operand_type: &Type) -> String {
+    let is_float = *operand_type == Type::Float;
+
+    match kind {
+        BinaryExprKind::Plus | BinaryExprKind::Minus | BinaryExprKind::Times => {
+            let operator = match kind {
+                BinaryExprKind::Plus => "+",
+                BinaryExprKind::Minus => "-",
+                _ => "*",
+            };
+            if is_float {
+                format!("{operand_1} {operator} {operand_2}")
+            } else {
+                format!("(int64_t) ((uint64_t) {operand_1} {operator} (uint64_t) {operand_2})")
+            }
+        }
+        BinaryExprKind::Divide if is_float => format!("{operand_1} / {operand_2}"),
+        BinaryExprKind::Divide => format!("divide({operand_1}, {operand_2})"),
+        BinaryExprKind::Mod => format!("modulo({operand_1}, {operand_2})"),
+        BinaryExprKind::LessThan => format!("{operand_1} < {operand_2}"),
+        BinaryExprKind::LessThanOrEquals => format!("{operand_1} <= {operand_2}"),
+        BinaryExprKind::MoreThan if is_float => format!("!({operand_1} <= {operand_2})"),
+        BinaryExprKind::MoreThan => format!("{operand_1} > {operand_2}"),
+        BinaryExprKind::MoreThanOrEquals if is_float => format!("!({operand_1} < {operand_2})"),
+        BinaryExprKind::MoreThanOrEquals => format!("{operand_1} >= {operand_2}"),
+        BinaryExprKind::EqualsEquals => format!("{operand_1} == {operand_2}"),
+        BinaryExprKind::NotEquals => format!("{operand_1} != {operand_2}"),
+    }
+}
+
+// The C expression of a direct.
+fn c_direct(direct: &DirectExpr) -> String {
+    match direct {
+        DirectExpr::Int { value } => {
+            if *value == i64::MIN {
+                "INT64_MIN".to_string()
+            } else if i32::try_from(*value).is_ok() {
+                value.to_string()
+            } else {
+                format!("INT64_C({value})")
+            }
+        }
+        DirectExpr::Bool { value } => value.to_string(),
+        DirectExpr::Float { value } => {
+            if value.is_finite() {
+                format!("{value:?}")
+            } else {
+                format!("float_from_bits(0x{:016x})", value.to_bits())
+            }
+        }
+        DirectExpr::Id { value, .. } => c_id(value),
+    }
+}
+
+// The name of a variable in C.
+fn c_id(id: &str) -> String {
+    id.strip_prefix('@')
+        .map_or_else(|| format!("v_{id}"), ToString::to_string)
+}
+
+// The C type of a type.
+const fn c_type(ir_type: &Type) -> &'static str {
+    match ir_type {
+        Type::Unit | Type::Int => "int64_t",
+        Type::Bool => "bool",
+        Type::Float => "double",
+    }
+}
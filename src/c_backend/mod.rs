@@ -0,0 +1,6 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The C backend module lowers the intermediate representation into C source, as an alternative to the compiler and
+//! the assembler.
+
+pub mod c_writer;
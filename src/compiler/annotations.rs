@@ -0,0 +1,92 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Annotates the instructions of the compiler with the Solis source that they were compiled from (with
+//! `--annotate-asm`), which makes the assembly easier to read when debugging the compiler.
+//!
+//! Before the instructions of each line (see `Instruction::Line`), a comment with the line of the source is added, along
+//! with where the register allocator put the variables that are bound on that line, like:
+//! ```text
+//! ; 2: let b: int = a * 4 (b: r9)
+//! ```
+//! Consecutive markers of the same line (like the temporary bindings of a line) only add a single comment.
+
+use asm::asm::{Instruction, Instruction::*};
+use asm::asm_writer::{float_register_to_string, register_to_string};
+use ir::ir::{Block, Expr};
+use register_allocation::register_allocator::{Assignment, Map};
+use File;
+
+/// Adds a comment with the source line before the instructions of each line, where `variable_assignment` is the result
+/// of the register allocator on `block` (the body of the program).
+pub fn annotate_asm(
+    instructions: Vec<Instruction>,
+    file: &File,
+    block: &Block,
+    variable_assignment: &Map<&String, Assignment>,
+) -> Vec<Instruction> {
+    let mut bindings = Map::new();
+    add_bindings(block, &mut bindings);
+
+    let source_lines: Vec<&str> = file.contents.lines().collect();
+    let mut annotated_instructions = vec![];
+    let mut last_line = None;
+
+    for instruction in instructions {
+        if let Line(line) = instruction {
+            if last_line != Some(line) {
+                let source = source_lines.get(line - 1).map_or("", |source| source.trim());
+
+                let assignments: Vec<String> = bindings
+                    .get(&line)
+                    .map_or(&[][..], Vec::as_slice)
+                    .iter()
+                    .map(|id| format!("{id}: {}", assignment_to_string(variable_assignment.get(id))))
+                    .collect();
+
+                annotated_instructions.push(Comment(if assignments.is_empty() {
+                    format!("{line}: {source}")
+                } else {
+                    format!("{line}: {source} ({})", assignments.join(", "))
+                }));
+                last_line = Some(line);
+            }
+        }
+        annotated_instructions.push(instruction);
+    }
+    annotated_instructions
+}
+
+// Adds the variables that are bound in a block (and its nested blocks) to the line that they are bound on. Temporary
+// variables of the translator (like `@temp0`) are left out.
+fn add_bindings<'a>(block: &'a Block, bindings: &mut Map<usize, Vec<&'a String>>) {
+    for expr in &block.exprs {
+        let init_expr = match expr {
+            Expr::Let { id, init_expr, line } => {
+                if let Some(line) = line {
+                    if !id.starts_with('@') {
+                        bindings.entry(*line).or_default().push(id);
+                    }
+                }
+                init_expr
+            }
+            _ => expr,
+        };
+
+        if let Expr::If { then_block, else_block, .. } = init_expr {
+            add_bindings(then_block, bindings);
+            if let Some(else_block) = else_block {
+                add_bindings(else_block, bindings);
+            }
+        }
+    }
+}
+
+// Converts the assignment of a variable to where it is stored, like `r8` or `[rsp + 8]` for spilled variables.
+fn assignment_to_string(assignment: Option<&Assignment>) -> String {
+    match assignment {
+        Some(Assignment::Register(register)) => register_to_string(*register),
+        Some(Assignment::FloatRegister(register)) => float_register_to_string(*register),
+        Some(Assignment::Spill(slot)) => format!("[rsp + {}]", 8 * slot),
+        Some(Assignment::None) | None => "not stored".to_string(),
+    }
+}
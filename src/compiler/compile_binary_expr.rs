@@ -12,8 +12,9 @@
 //!     This is because `operand_1` or `operand_2` or `location` could be the *same*, so if the `location` was modified
 //!     during the expression, you might modify one of the operands before you use it.
 
-use asm::asm::{FloatRegister::*, Instruction, Instruction::*, Operand::*, Register::*};
-use compiler::compiler::compile_direct;
+use asm::asm::{FloatRegister::*, Instruction, Instruction::*, Operand, Operand::*, Register::*};
+use compiler::compiler::{compile_direct, gen_label};
+use compiler::strength_reduction::{compile_divide_by_constant, compile_multiply_by_constant};
 use compiler::symbol_table::{Location, SymbolTable};
 use error_messages::internal_compiler_error;
 use ir::ir::{BinaryExprKind, DirectExpr, Type};
@@ -40,8 +41,6 @@ pub fn compile_binary_expr(
     let mut asm_operand_1 = compile_direct(operand_1, symbol_table);
     let mut asm_operand_2 = compile_direct(operand_2, symbol_table);
 
-    instructions.push(Comment(format!("{kind:?}, {operand_1:?}, {operand_2:?}"))); // TODO: option?
-
     match kind {
         BinaryExprKind::Plus | BinaryExprKind::Minus => {
             // Move the first operand to a temporary register, and then operate on the temporary register.
@@ -57,16 +56,24 @@ pub fn compile_binary_expr(
         }
 
         BinaryExprKind::Times => {
+            // Ensure second operand is the immediate, and swapping doesn't change the result of the operation
+            if let Imm(..) = asm_operand_1 {
+                (asm_operand_1, asm_operand_2) = (asm_operand_2, asm_operand_1);
+            }
+
+            // Multiplying by a constant can potentially be strength reduced (see `strength_reduction.rs`).
+            if let (Reg(..) | MemOffset(..), Imm(constant)) = (&asm_operand_1, &asm_operand_2) {
+                if compile_multiply_by_constant(&asm_operand_1, *constant, location, instructions) {
+                    return;
+                }
+            }
+
             // If the output location is a register, we can potentially take advantage of the `imul <reg>,<reg>,<imm>`
             // format of `imul`. Only one of the operands can be immediate.
             if matches!(&location.to_operand(), Reg(..))
-                && (matches!(asm_operand_1, Imm(..)) ^ matches!(asm_operand_2, Imm(..)))
+                && !matches!(asm_operand_1, Imm(..))
+                && matches!(asm_operand_2, Imm(..))
             {
-                // Ensure second operand is the immediate, and swapping doesn't change the result of the operation
-                if let Imm(..) = asm_operand_1 {
-                    (asm_operand_1, asm_operand_2) = (asm_operand_2, asm_operand_1);
-                }
-
                 instructions.push(Mul3(location.to_operand(), asm_operand_1, asm_operand_2));
             } else {
                 instructions.push(Mov(Reg(R14), asm_operand_1));
@@ -76,6 +83,13 @@ pub fn compile_binary_expr(
         }
 
         BinaryExprKind::Divide | BinaryExprKind::Mod => {
+            // Dividing by a constant can potentially be strength reduced (see `strength_reduction.rs`).
+            if let (Reg(..) | MemOffset(..), Imm(divisor)) = (&asm_operand_1, &asm_operand_2) {
+                if compile_divide_by_constant(kind, &asm_operand_1, *divisor, location, instructions) {
+                    return;
+                }
+            }
+
             // Division operates on the Rax register, so we move the first operand to Rax.
             // TODO: Do we need to save rax? Potentially not. because in the scenario that you set rax to something, and then call compile_binary_expr, shouldn't happen, it should just return if rax is meaningful.
             // What about Rdx. Probably worth it to make rdx a general purpose register, and just save it and restore for the purpose of this expression.
@@ -98,6 +112,76 @@ pub fn compile_binary_expr(
             ));
         }
 
+        BinaryExprKind::LessThan
+        | BinaryExprKind::MoreThan
+        | BinaryExprKind::LessThanOrEquals
+        | BinaryExprKind::MoreThanOrEquals
+        | BinaryExprKind::EqualsEquals
+        | BinaryExprKind::NotEquals => {
+            let condition = compile_cmp(kind, asm_operand_1, asm_operand_2, instructions);
+
+            // Zero out location
+            instructions.push(Mov(location.to_operand(), Imm(0)));
+
+            instructions.push(match condition {
+                Condition::Less => Setl(location.to_operand()),
+                Condition::LessOrEquals => Setle(location.to_operand()),
+                Condition::Equals => Setz(location.to_operand()),
+                Condition::NotEquals => Setnz(location.to_operand()),
+            });
+        }
+    }
+}
+
+/// Compiles a comparison into a jump to `label` that is taken if the comparison is false, without putting the result
+/// of the comparison in a location (like for the condition of an `if`).
+/// * `kind` - the kind of comparison
+/// * `label` - where to jump to if the comparison is false
+pub fn compile_comparison_jump(
+    kind: &BinaryExprKind,
+    operand_1: &DirectExpr,
+    operand_2: &DirectExpr,
+    operand_type: &Type,
+    label: &str,
+    symbol_table: &mut SymbolTable,
+    instructions: &mut Vec<Instruction>,
+) {
+    if matches!(operand_type, Type::Float) {
+        return compile_comparison_jump_float(kind, operand_1, operand_2, label, symbol_table, instructions);
+    }
+
+    // Convert the operands to assembly operands
+    let asm_operand_1 = compile_direct(operand_1, symbol_table);
+    let asm_operand_2 = compile_direct(operand_2, symbol_table);
+
+    let condition = compile_cmp(kind, asm_operand_1, asm_operand_2, instructions);
+
+    // Jump on the inverse of the condition.
+    instructions.push(match condition {
+        Condition::Less => Jnl(label.to_string()),
+        Condition::LessOrEquals => Jg(label.to_string()),
+        Condition::Equals => Jne(label.to_string()),
+        Condition::NotEquals => Je(label.to_string()),
+    });
+}
+
+// The condition of the flags (after a `cmp`) for which a integer comparison is true.
+enum Condition {
+    Less,
+    LessOrEquals,
+    Equals,
+    NotEquals,
+}
+
+// Compares the operands of a integer comparison with a `cmp` instruction.
+// * return - the condition of the flags for which the comparison is true.
+fn compile_cmp(
+    kind: &BinaryExprKind,
+    mut asm_operand_1: Operand,
+    mut asm_operand_2: Operand,
+    instructions: &mut Vec<Instruction>,
+) -> Condition {
+    match kind {
         BinaryExprKind::LessThan
         | BinaryExprKind::MoreThan
         | BinaryExprKind::LessThanOrEquals
@@ -118,13 +202,10 @@ pub fn compile_binary_expr(
 
             instructions.push(Cmp(asm_operand_1, asm_operand_2));
 
-            // Zero out location
-            instructions.push(Mov(location.to_operand(), Imm(0)));
-
             if let BinaryExprKind::LessThan | BinaryExprKind::MoreThan = kind {
-                instructions.push(Setl(location.to_operand()));
+                Condition::Less
             } else {
-                instructions.push(Setle(location.to_operand()));
+                Condition::LessOrEquals
             }
         }
 
@@ -147,15 +228,14 @@ pub fn compile_binary_expr(
 
             instructions.push(Cmp(asm_operand_1, asm_operand_2));
 
-            // Zero out location
-            instructions.push(Mov(location.to_operand(), Imm(0)));
-
             if let BinaryExprKind::EqualsEquals = kind {
-                instructions.push(Setz(location.to_operand()));
+                Condition::Equals
             } else {
-                instructions.push(Setnz(location.to_operand()));
+                Condition::NotEquals
             }
         }
+
+        _ => internal_compiler_error("not a comparison"),
     }
 }
 
@@ -172,13 +252,11 @@ fn compile_binary_expr_float(
     let mut asm_operand_1 = compile_direct(operand_1, symbol_table);
     let mut asm_operand_2 = compile_direct(operand_2, symbol_table);
 
-    instructions.push(Comment(format!("{kind:?}, {operand_1:?}, {operand_2:?}"))); // TODO: option?
-
     // Ensure the first operand is in a xmm register. It could possibly become xmm14 so do not use xmm14 for the rest
     // of this function!.
     match asm_operand_1 {
         FloatReg(..) => (),
-        MemOffset(..) => {
+        MemOffset(..) | MemLabel(..) => {
             instructions.push(Movq(FloatReg(Xmm14), asm_operand_1));
             asm_operand_1 = FloatReg(Xmm14);
         }
@@ -188,7 +266,7 @@ fn compile_binary_expr_float(
     // Ensure the second operand is in a xmm register or m64. It could possibly become xmm15 so do not use xmm15 for the
     // rest of this function!.
     match asm_operand_2 {
-        FloatReg(..) | MemOffset(..) => (),
+        FloatReg(..) | MemOffset(..) | MemLabel(..) => (),
         FloatImm(..) | Imm(..) | Reg(..) => internal_compiler_error("float operand was immediate or reg"),
     }
 
@@ -247,3 +325,72 @@ fn compile_binary_expr_float(
         BinaryExprKind::Mod => internal_compiler_error("invalid binary expression"),
     }
 }
+
+// Compiles a comparison into a jump to `label` that is taken if the comparison is false, **where the operands are
+// floats**. The result matches the predicates of `cmpsd` (see `compile_binary_expr_float`), so comparisons with NaN are
+// false, except for `>`, `>=` and `!=`.
+//
+// `ucomisd` sets the flags like a unsigned comparison, except that unordered operands (if either is NaN) set the zero,
+// parity, and carry flags.
+fn compile_comparison_jump_float(
+    kind: &BinaryExprKind,
+    operand_1: &DirectExpr,
+    operand_2: &DirectExpr,
+    label: &str,
+    symbol_table: &mut SymbolTable,
+    instructions: &mut Vec<Instruction>,
+) {
+    // Convert the operands to assembly operands
+    let asm_operand_1 = compile_direct(operand_1, symbol_table);
+    let asm_operand_2 = compile_direct(operand_2, symbol_table);
+
+    // Ordering comparisons compare the operands in reverse (`b` with `a` for `a < b`), since only the "below"
+    // conditions include unordered operands.
+    let (mut asm_operand_1, asm_operand_2) = if let BinaryExprKind::EqualsEquals | BinaryExprKind::NotEquals = kind {
+        (asm_operand_1, asm_operand_2)
+    } else {
+        (asm_operand_2, asm_operand_1)
+    };
+
+    // Ensure the first operand is in a xmm register, and the second operand is in a xmm register or m64.
+    match asm_operand_1 {
+        FloatReg(..) => (),
+        MemOffset(..) | MemLabel(..) => {
+            instructions.push(Movq(FloatReg(Xmm14), asm_operand_1));
+            asm_operand_1 = FloatReg(Xmm14);
+        }
+        FloatImm(..) | Imm(..) | Reg(..) => internal_compiler_error("float operand was immediate or reg"),
+    }
+    match asm_operand_2 {
+        FloatReg(..) | MemOffset(..) | MemLabel(..) => (),
+        FloatImm(..) | Imm(..) | Reg(..) => internal_compiler_error("float operand was immediate or reg"),
+    }
+
+    instructions.push(Ucomisd(asm_operand_1, asm_operand_2));
+
+    match kind {
+        // `a < b` is `b` above `a`, and `a <= b` is `b` above or equal to `a`, which are false if unordered.
+        BinaryExprKind::LessThan => instructions.push(Jbe(label.to_string())),
+        BinaryExprKind::LessThanOrEquals => instructions.push(Jb(label.to_string())),
+
+        // `a > b` is not `a <= b`, and `a >= b` is not `a < b`, which are true if unordered.
+        BinaryExprKind::MoreThan => instructions.push(Jae(label.to_string())),
+        BinaryExprKind::MoreThanOrEquals => instructions.push(Ja(label.to_string())),
+
+        // Jump if not equal, or if unordered (which also sets the zero flag).
+        BinaryExprKind::EqualsEquals => {
+            instructions.push(Jne(label.to_string()));
+            instructions.push(Jp(label.to_string()));
+        }
+
+        // Jump if equal, but not if unordered.
+        BinaryExprKind::NotEquals => {
+            let unordered_label = gen_label("unordered");
+            instructions.push(Jp(unordered_label.clone()));
+            instructions.push(Je(label.to_string()));
+            instructions.push(Label(unordered_label));
+        }
+
+        _ => internal_compiler_error("not a comparison"),
+    }
+}
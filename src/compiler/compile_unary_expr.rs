@@ -33,8 +33,6 @@ pub fn compile_unary_expr(
     // Convert the operand to a assembly operand
     let mut asm_operand = compile_direct(operand, symbol_table);
 
-    instructions.push(Comment(format!("{kind:?}, {operand:?}"))); // TODO: option?
-
     match (kind, operand_type) {
         (UnaryExprKind::Not, Type::Bool) => {
             // The first operand of the `Cmp` instruction must be a Reg/MemOffset
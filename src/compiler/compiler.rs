@@ -6,77 +6,252 @@
 //! It works by traveling down the IR and works with the register allocator. There are a variety of terms that are
 //! used throughout this step:
 //!   - symbol table: maps variables to where they are stored at runtime (see `symbol_table.rs`)
-//!   - stack index: indicates the relative offset to the `RSP` register of where to put the next object (word).
-//!                  Or equivalently, `stack_index + 8` represents where the bottom of the stack is.
+//!   - stack frame: the space on the stack for spilled variables, where the register allocator assigns each spilled
+//!     variable a slot, and slot `i` is at `[rsp + 8 * i]`. The frame is reserved once at entry (`sub rsp`), and
+//!     released before returning (`add rsp`).
+//!   - prologue and epilogue: `entry` is called from C (see `runtime.c`), so it follows the System V ABI. The prologue
+//!     sets up `rbp`, saves the callee saved registers that are used by the instructions (see
+//!     `CALLEE_SAVED_REGISTERS`), and reserves the stack frame, such that `rsp` is aligned to 16 bytes. The epilogue
+//!     restores them, in reverse.
+//!   - constant pool: the float constants, which are read (relative to `rip`) from the `.rodata` section after the
+//!     instructions, since x86 has no float immediates.
+//!   - line markers: the instructions of each let binding are preceded by the line of the binding in the Solis file
+//!     (see `Instruction::Line`), which are used for debug info (see `debug_info.rs`).
 //! These objects are kept and tracked while traveling through the IR.
 
-use asm::asm::{FloatRegister::*, Instruction, Instruction::*, Operand, Operand::*, Register, Register::*};
-use compiler::compile_binary_expr::compile_binary_expr;
+use asm::asm::{
+    FloatRegister, FloatRegister::*, Instruction, Instruction::*, Operand, Operand::*, Register, Register::*,
+};
+use compiler::compile_binary_expr::{compile_binary_expr, compile_comparison_jump};
 use compiler::compile_unary_expr::compile_unary_expr;
-use compiler::symbol_table::{Location, SymbolTable};
+use compiler::symbol_table::{float_constant_label, Location, SymbolTable};
 use error_messages::internal_compiler_error;
-use ir::ir::{Block, DirectExpr, Expr, Program, Type};
+use ir::ir::{BinaryExprKind, Block, DirectExpr, Expr, Program, Type};
+use optimizer::dead_code_elimination::count_references_expr;
+use register_allocation::linear_scan::allocate_registers_linear;
 use register_allocation::register_allocator::allocate_registers;
-use register_allocation::register_allocator::{Assignment, Map, Set};
+use register_allocation::register_allocator::{Assignment, Map, RegisterAllocator, Set};
 use std::cell::RefCell;
+use std::convert::TryFrom;
+
+/// The pool of registers that the register allocator assigns variables to, in the order that they are preferred.
+///
+/// The callee saved registers are last, since they must be saved if they are used (see `CALLEE_SAVED_REGISTERS`).
+pub const REGISTERS: [Register; 8] = [R8, R9, R10, R11, Rbx, R12, R13, R15];
+
+/// The registers that are callee saved in the System V ABI (other than `rbp`, which is always saved), which are saved in
+/// the prologue of `entry` if they are used.
+pub const CALLEE_SAVED_REGISTERS: [Register; 5] = [Rbx, R12, R13, R14, R15];
+
+/// The pool of float registers that the register allocator assigns float variables to.
+pub const FLOAT_REGISTERS: [FloatRegister; 13] = [
+    Xmm1, Xmm2, Xmm3, Xmm4, Xmm5, Xmm6, Xmm7, Xmm8, Xmm9, Xmm10, Xmm11, Xmm12, Xmm13,
+];
 
 /// Compiles a Program into assembly instructions.
 pub fn compile(program: Program) -> Vec<Instruction> {
-    let mut instructions = vec![
-        Global("entry".to_string()),
-        Section("text".to_string()),
-        Label("entry".to_string()),
-    ];
+    // Run the register allocator
+    let variable_assignment = allocate(&program.body, RegisterAllocator::Graph);
 
-    let mut symbol_table = SymbolTable::new();
-    let mut stack_index = Box::new(-8);
+    compile_with_allocation(&program, &variable_assignment)
+}
 
-    // Run the register allocator
-    let variable_assignment: Map<&String, Assignment> = allocate_registers(
-        &program.body,
-        &Set::new(),
-        Set::from([&R8, &R9, &R10, &R11, &R12, &R13]),
-        Set::from([
-            &Xmm1, &Xmm2, &Xmm3, &Xmm4, &Xmm5, &Xmm6, &Xmm7, &Xmm8, &Xmm9, &Xmm10, &Xmm11, &Xmm12, &Xmm13,
-        ]),
-    );
+/// Compiles a Program into assembly instructions, where `variable_assignment` is the result of the register allocator
+/// on the body of the program (see `allocate`).
+pub fn compile_with_allocation(program: &Program, variable_assignment: &Map<&String, Assignment>) -> Vec<Instruction> {
+    let mut body = vec![];
+    let mut symbol_table = SymbolTable::new();
 
     compile_block(
         &program.body,
         &mut symbol_table,
-        &mut stack_index,
-        &variable_assignment,
-        &mut instructions,
+        variable_assignment,
+        &mut body,
         Some(&Location::Register(Rax)),
     );
 
+    // Only save the callee saved registers that are used.
+    let saved_registers: Vec<Register> = CALLEE_SAVED_REGISTERS
+        .iter()
+        .copied()
+        .filter(|register| {
+            body.iter().any(|instruction| {
+                instruction
+                    .operands()
+                    .iter()
+                    .any(|operand| operand.uses_register(*register))
+            })
+        })
+        .collect();
+
+    let mut instructions = vec![
+        Global("entry".to_string()),
+        Section("text".to_string()),
+        Label("entry".to_string()),
+        Push(Reg(Rbp)),
+        Mov(Reg(Rbp), Reg(Rsp)),
+    ];
+    instructions.extend(saved_registers.iter().map(|register| Push(Reg(*register))));
+
+    // Reserve the stack frame for spilled variables.
+    let frame_size = frame_size(variable_assignment, saved_registers.len());
+    if frame_size > 0 {
+        instructions.push(Sub(Reg(Rsp), Imm(frame_size)));
+    }
+
+    instructions.extend(body);
+
+    if frame_size > 0 {
+        instructions.push(Add(Reg(Rsp), Imm(frame_size)));
+    }
+    instructions.extend(saved_registers.iter().rev().map(|register| Pop(Reg(*register))));
+    instructions.push(Pop(Reg(Rbp)));
     instructions.push(Ret);
+
+    // Emit the float constants into a read-only pool, which is aligned for the 64 bit loads.
+    let float_constants = symbol_table.float_constants();
+    if !float_constants.is_empty() {
+        instructions.push(Section("rodata".to_string()));
+        instructions.push(Align(8));
+
+        for &bits in float_constants {
+            instructions.push(Label(float_constant_label(bits)));
+            instructions.push(DqInt(i64::from_ne_bytes(bits.to_ne_bytes())));
+        }
+    }
     instructions
 }
 
+/// Computes the size (in bytes) of the stack frame that is needed for the spill slots of an allocation, where
+/// `saved_register_count` registers are pushed (after `rbp`) before the frame.
+///
+/// The size is padded so that `rsp` is aligned to 16 bytes after the frame is reserved.
+pub fn frame_size(variable_assignment: &Map<&String, Assignment>, saved_register_count: usize) -> i64 {
+    let slot_count = variable_assignment
+        .values()
+        .filter_map(|assignment| match assignment {
+            Assignment::Spill(slot) => Some(slot + 1),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+
+    // `rsp` is aligned after pushing `rbp` (on top of the return address).
+    let saved_size = 8 * saved_register_count;
+    i64::try_from((8 * slot_count + saved_size + 15) / 16 * 16 - saved_size)
+        .unwrap_or_else(|_| internal_compiler_error("frame is too large"))
+}
+
+/// Runs a register allocator on a block, with the pools of registers that the compiler uses.
+pub fn allocate(block: &Block, register_allocator: RegisterAllocator) -> Map<&String, Assignment> {
+    let allocate_registers = match register_allocator {
+        RegisterAllocator::Graph => allocate_registers,
+        RegisterAllocator::Linear => allocate_registers_linear,
+    };
+
+    allocate_registers(block, &Set::new(), &REGISTERS, &FLOAT_REGISTERS)
+}
+
 // Compiles a Block into assembly instructions, pushing the results into `instructions`.
 fn compile_block(
     block: &Block,
     symbol_table: &mut SymbolTable,
-    stack_index: &mut Box<i64>,
     variable_assignment: &Map<&String, Assignment>,
     instructions: &mut Vec<Instruction>,
     location: Option<&Location>,
 ) {
+    // For the last expression only, compile the result into Rax (for implicit returns).
+    let expr_location = |i: usize| if i == block.exprs.len() - 1 { location } else { None };
+
     // Compile each expression
-    for (i, expr) in block.exprs.iter().enumerate() {
-        compile_expr(
-            expr,
-            // For the last expression only, compile the result into Rax (for implicit returns).
-            if i == block.exprs.len() - 1 { location } else { None },
-            symbol_table,
-            stack_index,
-            variable_assignment,
-            instructions,
-        );
+    let mut i = 0;
+    while i < block.exprs.len() {
+        // Mark the instructions of let bindings with the line that they were translated from (for debug info).
+        if let Expr::Let { line: Some(line), .. } = &block.exprs[i] {
+            instructions.push(Line(*line));
+        }
+
+        if let Some((comparison, then_block, else_block)) = fused_comparison(&block.exprs[i..]) {
+            // The comparison is compiled into the jump of the `if` that follows it, so it is never put in a location.
+            let compile_fused_if =
+                |location: Option<&Location>, symbol_table: &mut SymbolTable, instructions: &mut Vec<Instruction>| {
+                    compile_if(
+                        &IfCondition::Comparison(comparison),
+                        then_block,
+                        else_block,
+                        location,
+                        symbol_table,
+                        variable_assignment,
+                        instructions,
+                    );
+                };
+
+            if let Expr::Let { id, .. } = &block.exprs[i + 1] {
+                compile_let_with(
+                    id,
+                    expr_location(i + 1),
+                    symbol_table,
+                    variable_assignment,
+                    instructions,
+                    compile_fused_if,
+                );
+            } else {
+                compile_fused_if(expr_location(i + 1), symbol_table, instructions);
+            }
+            i += 2;
+        } else {
+            compile_expr(
+                &block.exprs[i],
+                expr_location(i),
+                symbol_table,
+                variable_assignment,
+                instructions,
+            );
+            i += 1;
+        }
     }
 }
 
+// Finds a comparison that can be fused into the jump of an `if`, which is a binding of a comparison (at the start of
+// `exprs`) that is only used as the condition of the `if` that immediately follows it (or the init of a let binding).
+// * return - the comparison, and the blocks of the `if`.
+fn fused_comparison(exprs: &[Expr]) -> Option<(&Expr, &Block, Option<&Block>)> {
+    let [Expr::Let { id, init_expr: comparison, .. }, next_expr, ..] = exprs else {
+        return None;
+    };
+
+    let Expr::BinaryExpr {
+        kind:
+            BinaryExprKind::LessThan
+            | BinaryExprKind::MoreThan
+            | BinaryExprKind::LessThanOrEquals
+            | BinaryExprKind::MoreThanOrEquals
+            | BinaryExprKind::EqualsEquals
+            | BinaryExprKind::NotEquals,
+        ..
+    } = **comparison
+    else {
+        return None;
+    };
+
+    let if_expr = match next_expr {
+        Expr::Let { init_expr, .. } => init_expr,
+        _ => next_expr,
+    };
+    let Expr::If { condition, then_block, else_block } = if_expr else {
+        return None;
+    };
+
+    // The comparison must not be used anywhere else, other than the condition.
+    let mut references = Map::new();
+    for expr in &exprs[1..] {
+        count_references_expr(expr, &mut references);
+    }
+
+    let is_condition = matches!(&**condition, DirectExpr::Id { value, .. } if value == id);
+    (is_condition && references.get(id) == Some(&1)).then_some((&**comparison, then_block, else_block.as_ref()))
+}
+
 /// Compiles an expression into assembly instructions, pushing the results into `instructions`
 /// * expr - input expression
 /// * location - where to put the result of the expression. If None, the result is not needed in the future.
@@ -85,7 +260,6 @@ pub fn compile_expr(
     expr: &Expr,
     location: Option<&Location>,
     symbol_table: &mut SymbolTable,
-    stack_index: &mut Box<i64>,
     variable_assignment: &Map<&String, Assignment>,
     instructions: &mut Vec<Instruction>,
 ) {
@@ -94,79 +268,35 @@ pub fn compile_expr(
             // If location is None, we can safely ignore the Direct as the result is not needed. This happens for
             // for example when a identifier appears in the top level of the block, like `let a: int = 0; a; ...`
             if let Some(location) = location {
-                // For float registers, we must move the float immediate through a register. See #18
-                if let DirectExpr::Float { .. } = expr {
-                    instructions.push(Mov(Reg(R14), compile_direct(expr, symbol_table)));
-
-                    if let Location::FloatRegister { .. } = location {
-                        instructions.push(Movq(location.to_operand(), Reg(R14)));
-                    } else {
-                        instructions.push(Mov(location.to_operand(), Reg(R14)));
-                    }
-                } else {
-                    mov_instruction_safe(
-                        location.to_operand(),
-                        compile_direct(expr, symbol_table),
-                        instructions,
-                        R14,
-                    );
-                }
+                mov_instruction_safe(
+                    location.to_operand(),
+                    compile_direct(expr, symbol_table),
+                    instructions,
+                    R14,
+                );
             }
         }
-        Expr::Let { id, init_expr } => compile_let(
-            id,
-            init_expr,
+        Expr::Let { id, init_expr, .. } => {
+            compile_let(id, init_expr, location, symbol_table, variable_assignment, instructions);
+        }
+        Expr::If { condition, then_block, else_block } => compile_if(
+            &IfCondition::Binding(condition),
+            then_block,
+            else_block.as_ref(),
             location,
             symbol_table,
-            stack_index,
             variable_assignment,
             instructions,
         ),
-        Expr::If { condition, then_block, else_block } => {
-            let condition = compile_direct(condition, symbol_table);
-            let else_label = gen_label("else");
-            let continue_label = gen_label("continue");
-
-            // Condition must be a binding.
-            if matches!(condition, Imm(..) | FloatImm(..)) {
-                internal_compiler_error("invalid condition")
-            };
-
-            instructions.push(Cmp(condition, Imm(1)));
-            instructions.push(Jne(if else_block.is_some() {
-                else_label.to_string()
-            } else {
-                continue_label.to_string()
-            }));
-
-            compile_block(
-                then_block,
-                symbol_table,
-                stack_index,
-                variable_assignment,
-                instructions,
-                location,
-            );
-
-            if let Some(else_block) = else_block {
-                instructions.push(Jmp(continue_label.to_string()));
-                instructions.push(Label(else_label));
-
-                compile_block(
-                    else_block,
-                    symbol_table,
-                    stack_index,
-                    variable_assignment,
-                    instructions,
-                    location,
-                );
-            }
-
-            instructions.push(Label(continue_label));
-        }
         Expr::BinaryExpr { kind, operand_1, operand_2, operand_type } => {
             // If location is None, we can safely ignore the BinaryExpr as well since it *cannot induce any side
-            if let Some(location) = location {
+            // effects*, except for integer division, which crashes when dividing by zero. In that case, the result is
+            // put in a temporary register, and discarded.
+            let discarded_location = Location::Register(R14);
+            let is_int_division =
+                matches!(kind, BinaryExprKind::Divide | BinaryExprKind::Mod) && *operand_type != Type::Float;
+
+            if let Some(location) = location.or_else(|| is_int_division.then_some(&discarded_location)) {
                 compile_binary_expr(
                     kind,
                     operand_1,
@@ -198,39 +328,48 @@ pub fn compile_let(
     init_expr: &Expr,
     location: Option<&Location>,
     symbol_table: &mut SymbolTable,
-    stack_index: &mut Box<i64>,
     variable_assignment: &Map<&String, Assignment>,
     instructions: &mut Vec<Instruction>,
 ) {
-    // Convert assignment of let binding to a location
-    let assignment_location = match variable_assignment.get(id).unwrap() {
-        Assignment::Register(register) => Location::Register(*register),
-        Assignment::FloatRegister(register) => Location::FloatRegister(*register),
-        Assignment::Spill => {
-            let location = Location::StackIndex(**stack_index);
-            **stack_index -= 8;
-            location
-        }
-        Assignment::None => {
-            return compile_expr(
+    compile_let_with(
+        id,
+        location,
+        symbol_table,
+        variable_assignment,
+        instructions,
+        |init_location, symbol_table, instructions| {
+            compile_expr(
                 init_expr,
-                None,
+                init_location,
                 symbol_table,
-                stack_index,
                 variable_assignment,
                 instructions,
-            )
-        }
+            );
+        },
+    );
+}
+
+// Compiles a let expression, where `compile_init` compiles the init expression into a location (or None, if the result
+// is not needed).
+fn compile_let_with(
+    id: &String,
+    location: Option<&Location>,
+    symbol_table: &mut SymbolTable,
+    variable_assignment: &Map<&String, Assignment>,
+    instructions: &mut Vec<Instruction>,
+    compile_init: impl FnOnce(Option<&Location>, &mut SymbolTable, &mut Vec<Instruction>),
+) {
+    // Convert assignment of let binding to a location
+    let assignment_location = match variable_assignment.get(id).unwrap() {
+        Assignment::Register(register) => Location::Register(*register),
+        Assignment::FloatRegister(register) => Location::FloatRegister(*register),
+        Assignment::Spill(slot) => Location::StackIndex(
+            i64::try_from(8 * slot).unwrap_or_else(|_| internal_compiler_error("frame is too large")),
+        ),
+        Assignment::None => return compile_init(None, symbol_table, instructions),
     };
 
-    compile_expr(
-        init_expr,
-        Some(&assignment_location),
-        symbol_table,
-        stack_index,
-        variable_assignment,
-        instructions,
-    );
+    compile_init(Some(&assignment_location), symbol_table, instructions);
 
     // Move the result of the init_expr to location if it is set.
     if let Some(location) = location {
@@ -246,6 +385,69 @@ pub fn compile_let(
     symbol_table.insert(id.to_string(), assignment_location);
 }
 
+// The condition of an if expression.
+enum IfCondition<'a> {
+    // A boolean binding, like the `c` of `if c {...}`.
+    Binding(&'a DirectExpr),
+
+    // A comparison (a `BinaryExpr`) that is compiled directly into the jump (see `fused_comparison`).
+    Comparison(&'a Expr),
+}
+
+// Compiles an if expression, pushing the results into `instructions`.
+fn compile_if(
+    condition: &IfCondition,
+    then_block: &Block,
+    else_block: Option<&Block>,
+    location: Option<&Location>,
+    symbol_table: &mut SymbolTable,
+    variable_assignment: &Map<&String, Assignment>,
+    instructions: &mut Vec<Instruction>,
+) {
+    let else_label = gen_label("else");
+    let continue_label = gen_label("continue");
+
+    // Where to jump to if the condition is false.
+    let false_label = if else_block.is_some() { &else_label } else { &continue_label };
+
+    match condition {
+        IfCondition::Binding(condition) => {
+            let condition = compile_direct(condition, symbol_table);
+
+            // Condition must be a binding.
+            if matches!(condition, Imm(..) | FloatImm(..)) {
+                internal_compiler_error("invalid condition")
+            };
+
+            instructions.push(Cmp(condition, Imm(1)));
+            instructions.push(Jne(false_label.clone()));
+        }
+        IfCondition::Comparison(Expr::BinaryExpr { kind, operand_1, operand_2, operand_type }) => {
+            compile_comparison_jump(
+                kind,
+                operand_1,
+                operand_2,
+                operand_type,
+                false_label,
+                symbol_table,
+                instructions,
+            );
+        }
+        IfCondition::Comparison(..) => internal_compiler_error("invalid condition"),
+    }
+
+    compile_block(then_block, symbol_table, variable_assignment, instructions, location);
+
+    if let Some(else_block) = else_block {
+        instructions.push(Jmp(continue_label.to_string()));
+        instructions.push(Label(else_label));
+
+        compile_block(else_block, symbol_table, variable_assignment, instructions, location);
+    }
+
+    instructions.push(Label(continue_label));
+}
+
 /// Converts (coercion) an expression from one type to another type, pushing the results into `instructions`
 /// * expr - input expression
 /// * location - where to put the result of the expression. If None, the result is not needed in the future.
@@ -283,17 +485,19 @@ pub fn compile_direct(direct: &DirectExpr, symbol_table: &mut SymbolTable) -> Op
             .unwrap_or_else(|| internal_compiler_error(&format!("symbol `{value}` not in symbol_table")))
             .to_operand(),
         DirectExpr::Bool { value } => Imm(i64::from(*value)),
-        DirectExpr::Float { value } => FloatImm(*value),
+        DirectExpr::Float { value } => symbol_table.float_constant(*value),
     }
 }
 
-/// Converts a stack index into a assembly operand.
+/// Converts a stack index (the offset of a spill slot from `RSP`) into a assembly operand.
 pub fn stack_address(stack_index: i64) -> Operand {
     MemOffset(Box::new(Reg(Rsp)), Box::new(Imm(stack_index)))
 }
 
-/// Same as adding a `Mov(asm_operand_1, asm_operand_2)`, but ensures that both operands are not `MemOffset`.
-/// If both are, the second operand is moved to the `backup_temporary_register`.
+/// Same as adding a `Mov(asm_operand_1, asm_operand_2)`, but ensures that both operands are not memory, and that
+/// immediates moved into a `MemOffset` fit in 32 bits.
+///
+/// If not, the second operand is moved to the `backup_temporary_register`.
 pub fn mov_instruction_safe(
     asm_operand_1: Operand,
     asm_operand_2: Operand,
@@ -307,7 +511,14 @@ pub fn mov_instruction_safe(
         Mov
     };
 
-    if matches!(asm_operand_1, MemOffset(..)) && matches!(asm_operand_2, MemOffset(..)) {
+    let is_imm64 = matches!(asm_operand_2, Imm(value) if i32::try_from(value).is_err());
+
+    // Moving a operand to itself is a no-op (for example, for variables that were coalesced by the register allocator).
+    if asm_operand_1 == asm_operand_2 {
+        return;
+    }
+
+    if matches!(asm_operand_1, MemOffset(..)) && (matches!(asm_operand_2, MemOffset(..) | MemLabel(..)) || is_imm64) {
         instructions.push(mov_instruction(Reg(backup_temporary_register), asm_operand_2));
         instructions.push(mov_instruction(asm_operand_1, Reg(backup_temporary_register)));
     } else {
@@ -315,8 +526,8 @@ pub fn mov_instruction_safe(
     }
 }
 
-// Gives a assembly label that is unique and can't conflict with any previously generated labels.
-fn gen_label(label: &str) -> String {
+/// Gives a assembly label that is unique and can't conflict with any previously generated labels.
+pub fn gen_label(label: &str) -> String {
     thread_local! {
         pub static TAG: RefCell<u32> = RefCell::new(0);
     }
@@ -2,8 +2,10 @@
 
 //! The `compiler` module generates the assembly instructions for the input program.
 
+pub mod annotations;
 pub mod compiler;
 
 mod compile_binary_expr;
 mod compile_unary_expr;
+mod strength_reduction;
 mod symbol_table;
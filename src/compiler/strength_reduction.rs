@@ -0,0 +1,177 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Strength reduction of integer multiplication, division and modulo by a constant, which replaces `imul` and `idiv`
+//! with cheaper instructions. See `compile_binary_expr.rs`.
+//!   - multiplication by `0`, `1`, `-1`, `2^k`, `2^k + 1` and `2^k - 1` (or their negation) becomes a shift and an add
+//!     or subtract (like `x * 9` = `(x << 3) + x`).
+//!   - division and modulo by `2^k` (or its negation) become shifts, where negative dividends are biased by `2^k - 1`
+//!     so that the result rounds towards zero (like `idiv`).
+//!   - division and modulo by any other constant become a multiply by a "magic number", where the high half of the
+//!     product is the quotient (see Hacker's Delight, chapter 10).
+//!
+//! Division and modulo by `0` and `-1` are not reduced, since they must crash like `idiv` (for `0`, and for
+//! `i64::MIN / -1`).
+//!
+//! Like `compile_binary_expr.rs`, the operand and `location` must not be modified until the result is put in `location`.
+//! Like `idiv`, division and modulo use `Rax` and `Rdx` as scratch registers (along with `R14`).
+
+use asm::asm::{Instruction, Instruction::*, Operand, Operand::*, Register::*};
+use compiler::symbol_table::Location;
+use ir::ir::BinaryExprKind;
+use std::convert::TryFrom;
+
+/// Compiles `asm_operand * constant` without `imul`, pushing the results into `instructions`
+/// * return - whether the multiplication was reduced. If not, no instructions are pushed.
+pub fn compile_multiply_by_constant(
+    asm_operand: &Operand,
+    constant: i64,
+    location: &Location,
+    instructions: &mut Vec<Instruction>,
+) -> bool {
+    // Negative constants are reduced as a multiplication by the magnitude, followed by a negation. Everything wraps.
+    let magnitude = constant.unsigned_abs();
+
+    if magnitude == 0 {
+        instructions.push(Mov(location.to_operand(), Imm(0)));
+        return true;
+    }
+
+    // Multiply by a power of two (with a shift), and then add or subtract the operand if needed.
+    let (power, adjustment) = if magnitude.is_power_of_two() {
+        (magnitude, None)
+    } else if (magnitude - 1).is_power_of_two() {
+        (magnitude - 1, Some(Add(Reg(R14), asm_operand.clone())))
+    } else if (magnitude + 1).is_power_of_two() {
+        (magnitude + 1, Some(Sub(Reg(R14), asm_operand.clone())))
+    } else {
+        return false;
+    };
+
+    instructions.push(Mov(Reg(R14), asm_operand.clone()));
+    if power > 1 {
+        instructions.push(Shl(Reg(R14), Imm(i64::from(power.trailing_zeros()))));
+    }
+    instructions.extend(adjustment);
+
+    if constant < 0 {
+        instructions.push(Neg(Reg(R14)));
+    }
+    instructions.push(Mov(location.to_operand(), Reg(R14)));
+    true
+}
+
+/// Compiles `asm_operand / divisor` or `asm_operand % divisor` (depending on `kind`) without `idiv`, pushing the
+/// results into `instructions`
+/// * return - whether the division was reduced. If not, no instructions are pushed.
+pub fn compile_divide_by_constant(
+    kind: &BinaryExprKind,
+    asm_operand: &Operand,
+    divisor: i64,
+    location: &Location,
+    instructions: &mut Vec<Instruction>,
+) -> bool {
+    // Division by the magnitude has the same result, except that the quotient is negated.
+    let magnitude = match divisor.checked_abs() {
+        Some(magnitude) if magnitude > 1 => magnitude.unsigned_abs(),
+        _ => return false,
+    };
+    let is_divide = matches!(kind, BinaryExprKind::Divide);
+
+    if magnitude.is_power_of_two() {
+        let shift = i64::from(magnitude.trailing_zeros());
+
+        // The bias (`2^k - 1` if the dividend is negative, otherwise `0`) is computed from the sign bits.
+        instructions.push(Mov(Reg(R14), asm_operand.clone()));
+        instructions.push(Mov(Reg(Rdx), Reg(R14)));
+        instructions.push(Sar(Reg(Rdx), Imm(63)));
+        instructions.push(Shr(Reg(Rdx), Imm(64 - shift)));
+
+        if is_divide {
+            instructions.push(Add(Reg(R14), Reg(Rdx)));
+            instructions.push(Sar(Reg(R14), Imm(shift)));
+        } else {
+            // The remainder is the dividend minus the biased dividend, rounded down to a multiple of `2^k`.
+            instructions.push(Add(Reg(Rdx), Reg(R14)));
+            instructions.push(Sar(Reg(Rdx), Imm(shift)));
+            instructions.push(Shl(Reg(Rdx), Imm(shift)));
+            instructions.push(Sub(Reg(R14), Reg(Rdx)));
+        }
+    } else {
+        let (magic, shift) = magic_number(magnitude);
+
+        // The high half of the product (in Rdx) is the quotient, before adjusting it.
+        instructions.push(Mov(Reg(Rax), Imm(magic)));
+        instructions.push(Mul1(asm_operand.clone()));
+        if magic < 0 {
+            instructions.push(Add(Reg(Rdx), asm_operand.clone()));
+        }
+        if shift > 0 {
+            instructions.push(Sar(Reg(Rdx), Imm(shift)));
+        }
+
+        // Round the quotient towards zero, by adding 1 if it is negative.
+        instructions.push(Mov(Reg(R14), Reg(Rdx)));
+        instructions.push(Shr(Reg(R14), Imm(63)));
+        instructions.push(Add(Reg(Rdx), Reg(R14)));
+
+        if is_divide {
+            instructions.push(Mov(Reg(R14), Reg(Rdx)));
+        } else {
+            // The remainder is the dividend minus the quotient times the divisor.
+            if let Ok(magnitude) = i32::try_from(magnitude) {
+                instructions.push(Mul3(Reg(Rdx), Reg(Rdx), Imm(i64::from(magnitude))));
+            } else {
+                instructions.push(Mov(Reg(R14), Imm(divisor.abs())));
+                instructions.push(Mul(Reg(Rdx), Reg(R14)));
+            }
+            instructions.push(Mov(Reg(R14), asm_operand.clone()));
+            instructions.push(Sub(Reg(R14), Reg(Rdx)));
+        }
+    }
+
+    if is_divide && divisor < 0 {
+        instructions.push(Neg(Reg(R14)));
+    }
+    instructions.push(Mov(location.to_operand(), Reg(R14)));
+    true
+}
+
+// Computes the magic number and shift for signed division by a constant (that is at least 2, and not a power of two),
+// such that `x / divisor` is the high half of `x * magic` (plus `x` if the magic number is negative), shifted right
+// arithmetically by the shift, and then rounded towards zero. See Hacker's Delight, section 10-4.
+const fn magic_number(divisor: u64) -> (i64, i64) {
+    let two_63: u64 = 1 << 63;
+    let absolute_nc = two_63 - 1 - two_63 % divisor;
+
+    let mut exponent = 63;
+    let (mut quotient_1, mut remainder_1) = (two_63 / absolute_nc, two_63 % absolute_nc);
+    let (mut quotient_2, mut remainder_2) = (two_63 / divisor, two_63 % divisor);
+
+    loop {
+        exponent += 1;
+
+        quotient_1 = quotient_1.wrapping_mul(2);
+        remainder_1 = remainder_1.wrapping_mul(2);
+        if remainder_1 >= absolute_nc {
+            quotient_1 = quotient_1.wrapping_add(1);
+            remainder_1 -= absolute_nc;
+        }
+
+        quotient_2 = quotient_2.wrapping_mul(2);
+        remainder_2 = remainder_2.wrapping_mul(2);
+        if remainder_2 >= divisor {
+            quotient_2 = quotient_2.wrapping_add(1);
+            remainder_2 -= divisor;
+        }
+
+        let delta = divisor - remainder_2;
+        if quotient_1 > delta || (quotient_1 == delta && remainder_1 != 0) {
+            break;
+        }
+    }
+
+    (
+        i64::from_ne_bytes(quotient_2.wrapping_add(1).to_ne_bytes()),
+        exponent - 64,
+    )
+}
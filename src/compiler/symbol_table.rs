@@ -2,6 +2,9 @@
 
 //! A symbol table is a type that is created on the fly in the compiler. It maps variables to where they are stored at
 //! runtime. This file contains definitions and helper functions for dealing with the symbol table.
+//!
+//! The symbol table also keeps the float constants that are used by the instructions that are being compiled, since
+//! they are emitted into a constant pool after the instructions (see `compile_with_allocation`).
 
 use asm::asm::{
     FloatRegister,
@@ -12,7 +15,13 @@ use compiler::compiler::stack_address;
 use std::collections::HashMap;
 
 /// Symbol Table type
-pub type SymbolTable = HashMap<String, Location>;
+#[derive(Default)]
+pub struct SymbolTable {
+    locations: HashMap<String, Location>,
+
+    // The bits of the float constants, in the order of their first use (see `float_constant`).
+    float_constants: Vec<u64>,
+}
 
 // Where the symbol is located at run time.
 pub enum Location {
@@ -21,6 +30,44 @@ pub enum Location {
     StackIndex(i64),
 }
 
+impl SymbolTable {
+    /// Creates a symbol table without any symbols or float constants.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets where a variable is located.
+    pub fn get(&self, id: &str) -> Option<&Location> {
+        self.locations.get(id)
+    }
+
+    /// Adds a variable, which is located at `location`.
+    pub fn insert(&mut self, id: String, location: Location) {
+        self.locations.insert(id, location);
+    }
+
+    /// The operand of a float constant, which is read from the constant pool. There are no float immediates in x86, so
+    /// each (distinct) float constant is put in the pool.
+    pub fn float_constant(&mut self, value: f64) -> Operand {
+        let bits = value.to_bits();
+        if !self.float_constants.contains(&bits) {
+            self.float_constants.push(bits);
+        }
+
+        MemLabel(float_constant_label(bits))
+    }
+
+    /// The bits of the float constants that were used, in the order of their first use.
+    pub fn float_constants(&self) -> &[u64] {
+        &self.float_constants
+    }
+}
+
+/// The label of a float constant in the constant pool, by the bits of the float.
+pub fn float_constant_label(bits: u64) -> String {
+    format!("float__{bits:x}")
+}
+
 impl Location {
     /// Converts a Location to a `asm::Operand`
     pub fn to_operand(&self) -> Operand {
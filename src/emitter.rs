@@ -0,0 +1,114 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The emitter dumps the intermediate stages of compilation (see `--emit`), which is useful for debugging the compiler.
+//!
+//! Each stage is either printed to stdout, or written to a file in the destination directory named after the stage.
+
+use clap::ValueEnum;
+use colored::Colorize;
+use error_messages::internal_compiler_error;
+use ir::ir::Block;
+use register_allocation::conflict_analysis::conflict_analysis;
+use register_allocation::register_allocator::{Assignment, Map, Set};
+use register_allocation::spill_cost::spill_costs;
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+
+/// The intermediate stages of compilation that can be emitted, in the order that they are created. The names of each
+/// stage are used in the CLI.
+#[derive(ValueEnum, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Stage {
+    /// Output of the tokenizer.
+    Tokens,
+
+    /// Output of the parser.
+    Ast,
+
+    /// Output of the translator (and the optimizer), in the textual IR format.
+    Ir,
+
+    /// The spill cost of each variable of the program body, which the register allocator uses to choose spills.
+    SpillCosts,
+
+    /// Output of the register allocator, as the assignment of each variable of the program body.
+    Regalloc,
+
+    /// Output of the compiler.
+    Asm,
+
+    /// Output of the C backend (with `--backend=c`).
+    C,
+}
+
+impl Stage {
+    // The extension of the file that the stage is written to.
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Tokens => "tokens",
+            Self::Ast => "ast",
+            Self::Ir => "ir",
+            Self::SpillCosts => "spill-costs",
+            Self::Regalloc => "regalloc",
+            Self::Asm => "s",
+            Self::C => "c",
+        }
+    }
+}
+
+/// Emits the contents of a stage.
+/// * destination - the directory to write `<name>.<extension>` to. If None, the contents are printed to stdout.
+/// * name - the name of the executable.
+pub fn emit(stage: Stage, contents: &str, destination: Option<&Path>, name: &str) {
+    if let Some(destination) = destination {
+        let file_path = destination.join(format!("{name}.{}", stage.extension()));
+
+        create_dir_all(destination)
+            .and_then(|()| write(&file_path, contents))
+            .unwrap_or_else(|error| internal_compiler_error(&format!("unable to emit {stage:?}: {error}")));
+    } else {
+        println!("{}", format!("--- {name} {stage:?} ---").bold());
+        println!("{contents}");
+    }
+}
+
+/// Formats the assignment of each variable from the register allocator, one variable per line (sorted by name).
+pub fn format_allocation(allocation: &Map<&String, Assignment>) -> String {
+    let mut lines: Vec<String> = allocation
+        .iter()
+        .map(|(variable, assignment)| format!("{variable}: {assignment:?}\n"))
+        .collect();
+
+    lines.sort();
+    lines.concat()
+}
+
+/// Formats the spill cost of each variable of a block, one variable per line (sorted by name). The degree of each
+/// variable is its degree in the interference graph, before coalescing.
+pub fn format_spill_costs(block: &Block) -> String {
+    let (interference_graph, float_interference_graph, _) = conflict_analysis(block, &Set::new());
+
+    let mut lines: Vec<String> = spill_costs(block)
+        .iter()
+        .map(|(variable, spill_cost)| {
+            let degree = if float_interference_graph.nodes.contains_key(variable) {
+                float_interference_graph.degree(variable)
+            } else {
+                interference_graph.degree(variable)
+            };
+
+            let cost = if spill_cost.is_short_lived {
+                "never spilled (short-lived)".to_string()
+            } else {
+                format!("cost {:.3}", spill_cost.cost(degree))
+            };
+
+            format!(
+                "{variable}: references {}, degree {degree}, {cost}\n",
+                spill_cost.weighted_references
+            )
+        })
+        .collect();
+
+    lines.sort();
+    lines.concat()
+}
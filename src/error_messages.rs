@@ -3,7 +3,7 @@
 //! This file is responsible for error messaging for compiler errors. A compiler error refers to a state when a
 //! compiler fails to compile a piece of computer program source code.
 
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use ir::ir::Type;
 
 use std::backtrace::Backtrace;
@@ -26,6 +26,31 @@ use File;
 ///             **It is assumed that position lies on 1 line and is valid and in bounds**
 /// * message: the error message to display
 pub fn compilation_error(file: &File, position: &Range<usize>, message: &str) -> ! {
+    let error_message = format_snippet(file, position, &"Error".red().bold(), message);
+
+    // For testing purposes, we don't want to exit() when we want to test that certain inputs raise errors.
+    // Instead, we are able to test for panics.
+    if cfg!(feature = "test") {
+        panic!("{}", error_message.normal());
+    } else {
+        println!("{error_message}");
+        std::process::exit(exitcode::DATAERR)
+    }
+}
+
+/// Creates the message for a warning within the Solis **input program**.
+///
+/// Warnings point at code that compiles, but is likely a mistake (see `warnings.rs`), and are pretty printed in the
+/// same format as `compilation_error`.
+/// * file: the original Solis file
+/// * position: describes where the warning is in the source code, as a index range.
+/// * message: the warning message to display
+pub fn compilation_warning(file: &File, position: &Range<usize>, message: &str) -> String {
+    format_snippet(file, position, &"Warning".yellow().bold(), message)
+}
+
+// Pretty prints a snippet of the Solis input, pin pointing `position`, with a `label` (like "Error") and `message`.
+fn format_snippet(file: &File, position: &Range<usize>, label: &ColoredString, message: &str) -> String {
     let next_newline_search = file.contents[position.start..].find('\n');
     let mut next_newline = next_newline_search.unwrap_or(file.contents.len() - 1 - position.start) + position.start;
     let mut newline_indicies: Vec<usize> = file.contents[..=next_newline]
@@ -50,12 +75,11 @@ pub fn compilation_error(file: &File, position: &Range<usize>, message: &str) ->
         SHOULD_COLORIZE.set_override(false);
     }
 
-    let error_message = format!(
-        "{error}: {message}\n {arrow} {filename}:{line_number}:{column}\n  \
+    format!(
+        "{label}: {message}\n {arrow} {filename}:{line_number}:{column}\n  \
                  {bar_padding}{bar}\n\
         {display_line_number} {bar} {line}\n  \
                  {bar_padding}{bar} {padding}{caret}\n",
-        error = "Error".red().bold(),
         message = message.bold(),
         arrow = "-->".blue().bold(),
         filename = file.name,
@@ -67,16 +91,7 @@ pub fn compilation_error(file: &File, position: &Range<usize>, message: &str) ->
         line = &file.contents[prev_newline..next_newline],
         padding = " ".repeat(column),
         caret = "^".repeat(position.len()).yellow().bold()
-    );
-
-    // For testing purposes, we don't want to exit() when we want to test that certain inputs raise errors.
-    // Instead, we are able to test for panics.
-    if cfg!(feature = "test") {
-        panic!("{}", error_message.normal());
-    } else {
-        println!("{error_message}");
-        std::process::exit(exitcode::DATAERR)
-    }
+    )
 }
 
 /// Called when there is an error within the Solis **compiler** itself, at compile time. Ideally, this should never be
@@ -0,0 +1,274 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! A tree-walking interpreter for the IR, which is the reference semantics of Solis programs.
+//!
+//! It is used to run programs without an assembler or linker (`--interpret`), and as an oracle for testing the rest of
+//! the back end.
+//!
+//! The semantics of each expression match the executable that the compiler creates:
+//!   - int arithmetic wraps on overflow, and integer division truncates towards zero (like `idiv`). Dividing by zero
+//!     (or dividing `i64::MIN` by `-1`) is a runtime error, instead of a crash of the executable.
+//!   - float comparisons follow the predicates of `cmpsd`, so `>`, `>=`, and `!=` are true if either operand is `NaN`.
+//!   - float negation is `0.0 - x`, like the compiler (so `-0.0` is `0.0`).
+//!   - bools are compared as the integers 0 and 1.
+//!
+//! The result of a program is printed the same way as the runtime (`runtime.c`), which prints the result of the body
+//! as a 64 bit integer. Bools are printed as 0 or 1, floats are printed as their bits, and unit is printed as 0.
+
+use colored::Colorize;
+use error_messages::internal_compiler_error;
+use ir::ir::{BinaryExprKind, Block, DirectExpr, Expr, Function, Program, Type, UnaryExprKind};
+use register_allocation::register_allocator::Map;
+use std::fmt::{self, Display};
+
+/// A value that an expression evaluates to at runtime.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Value {
+    Unit,
+    Int(i64),
+    Bool(bool),
+    Float(f64),
+}
+
+impl Value {
+    /// The 64 bit integer that the runtime would print for the value.
+    pub fn to_runtime_output(self) -> i64 {
+        match self {
+            Self::Unit => 0,
+            Self::Int(value) => value,
+            Self::Bool(value) => i64::from(value),
+            Self::Float(value) => i64::from_ne_bytes(value.to_ne_bytes()),
+        }
+    }
+}
+
+/// Errors that can occur while interpreting a program. These correspond to crashes of the executable.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuntimeError {
+    /// Integer division (or modulo) by zero.
+    DivisionByZero,
+
+    /// Integer division (or modulo) of `i64::MIN` by `-1`, which overflows.
+    DivisionOverflow,
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::DivisionOverflow => write!(f, "overflow in division"),
+        }
+    }
+}
+
+/// Interprets a program.
+/// * return - the value of the body of the program, or the error that occurred at runtime.
+///
+/// # Errors
+/// Returns a `RuntimeError` if the program would crash at runtime (like dividing by zero).
+pub fn interpret(program: &Program) -> Result<Value, RuntimeError> {
+    let interpreter = Interpreter {
+        functions: program
+            .functions
+            .iter()
+            .map(|function| (&function.id, function))
+            .collect(),
+    };
+
+    interpreter.interpret_block(&program.body, &mut Environment { scopes: vec![] })
+}
+
+/// Interprets a program and prints the result, like running the executable of the program. If there is a runtime
+/// error, the error is reported and we exit.
+pub fn run(program: &Program) {
+    match interpret(program) {
+        Ok(value) => print!("{}", value.to_runtime_output()),
+        Err(error) => {
+            // For testing purposes, we don't want to exit() when we want to test that certain inputs raise errors.
+            if cfg!(feature = "test") {
+                panic!("Runtime Error: {}", error);
+            } else {
+                eprintln!("{}: {}", "Runtime Error".red().bold(), error.to_string().bold());
+                std::process::exit(exitcode::SOFTWARE)
+            }
+        }
+    }
+}
+
+// The values of the variables that are in scope, where each scope is a block.
+struct Environment<'a> {
+    scopes: Vec<Map<&'a String, Value>>,
+}
+
+impl Environment<'_> {
+    // Gets the value of a variable, which is in the innermost scope that it is bound in.
+    fn get(&self, id: &String) -> Value {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(id).copied())
+            .unwrap_or_else(|| internal_compiler_error(&format!("variable `{id}` is not in scope")))
+    }
+}
+
+// State that is kept while interpreting.
+struct Interpreter<'a> {
+    functions: Map<&'a String, &'a Function>,
+}
+
+impl<'a> Interpreter<'a> {
+    // Interprets a block in a new scope.
+    // * return - the value of the last expression of the block, or unit if the block is empty.
+    fn interpret_block(&self, block: &'a Block, environment: &mut Environment<'a>) -> Result<Value, RuntimeError> {
+        environment.scopes.push(Map::new());
+
+        let mut result = Value::Unit;
+        for expr in &block.exprs {
+            match self.interpret_expr(expr, environment) {
+                Ok(value) => result = value,
+                Err(error) => {
+                    environment.scopes.pop();
+                    return Err(error);
+                }
+            }
+        }
+
+        environment.scopes.pop();
+        Ok(result)
+    }
+
+    // Interprets an expression.
+    fn interpret_expr(&self, expr: &'a Expr, environment: &mut Environment<'a>) -> Result<Value, RuntimeError> {
+        match expr {
+            Expr::Direct { expr } => Ok(interpret_direct(expr, environment)),
+            Expr::Let { id, init_expr, .. } => {
+                let value = self.interpret_expr(init_expr, environment)?;
+
+                if let Some(scope) = environment.scopes.last_mut() {
+                    scope.insert(id, value);
+                }
+                Ok(Value::Unit)
+            }
+            Expr::If { condition, then_block, else_block } => match interpret_direct(condition, environment) {
+                Value::Bool(true) => self.interpret_block(then_block, environment),
+                Value::Bool(false) => else_block.as_ref().map_or(Ok(Value::Unit), |else_block| {
+                    self.interpret_block(else_block, environment)
+                }),
+                _ => internal_compiler_error("if condition is not a bool"),
+            },
+            Expr::UnaryExpr { kind, operand, .. } => {
+                Ok(interpret_unary_expr(kind, interpret_direct(operand, environment)))
+            }
+            Expr::BinaryExpr { kind, operand_1, operand_2, .. } => interpret_binary_expr(
+                kind,
+                interpret_direct(operand_1, environment),
+                interpret_direct(operand_2, environment),
+            ),
+            Expr::Call { id, args, .. } => {
+                let function = self
+                    .functions
+                    .get(id)
+                    .unwrap_or_else(|| internal_compiler_error(&format!("function `{id}` is not declared")));
+
+                // Bind the arguments to the parameters, in a new environment.
+                let scope = function
+                    .params
+                    .iter()
+                    .zip(args)
+                    .map(|(param, arg)| (param, interpret_direct(arg, environment)))
+                    .collect();
+
+                self.interpret_block(&function.body, &mut Environment { scopes: vec![scope] })
+            }
+            Expr::TypeCoercion { expr, from_type, to_type } => {
+                match (interpret_direct(expr, environment), from_type, to_type) {
+                    #[allow(clippy::cast_precision_loss)]
+                    (Value::Int(value), Type::Int, Type::Float) => Ok(Value::Float(value as f64)),
+                    _ => internal_compiler_error("invalid type coercion"),
+                }
+            }
+        }
+    }
+}
+
+// Interprets a direct.
+fn interpret_direct(direct: &DirectExpr, environment: &Environment) -> Value {
+    match direct {
+        DirectExpr::Int { value } => Value::Int(*value),
+        DirectExpr::Bool { value } => Value::Bool(*value),
+        DirectExpr::Float { value } => Value::Float(*value),
+        DirectExpr::Id { value, .. } => environment.get(value),
+    }
+}
+
+/// Interprets a unary expression on a value.
+pub fn interpret_unary_expr(kind: &UnaryExprKind, operand: Value) -> Value {
+    match (kind, operand) {
+        (UnaryExprKind::Not, Value::Bool(value)) => Value::Bool(!value),
+        (UnaryExprKind::Negative, Value::Int(value)) => Value::Int(value.wrapping_neg()),
+        (UnaryExprKind::Negative, Value::Float(value)) => Value::Float(0.0 - value),
+        _ => internal_compiler_error("invalid unary expression"),
+    }
+}
+
+/// Interprets a binary expression on two values of the same type.
+///
+/// # Errors
+/// Returns a `RuntimeError` if the expression is an integer division (or modulo) that would crash at runtime.
+pub fn interpret_binary_expr(kind: &BinaryExprKind, operand_1: Value, operand_2: Value) -> Result<Value, RuntimeError> {
+    match (operand_1, operand_2) {
+        (Value::Int(value_1), Value::Int(value_2)) => interpret_binary_expr_int(kind, value_1, value_2),
+        (Value::Bool(value_1), Value::Bool(value_2)) => {
+            interpret_binary_expr_int(kind, i64::from(value_1), i64::from(value_2))
+        }
+        (Value::Float(value_1), Value::Float(value_2)) => Ok(interpret_binary_expr_float(kind, value_1, value_2)),
+        _ => internal_compiler_error("operand type mismatch"),
+    }
+}
+
+// Interprets a binary expression, where the operands are ints (or bools).
+fn interpret_binary_expr_int(kind: &BinaryExprKind, value_1: i64, value_2: i64) -> Result<Value, RuntimeError> {
+    let checked_division = |operation: fn(i64, i64) -> Option<i64>| {
+        if value_2 == 0 {
+            Err(RuntimeError::DivisionByZero)
+        } else {
+            operation(value_1, value_2)
+                .map(Value::Int)
+                .ok_or(RuntimeError::DivisionOverflow)
+        }
+    };
+
+    match kind {
+        BinaryExprKind::Plus => Ok(Value::Int(value_1.wrapping_add(value_2))),
+        BinaryExprKind::Minus => Ok(Value::Int(value_1.wrapping_sub(value_2))),
+        BinaryExprKind::Times => Ok(Value::Int(value_1.wrapping_mul(value_2))),
+        BinaryExprKind::Divide => checked_division(i64::checked_div),
+        BinaryExprKind::Mod => checked_division(i64::checked_rem),
+        BinaryExprKind::LessThan => Ok(Value::Bool(value_1 < value_2)),
+        BinaryExprKind::LessThanOrEquals => Ok(Value::Bool(value_1 <= value_2)),
+        BinaryExprKind::MoreThan => Ok(Value::Bool(value_1 > value_2)),
+        BinaryExprKind::MoreThanOrEquals => Ok(Value::Bool(value_1 >= value_2)),
+        BinaryExprKind::EqualsEquals => Ok(Value::Bool(value_1 == value_2)),
+        BinaryExprKind::NotEquals => Ok(Value::Bool(value_1 != value_2)),
+    }
+}
+
+// Interprets a binary expression, where the operands are floats. Comparisons match the predicates of `cmpsd`.
+#[allow(clippy::float_cmp)]
+fn interpret_binary_expr_float(kind: &BinaryExprKind, value_1: f64, value_2: f64) -> Value {
+    let is_unordered = value_1.is_nan() || value_2.is_nan();
+
+    match kind {
+        BinaryExprKind::Plus => Value::Float(value_1 + value_2),
+        BinaryExprKind::Minus => Value::Float(value_1 - value_2),
+        BinaryExprKind::Times => Value::Float(value_1 * value_2),
+        BinaryExprKind::Divide => Value::Float(value_1 / value_2),
+        BinaryExprKind::LessThan => Value::Bool(value_1 < value_2),
+        BinaryExprKind::LessThanOrEquals => Value::Bool(value_1 <= value_2),
+        BinaryExprKind::MoreThan => Value::Bool(value_1 > value_2 || is_unordered),
+        BinaryExprKind::MoreThanOrEquals => Value::Bool(value_1 >= value_2 || is_unordered),
+        BinaryExprKind::EqualsEquals => Value::Bool(value_1 == value_2),
+        BinaryExprKind::NotEquals => Value::Bool(value_1 != value_2),
+        BinaryExprKind::Mod => internal_compiler_error("invalid binary expression"),
+    }
+}
@@ -0,0 +1,5 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The interpreter module executes the intermediate representation directly, without creating an executable.
+
+pub mod interpreter;
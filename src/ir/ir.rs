@@ -52,19 +52,24 @@ pub struct Program {
     pub body: Block,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Function {
     pub id: String,
     pub params: Vec<String>,
+    pub param_types: Vec<Type>,
+    pub return_type: Type,
     pub body: Block,
+
+    /// Whether the function should be inlined into its callers (see `inliner.rs`).
+    pub inline: Inline,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Block {
     pub exprs: Vec<Expr>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Expr {
     Direct {
         expr: DirectExpr,
@@ -72,6 +77,10 @@ pub enum Expr {
     Let {
         id: String,
         init_expr: Box<Expr>,
+
+        /// The line of the source that the binding was translated from, if any. This is used for debug info (see
+        /// `debug_info.rs`), and is not part of the textual IR format.
+        line: Option<usize>,
     },
     If {
         condition: Box<DirectExpr>,
@@ -106,7 +115,7 @@ pub enum Expr {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum DirectExpr {
     Int { value: i64 },
     Bool { value: bool },
@@ -114,6 +123,13 @@ pub enum DirectExpr {
     Id { value: String, id_type: Type },
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Inline {
+    Auto,
+    Always,
+    Never,
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum Type {
     Unit,
@@ -122,13 +138,13 @@ pub enum Type {
     Float,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum UnaryExprKind {
     Not,
     Negative,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BinaryExprKind {
     Plus,
     Minus,
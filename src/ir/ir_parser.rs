@@ -0,0 +1,368 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Parses the textual IR format (see `ir_printer.rs` for the syntax) back into a `ir::Program`.
+//!
+//! This allows IR to be written by hand, so that the later stages of the compiler (like the register allocator) can be
+//! driven directly.
+//!
+//! Since the format is printed by the compiler, the parser is a simple recursive decent parser that works on the words
+//! and punctuation of the text. Whitespace is insignificant, and `#` starts a comment that goes to the end of the line.
+//! Note that the IR is not type checked: the types of the text are trusted, just like the output of the translator.
+
+use error_messages::compilation_error;
+use ir::ir::{BinaryExprKind, Block, DirectExpr, Expr, Function, Inline, Program, Type, UnaryExprKind};
+use register_allocation::register_allocator::Set;
+use std::cell::RefCell;
+use std::ops::Range;
+use File;
+
+// Characters that are their own token.
+const PUNCTUATION: [char; 7] = ['{', '}', '(', ')', ',', ':', '='];
+
+/// Parses a file in the textual IR format into a `ir::Program`.
+pub fn parse_ir(file: &File) -> Program {
+    let mut parser = IrParser { file, tokens: tokenize_ir(&file.contents), index: 0 };
+    let mut functions = vec![];
+    let mut exprs = vec![];
+
+    while parser.peek(0).is_some() {
+        if matches!(parser.peek(0), Some("fun" | "@inline" | "@noinline")) {
+            functions.push(parser.parse_function());
+        } else {
+            exprs.push(parser.parse_expr());
+        }
+    }
+
+    Program { functions, body: Block { exprs } }
+}
+
+// A word or punctuation of the textual IR.
+struct IrToken<'a> {
+    text: &'a str,
+    position: Range<usize>,
+}
+
+// Splits the text into tokens, where each token is either a punctuation character or a word (characters up until
+// whitespace or punctuation).
+fn tokenize_ir(contents: &str) -> Vec<IrToken<'_>> {
+    let mut tokens = vec![];
+    let mut chars = contents.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c == '#' {
+            while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+        } else if PUNCTUATION.contains(&c) {
+            tokens.push(IrToken { text: &contents[start..=start], position: start..start + 1 });
+        } else if !c.is_whitespace() {
+            let mut end = start + c.len_utf8();
+
+            while let Some((i, c)) = chars.next_if(|(_, c)| !c.is_whitespace() && !PUNCTUATION.contains(c) && *c != '#')
+            {
+                end = i + c.len_utf8();
+            }
+            tokens.push(IrToken { text: &contents[start..end], position: start..end });
+        }
+    }
+
+    tokens
+}
+
+// Cursor over the tokens, where each method corresponds to a production of the textual IR.
+struct IrParser<'a> {
+    file: &'a File,
+    tokens: Vec<IrToken<'a>>,
+    index: usize,
+}
+
+impl<'a> IrParser<'a> {
+    // Parses `[@inline|@noinline] fun <id>(<params>) { ... }` into a `ir::Function`.
+    fn parse_function(&mut self) -> Function {
+        let inline = match self.peek(0) {
+            Some("@inline") => Inline::Always,
+            Some("@noinline") => Inline::Never,
+            _ => Inline::Auto,
+        };
+        if inline != Inline::Auto {
+            self.index += 1;
+        }
+
+        self.consume("fun");
+        let id = self.parse_id();
+        self.consume("(");
+
+        let mut params = vec![];
+        let mut param_types = vec![];
+        while self.peek(0) != Some(")") {
+            if !params.is_empty() {
+                self.consume(",");
+            }
+            params.push(self.parse_id());
+            param_types.push(self.parse_type_annotation());
+        }
+        self.consume(")");
+        let return_type = self.parse_type_annotation();
+
+        Function {
+            id,
+            params,
+            param_types,
+            return_type,
+            body: self.parse_nested_block(),
+            inline,
+        }
+    }
+
+    // Parses `{ <exprs> }` into a `ir::Block`.
+    fn parse_nested_block(&mut self) -> Block {
+        self.consume("{");
+
+        let mut exprs = vec![];
+        while self.peek(0) != Some("}") {
+            exprs.push(self.parse_expr());
+        }
+        self.consume("}");
+
+        Block { exprs }
+    }
+
+    // Parses an expression into a `ir::Expr`.
+    fn parse_expr(&mut self) -> Expr {
+        let token = self.next();
+        let text = token.text;
+        let position = token.position.clone();
+
+        // Keywords that are followed by a `:` are identifiers of directs, like `call: int`.
+        match (text, self.peek(0)) {
+            ("let", _) => {
+                let id = self.parse_id();
+                self.consume("=");
+                Expr::Let { id, init_expr: Box::new(self.parse_expr()), line: None }
+            }
+            ("if", _) => {
+                let condition = Box::new(self.parse_direct(Some(Type::Bool)));
+                let then_block = self.parse_nested_block();
+
+                let else_block = if self.peek(0) == Some("else") {
+                    self.consume("else");
+                    Some(self.parse_nested_block())
+                } else {
+                    None
+                };
+                Expr::If { condition, then_block, else_block }
+            }
+            ("call", Some(next_text)) if next_text != ":" => self.parse_call(),
+            _ if text.contains('.') && text.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                self.parse_operation(text, &position)
+            }
+            _ => {
+                self.index -= 1;
+                Expr::Direct { expr: self.parse_direct(None) }
+            }
+        }
+    }
+
+    // Parses `<operator>.<type> <operands>` (or `coerce.<type>.<type> <operand>`), where `text` is the first word.
+    fn parse_operation(&mut self, text: &str, position: &Range<usize>) -> Expr {
+        let parts: Vec<&str> = text.split('.').collect();
+
+        match parts.as_slice() {
+            ["coerce", from_type, to_type] => {
+                let from_type = self.to_type(from_type, position);
+                let to_type = self.to_type(to_type, position);
+
+                Expr::TypeCoercion {
+                    expr: Box::new(self.parse_direct(Some(from_type.clone()))),
+                    from_type,
+                    to_type,
+                }
+            }
+            [operator, operand_type] => {
+                let operand_type = self.to_type(operand_type, position);
+
+                if let Some(kind) = to_unary_operator(operator) {
+                    let operand = Box::new(self.parse_direct(Some(operand_type.clone())));
+                    Expr::UnaryExpr { kind, operand, operand_type }
+                } else if let Some(kind) = to_binary_operator(operator) {
+                    let operand_1 = Box::new(self.parse_direct(Some(operand_type.clone())));
+                    self.consume(",");
+                    let operand_2 = Box::new(self.parse_direct(Some(operand_type.clone())));
+                    Expr::BinaryExpr { kind, operand_1, operand_2, operand_type }
+                } else {
+                    self.error(position, &format!("Syntax Error: unknown operator `{operator}`"))
+                }
+            }
+            _ => self.error(position, &format!("Syntax Error: unknown operator `{text}`")),
+        }
+    }
+
+    // Parses `<id>(<args>) live(<ids>)` (after `call`), where `live(...)` is optional.
+    fn parse_call(&mut self) -> Expr {
+        let id = self.parse_id();
+        self.consume("(");
+
+        let mut args = vec![];
+        while self.peek(0) != Some(")") {
+            if !args.is_empty() {
+                self.consume(",");
+            }
+            args.push(self.parse_direct(None));
+        }
+        self.consume(")");
+
+        let mut live_variables = Set::new();
+        if self.peek(0) == Some("live") && self.peek(1) == Some("(") {
+            self.consume("live");
+            self.consume("(");
+
+            while self.peek(0) != Some(")") {
+                if !live_variables.is_empty() {
+                    self.consume(",");
+                }
+                live_variables.insert(self.parse_id());
+            }
+            self.consume(")");
+        }
+
+        Expr::Call { id, args, live_variables: RefCell::new(live_variables) }
+    }
+
+    // Parses a literal or `<id>: <type>` into a `ir::DirectExpr`. The type of identifiers can be omitted if there is an
+    // `implied_type`.
+    fn parse_direct(&mut self, implied_type: Option<Type>) -> DirectExpr {
+        let token = self.next();
+        let text = token.text;
+        let position = token.position.clone();
+
+        match text {
+            "true" => DirectExpr::Bool { value: true },
+            "false" => DirectExpr::Bool { value: false },
+            _ if text.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+' || c == '.') => {
+                if text.contains(['.', 'e', 'E', 'i', 'N']) {
+                    match text.parse() {
+                        Ok(value) => DirectExpr::Float { value },
+                        Err(_) => self.error(&position, "Syntax Error: invalid float"),
+                    }
+                } else {
+                    match text.parse() {
+                        Ok(value) => DirectExpr::Int { value },
+                        Err(_) => self.error(&position, "Syntax Error: invalid int"),
+                    }
+                }
+            }
+            _ => {
+                self.index -= 1;
+                let value = self.parse_id();
+
+                let id_type = if self.peek(0) == Some(":") {
+                    self.parse_type_annotation()
+                } else {
+                    implied_type
+                        .unwrap_or_else(|| self.error(&position, "Syntax Error: expected the type of identifier"))
+                };
+
+                DirectExpr::Id { value, id_type }
+            }
+        }
+    }
+
+    // Parses an identifier, where the `%` sigil of temporary variables is converted back to `@`.
+    fn parse_id(&mut self) -> String {
+        let token = self.next();
+        let (id, is_temp) = token
+            .text
+            .strip_prefix('%')
+            .map_or((token.text, false), |temp_id| (temp_id, true));
+
+        let is_valid = id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        if !is_valid {
+            let position = token.position.clone();
+            self.error(&position, "Syntax Error: unexpected token")
+        }
+
+        if is_temp {
+            format!("@{id}")
+        } else {
+            id.to_string()
+        }
+    }
+
+    // Parses `: <type>` into a `ir::Type`.
+    fn parse_type_annotation(&mut self) -> Type {
+        self.consume(":");
+
+        let token = self.next();
+        let (text, position) = (token.text, token.position.clone());
+        self.to_type(text, &position)
+    }
+
+    // Converts the name of a type into a `ir::Type`.
+    fn to_type(&self, text: &str, position: &Range<usize>) -> Type {
+        match text {
+            "unit" => Type::Unit,
+            "int" => Type::Int,
+            "bool" => Type::Bool,
+            "float" => Type::Float,
+            _ => self.error(position, &format!("Invalid type: {text}")),
+        }
+    }
+
+    // Advances to the next token, and ensures that it is `text`.
+    fn consume(&mut self, text: &str) {
+        let token = self.next();
+
+        if token.text != text {
+            let position = token.position.clone();
+            self.error(&position, &format!("Syntax Error: expected `{text}`"))
+        }
+    }
+
+    // Advances to the next token, raising a compilation error if there are no more tokens.
+    fn next(&mut self) -> &IrToken<'a> {
+        if self.index >= self.tokens.len() {
+            let position = self.tokens.last().map_or(0..0, |token| token.position.clone());
+            self.error(&position, "Syntax Error: unexpected end of file")
+        }
+
+        self.index += 1;
+        &self.tokens[self.index - 1]
+    }
+
+    // Gets the text of the token that is `offset` tokens ahead, without advancing.
+    fn peek(&self, offset: usize) -> Option<&'a str> {
+        self.tokens.get(self.index + offset).map(|token| token.text)
+    }
+
+    // Raises a compilation error at `position` of the textual IR.
+    fn error(&self, position: &Range<usize>, message: &str) -> ! {
+        compilation_error(self.file, position, message)
+    }
+}
+
+// Converts the name of a unary operator into a `ir::UnaryExprKind`.
+fn to_unary_operator(name: &str) -> Option<UnaryExprKind> {
+    match name {
+        "not" => Some(UnaryExprKind::Not),
+        "neg" => Some(UnaryExprKind::Negative),
+        _ => None,
+    }
+}
+
+// Converts the name of a binary operator into a `ir::BinaryExprKind`.
+fn to_binary_operator(name: &str) -> Option<BinaryExprKind> {
+    match name {
+        "add" => Some(BinaryExprKind::Plus),
+        "sub" => Some(BinaryExprKind::Minus),
+        "mul" => Some(BinaryExprKind::Times),
+        "div" => Some(BinaryExprKind::Divide),
+        "mod" => Some(BinaryExprKind::Mod),
+        "lt" => Some(BinaryExprKind::LessThan),
+        "le" => Some(BinaryExprKind::LessThanOrEquals),
+        "gt" => Some(BinaryExprKind::MoreThan),
+        "ge" => Some(BinaryExprKind::MoreThanOrEquals),
+        "eq" => Some(BinaryExprKind::EqualsEquals),
+        "ne" => Some(BinaryExprKind::NotEquals),
+        _ => None,
+    }
+}
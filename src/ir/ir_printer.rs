@@ -0,0 +1,214 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Prints the IR in a compact, human-readable textual format.
+//!
+//! The format can be read back with `ir_parser.rs`, which means that IR can be written by hand (for example, to test
+//! the register allocator and compiler directly).
+//!
+//! Each expression of a block is on its own line. For example:
+//! ```text
+//! fun fib(n: int): int {
+//!   let %temp0 = le.int n, 1
+//!   if %temp0 {
+//!     1
+//!   } else {
+//!     let %temp1 = sub.int n, 1
+//!     let %temp2 = call fib(%temp1: int)
+//!     ...
+//!   }
+//! }
+//!
+//! let a = call fib(10) live(b)
+//! let %temp3 = coerce.int.float a
+//! a: int
+//! ```
+//! The syntax of each kind of expression is:
+//!   - directs: literals are printed as is (`1`, `true`, `2.5`), and identifiers are followed by their type (`a: int`),
+//!     unless the type is implied by the expression that they are an operand of. Temporary variables of the translator
+//!     (`@temp0`) are printed with a `%` sigil instead (`%temp0`). Floats that are not finite are signed (`+inf`,
+//!     `-inf`, `+NaN`), so they can't be mistaken for identifiers.
+//!   - `let <id> = <expr>`
+//!   - `if <condition> { ... } else { ... }`, where the else block is optional.
+//!   - unary and binary expressions: `<operator>.<operand type>`, like `neg.int a` or `add.float a, b`.
+//!   - type coercions: `coerce.<from type>.<to type> a`.
+//!   - calls: `call <id>(<args>) live(<live variables>)`, where `live(...)` is omitted if there are no live variables.
+//!
+//! Functions are printed with the types of their parameters and their return type, like the source. Functions that are
+//! annotated with `@inline` or `@noinline` are printed with their annotation before `fun`.
+//!
+//! Types are written as `unit`, `int`, `bool`, and `float`.
+
+use ir::ir::{BinaryExprKind, Block, DirectExpr, Expr, Inline, Program, Type, UnaryExprKind};
+
+// Number of spaces that each nested block is indented by.
+const INDENT_SIZE: usize = 2;
+
+/// Prints a `ir::Program` in the textual IR format.
+pub fn print_program(program: &Program) -> String {
+    let functions = program.functions.iter().fold(String::new(), |acc, function| {
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .zip(&function.param_types)
+            .map(|(param, param_type)| format!("{}: {}", print_id(param), type_name(param_type)))
+            .collect();
+        let annotation = match function.inline {
+            Inline::Auto => "",
+            Inline::Always => "@inline ",
+            Inline::Never => "@noinline ",
+        };
+
+        acc + &format!(
+            "{annotation}fun {}({}): {} {}\n\n",
+            function.id,
+            params.join(", "),
+            type_name(&function.return_type),
+            print_nested_block(&function.body, 0)
+        )
+    });
+
+    functions + &print_block(&program.body)
+}
+
+/// Prints a top-level `ir::Block` in the textual IR format (with one expression per line, without braces).
+pub fn print_block(block: &Block) -> String {
+    print_exprs(block, 0)
+}
+
+// Prints each expression of a block on its own line, indented by `indent` levels.
+fn print_exprs(block: &Block, indent: usize) -> String {
+    block.exprs.iter().fold(String::new(), |acc, expr| {
+        acc + &format!("{}{}\n", " ".repeat(indent * INDENT_SIZE), print_expr(expr, indent))
+    })
+}
+
+// Prints a block that is nested in an expression (like the then block of an if), where the block is indented by
+// `indent` levels.
+fn print_nested_block(block: &Block, indent: usize) -> String {
+    if block.exprs.is_empty() {
+        "{}".to_string()
+    } else {
+        format!(
+            "{{\n{}{}}}",
+            print_exprs(block, indent + 1),
+            " ".repeat(indent * INDENT_SIZE)
+        )
+    }
+}
+
+// Prints an expression, where `indent` is the indentation level of the line that the expression starts on.
+fn print_expr(expr: &Expr, indent: usize) -> String {
+    match expr {
+        Expr::Direct { expr } => print_direct(expr, None),
+        Expr::Let { id, init_expr, .. } => format!("let {} = {}", print_id(id), print_expr(init_expr, indent)),
+        Expr::If { condition, then_block, else_block } => {
+            let if_expr = format!(
+                "if {} {}",
+                print_direct(condition, Some(&Type::Bool)),
+                print_nested_block(then_block, indent)
+            );
+
+            match else_block {
+                Some(else_block) => format!("{if_expr} else {}", print_nested_block(else_block, indent)),
+                None => if_expr,
+            }
+        }
+        Expr::UnaryExpr { kind, operand, operand_type } => format!(
+            "{}.{} {}",
+            unary_operator_name(kind),
+            type_name(operand_type),
+            print_direct(operand, Some(operand_type))
+        ),
+        Expr::BinaryExpr { kind, operand_1, operand_2, operand_type } => format!(
+            "{}.{} {}, {}",
+            binary_operator_name(kind),
+            type_name(operand_type),
+            print_direct(operand_1, Some(operand_type)),
+            print_direct(operand_2, Some(operand_type))
+        ),
+        Expr::Call { id, args, live_variables } => {
+            let args: Vec<String> = args.iter().map(|arg| print_direct(arg, None)).collect();
+            let call_expr = format!("call {id}({})", args.join(", "));
+
+            let mut live_variables: Vec<String> = live_variables.borrow().iter().map(|id| print_id(id)).collect();
+            live_variables.sort();
+
+            if live_variables.is_empty() {
+                call_expr
+            } else {
+                format!("{call_expr} live({})", live_variables.join(", "))
+            }
+        }
+        Expr::TypeCoercion { expr, from_type, to_type } => format!(
+            "coerce.{}.{} {}",
+            type_name(from_type),
+            type_name(to_type),
+            print_direct(expr, Some(from_type))
+        ),
+    }
+}
+
+// Prints a direct, where `implied_type` is the type that identifiers are implied to be, if any.
+fn print_direct(direct: &DirectExpr, implied_type: Option<&Type>) -> String {
+    match direct {
+        DirectExpr::Int { value } => value.to_string(),
+        DirectExpr::Bool { value } => value.to_string(),
+        DirectExpr::Float { value } => {
+            if value.is_nan() {
+                "+NaN".to_string()
+            } else if value.is_infinite() {
+                format!("{value:+?}")
+            } else {
+                format!("{value:?}")
+            }
+        }
+        DirectExpr::Id { value, id_type } => {
+            if implied_type == Some(id_type) {
+                print_id(value)
+            } else {
+                format!("{}: {}", print_id(value), type_name(id_type))
+            }
+        }
+    }
+}
+
+// Prints an identifier, where temporary variables (`@temp0`) are printed with a `%` sigil (`%temp0`).
+fn print_id(id: &str) -> String {
+    id.strip_prefix('@')
+        .map_or_else(|| id.to_string(), |temp_id| format!("%{temp_id}"))
+}
+
+/// The name of a type in the textual IR format.
+pub const fn type_name(ir_type: &Type) -> &'static str {
+    match ir_type {
+        Type::Unit => "unit",
+        Type::Int => "int",
+        Type::Bool => "bool",
+        Type::Float => "float",
+    }
+}
+
+/// The name of a unary operator in the textual IR format.
+pub const fn unary_operator_name(kind: &UnaryExprKind) -> &'static str {
+    match kind {
+        UnaryExprKind::Not => "not",
+        UnaryExprKind::Negative => "neg",
+    }
+}
+
+/// The name of a binary operator in the textual IR format.
+pub const fn binary_operator_name(kind: &BinaryExprKind) -> &'static str {
+    match kind {
+        BinaryExprKind::Plus => "add",
+        BinaryExprKind::Minus => "sub",
+        BinaryExprKind::Times => "mul",
+        BinaryExprKind::Divide => "div",
+        BinaryExprKind::Mod => "mod",
+        BinaryExprKind::LessThan => "lt",
+        BinaryExprKind::LessThanOrEquals => "le",
+        BinaryExprKind::MoreThan => "gt",
+        BinaryExprKind::MoreThanOrEquals => "ge",
+        BinaryExprKind::EqualsEquals => "eq",
+        BinaryExprKind::NotEquals => "ne",
+    }
+}
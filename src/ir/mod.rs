@@ -3,5 +3,8 @@
 //! The IR module is responsible for lowering the AST into the intermediate representation.
 
 pub mod ir;
+pub mod ir_parser;
+pub mod ir_printer;
 pub mod translator;
 pub mod type_checker;
+pub mod type_inference;
@@ -13,6 +13,7 @@ use ir::type_checker::TypeChecker;
 use parser::ast;
 use register_allocation::register_allocator::Set;
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use File;
 
 /// Translates a `ast::Program` into a `ir::Program`
@@ -58,7 +59,18 @@ fn translate_function(type_checker: &mut TypeChecker, function: &ast::Function)
     ir::Function {
         id: function.id.to_string(),
         params: function.params.iter().map(|p| p.id.to_string()).collect(),
+        param_types: function
+            .params
+            .iter()
+            .map(|p| ast_type_to_ir_type(&p.type_reference))
+            .collect(),
+        return_type: ast_type_to_ir_type(&function.return_type),
         body,
+        inline: match function.inline {
+            ast::Inline::Auto => ir::Inline::Auto,
+            ast::Inline::Always => ir::Inline::Always,
+            ast::Inline::Never => ir::Inline::Never,
+        },
     }
 }
 
@@ -86,6 +98,27 @@ fn translate_block(type_checker: &mut TypeChecker, block: &ast::Block) -> (ir::B
 // Translates a `ast::Expr` into a `ir::Expr`
 // * bindings - where to put additional bindings that are needed to translate the expression (temporary let-bindings)
 fn translate_expr(expr: &ast::Expr, type_checker: &mut TypeChecker, bindings: &mut Vec<ir::Expr>) -> (ir::Expr, Type) {
+    let first_binding = bindings.len();
+    let result = translate_expr_kind(expr, type_checker, bindings);
+
+    // The bindings of the expression are on the line of the expression, except for the bindings of sub expressions,
+    // which were given the line of the sub expression first.
+    let line = type_checker.line(&expr.position);
+    for binding in &mut bindings[first_binding..] {
+        if let ir::Expr::Let { line: binding_line @ None, .. } = binding {
+            *binding_line = Some(line);
+        }
+    }
+
+    result
+}
+
+// Translates the kind of a `ast::Expr` into a `ir::Expr` (see `translate_expr`).
+fn translate_expr_kind(
+    expr: &ast::Expr,
+    type_checker: &mut TypeChecker,
+    bindings: &mut Vec<ir::Expr>,
+) -> (ir::Expr, Type) {
     match &expr.kind {
         ast::ExprKind::Id { value } => {
             let id_type = type_checker.get_declared_variable_type(value, &expr.position);
@@ -96,25 +129,31 @@ fn translate_expr(expr: &ast::Expr, type_checker: &mut TypeChecker, bindings: &m
                 id_type,
             )
         }
-        ast::ExprKind::Int { value } => (
-            ir::Expr::Direct { expr: ir::DirectExpr::Int { value: *value } },
-            Type::Int,
-        ),
+        ast::ExprKind::Int { value } => {
+            let int_expr = ir::Expr::Direct { expr: ir::DirectExpr::Int { value: *value } };
+
+            // Most x86 instructions only have 32 bit immediates, so ints that don't fit in 32 bits are made into a
+            // variable binding.
+            if i32::try_from(*value).is_ok() {
+                (int_expr, Type::Int)
+            } else {
+                (
+                    ir::Expr::Direct { expr: to_binding(int_expr, Type::Int, bindings) },
+                    Type::Int,
+                )
+            }
+        }
         ast::ExprKind::Bool { value } => (
             ir::Expr::Direct { expr: ir::DirectExpr::Bool { value: *value } },
             Type::Bool,
         ),
-        ast::ExprKind::Float { value } => {
-            let float_expr = ir::Expr::Direct { expr: ir::DirectExpr::Float { value: *value } };
-            (
-                // There are no such things as float immediates for x86. Instead, we must make each float a variable
-                // binding (in the compiler, there must be a `Location` for floats). For example `let a: float = 1.2 + 2.3`
-                // should translate to `let temp1 = 1.2; let temp2 = 2.3; let a = temp1 + temp2`.
-                ir::Expr::Direct { expr: to_binding(float_expr, Type::Float, bindings) },
-                Type::Float,
-            )
-        }
-        ast::ExprKind::Let { id, init_expr, type_reference } => {
+        // There are no such things as float immediates for x86, so floats are read from a constant pool instead (see
+        // `compiler.rs`), which means that they can be used as operands like ints.
+        ast::ExprKind::Float { value } => (
+            ir::Expr::Direct { expr: ir::DirectExpr::Float { value: *value } },
+            Type::Float,
+        ),
+        ast::ExprKind::Let { id, init_expr, type_reference, .. } => {
             let type_reference = ast_type_to_ir_type(type_reference);
 
             type_checker.register_variable_being_declared(id, type_reference.clone(), &expr.position);
@@ -122,7 +161,7 @@ fn translate_expr(expr: &ast::Expr, type_checker: &mut TypeChecker, bindings: &m
             type_checker.type_check_let(id, init_type.clone(), type_reference, &expr.position);
 
             // Flatten out let bindings inside sub expressions as well.
-            bindings.push(ir::Expr::Let { id: id.clone(), init_expr: Box::new(init_expr) });
+            bindings.push(ir::Expr::Let { id: id.clone(), init_expr: Box::new(init_expr), line: None });
             (
                 ir::Expr::Direct { expr: ir::DirectExpr::Id { value: id.to_string(), id_type: init_type } },
                 Type::Unit,
@@ -265,7 +304,11 @@ fn coerce_type(
             from_type: expr_type,
             to_type: expr_coercion.clone(),
         };
-        bindings.push(ir::Expr::Let { id: direct_identifier.to_string(), init_expr: Box::new(init_expr) });
+        bindings.push(ir::Expr::Let {
+            id: direct_identifier.to_string(),
+            init_expr: Box::new(init_expr),
+            line: None,
+        });
 
         (
             ir::DirectExpr::Id { value: direct_identifier, id_type: expr_coercion.clone() },
@@ -289,7 +332,11 @@ fn to_direct(expr: ir::Expr, expr_type: Type, bindings: &mut Vec<ir::Expr>) -> i
 // Translates a `ir::Expr` into `ir::DirectExpr::Id` by adding a temporary let-binding.
 fn to_binding(expr: ir::Expr, expr_type: Type, bindings: &mut Vec<ir::Expr>) -> ir::DirectExpr {
     let direct_identifier = gen_temp_identifier();
-    bindings.push(ir::Expr::Let { id: direct_identifier.to_string(), init_expr: Box::new(expr) });
+    bindings.push(ir::Expr::Let {
+        id: direct_identifier.to_string(),
+        init_expr: Box::new(expr),
+        line: None,
+    });
     ir::DirectExpr::Id { value: direct_identifier, id_type: expr_type }
 }
 
@@ -309,6 +356,11 @@ fn gen_temp_identifier() -> String {
     format!("@temp{tag_value}")
 }
 
+/// Whether a variable is a temporary variable that was created by the translator (see `gen_temp_identifier`).
+pub fn is_temp_identifier(id: &str) -> bool {
+    id.starts_with("@temp")
+}
+
 /// Converts a `ast::Type` to the corresponding `ir::Type`.
 const fn ast_type_to_ir_type(ast_type: &ast::Type) -> ir::Type {
     match ast_type {
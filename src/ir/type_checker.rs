@@ -12,7 +12,9 @@
 use error_messages::{compilation_error, internal_compiler_error};
 use ir::ir::{self, Type};
 use std::collections::HashMap;
+use std::iter;
 use std::ops::Range;
+use std::rc::Rc;
 use File;
 
 /// Type Checker for each scope of the program.
@@ -32,13 +34,25 @@ pub struct TypeChecker<'a> {
 
     /// The original Solis input file, for error messaging purposes.
     pub file: &'a File,
+
+    /// The offset of the start of each line of `file`, to find the line of a position (see `line`).
+    line_starts: Rc<Vec<usize>>,
 }
 
 impl<'a> TypeChecker<'a> {
     /// Type Checker constructor.
     /// * file: the original Solis file
     pub fn new(file: &'a File) -> Self {
-        TypeChecker { file, identifier_types: HashMap::new(), functions: HashMap::new() }
+        let line_starts = iter::once(0)
+            .chain(file.contents.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        TypeChecker {
+            file,
+            identifier_types: HashMap::new(),
+            functions: HashMap::new(),
+            line_starts: Rc::new(line_starts),
+        }
     }
 
     /// Constructs a `TypeChecker` from another `TypeChecker`, with the `identifier_types` cloned
@@ -47,9 +61,16 @@ impl<'a> TypeChecker<'a> {
             file: type_checker.file,
             identifier_types: type_checker.identifier_types.clone(),
             functions: type_checker.functions.clone(),
+            line_starts: Rc::clone(&type_checker.line_starts),
         }
     }
 
+    /// The line (starting at 1) of `file` that a position is on.
+    pub fn line(&self, position: &Range<usize>) -> usize {
+        self.line_starts
+            .partition_point(|line_start| *line_start <= position.start)
+    }
+
     /// Type checks a let expression.
     pub fn type_check_let(&mut self, id: &String, init_expr_type: Type, type_reference: Type, position: &Range<usize>) {
         if type_reference != init_expr_type {
@@ -0,0 +1,80 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Finds the types of the expressions of a program, which the IR only records for operands.
+//!
+//! The backends need the types of variables, like the C backend (see `c_writer.rs`) to declare them and the debug info
+//! (see `debug_info.rs`) to describe them. The type of an expression is found from its operands, and calls have the
+//! return type of the function, which is recorded in the IR (see `ir::Function`).
+
+use ir::ir::{BinaryExprKind, Block, DirectExpr, Expr, Program, Type, UnaryExprKind};
+use register_allocation::register_allocator::Map;
+
+/// The types of the expressions of a program.
+pub struct ProgramTypes<'a> {
+    /// Maps each function to its return type.
+    pub return_types: Map<&'a String, &'a Type>,
+}
+
+impl<'a> ProgramTypes<'a> {
+    /// Collects the return types of the functions of a program, which are the types of calls.
+    pub fn new(program: &'a Program) -> Self {
+        ProgramTypes {
+            return_types: program
+                .functions
+                .iter()
+                .map(|function| (&function.id, &function.return_type))
+                .collect(),
+        }
+    }
+
+    /// The type that an expression evaluates to.
+    pub fn expr_type(&self, expr: &Expr) -> Type {
+        expr_type(expr, &self.return_types)
+    }
+
+    /// The type that a block evaluates to.
+    pub fn block_type(&self, block: &Block) -> Type {
+        block_type(block, &self.return_types)
+    }
+}
+
+// The type of a direct.
+fn direct_type(direct: &DirectExpr) -> Type {
+    match direct {
+        DirectExpr::Int { .. } => Type::Int,
+        DirectExpr::Bool { .. } => Type::Bool,
+        DirectExpr::Float { .. } => Type::Float,
+        DirectExpr::Id { id_type, .. } => id_type.clone(),
+    }
+}
+
+// The type that a block evaluates to.
+fn block_type(block: &Block, return_types: &Map<&String, &Type>) -> Type {
+    block
+        .exprs
+        .last()
+        .map_or(Type::Unit, |expr| expr_type(expr, return_types))
+}
+
+// The type that an expression evaluates to.
+fn expr_type(expr: &Expr, return_types: &Map<&String, &Type>) -> Type {
+    match expr {
+        Expr::Direct { expr } => direct_type(expr),
+        Expr::Let { .. } | Expr::If { else_block: None, .. } => Type::Unit,
+        Expr::If { then_block, .. } => block_type(then_block, return_types),
+        Expr::UnaryExpr { kind: UnaryExprKind::Not, .. } => Type::Bool,
+        Expr::UnaryExpr { kind: UnaryExprKind::Negative, operand_type, .. } => operand_type.clone(),
+        Expr::BinaryExpr { kind, operand_type, .. } => match kind {
+            BinaryExprKind::Plus
+            | BinaryExprKind::Minus
+            | BinaryExprKind::Times
+            | BinaryExprKind::Divide
+            | BinaryExprKind::Mod => operand_type.clone(),
+            _ => Type::Bool,
+        },
+        Expr::Call { id, .. } => return_types
+            .get(id)
+            .map_or(Type::Unit, |return_type| (*return_type).clone()),
+        Expr::TypeCoercion { to_type, .. } => to_type.clone(),
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The JIT runs the compiled program in the process of the compiler (with `--jit`), instead of creating an executable
+//! (see `bootstrapper.rs`).
+//!
+//! No files are written, and no assembler or linker is run. Specifically, we:
+//!  1. encode the instructions with the built-in assembler (see `encoder.rs`).
+//!  2. map each section into executable (text), writable (data) or read only (rodata) memory.
+//!  3. apply the relocations of the linker, where `Extern` labels are resolved to the functions of the compiler process
+//!     (with `dlsym`), through a stub in the text section that jumps to them (since they are too far for a call).
+//!  4. call `entry` directly, and print its result (like `runtime.c`).
+//!
+//! Like the executable, a program that crashes (like a division by zero) crashes the process.
+
+use asm::asm::Instruction;
+use asm::encoder::{encode, Object, RelocationKind, SectionKind};
+use error_messages::internal_compiler_error;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::mem;
+use std::ptr;
+
+// The size of a stub that jumps to an `Extern` function (`jmp [rip + 0]`, followed by the address).
+const STUB_SIZE: usize = 16;
+
+/// Runs the instructions with the JIT, and prints the result of `entry`.
+pub fn run(instructions: &[Instruction]) {
+    print!("{}", execute(instructions));
+}
+
+/// Runs the instructions with the JIT.
+/// * return - the result of `entry`.
+pub fn execute(instructions: &[Instruction]) -> i64 {
+    let object = encode(instructions);
+    let externs: Vec<&String> = object
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.definition.is_none())
+        .map(|symbol| &symbol.name)
+        .collect();
+
+    // Each section starts on a separate page, so that each section has its own protection. The stubs are after the
+    // text section.
+    let page_size = page_size();
+    let mut section_offsets = vec![];
+    let mut size = 0;
+    for section in &object.sections {
+        section_offsets.push(size);
+        let mut section_size = section.bytes.len();
+        if section.kind == SectionKind::Text {
+            section_size += STUB_SIZE * externs.len();
+        }
+        size += (section_size / page_size + 1) * page_size;
+    }
+
+    // SAFETY: the memory is a new anonymous mapping, which is only used by the JIT.
+    let memory = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            size,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANON,
+            -1,
+            0,
+        )
+    };
+    if memory == libc::MAP_FAILED {
+        internal_compiler_error("unable to map memory for the JIT")
+    }
+
+    // SAFETY: the memory is `size` bytes, which fits every section (and the stubs).
+    let memory = unsafe { std::slice::from_raw_parts_mut(memory.cast::<u8>(), size) };
+    let base = memory.as_ptr() as usize;
+
+    for (section, offset) in object.sections.iter().zip(&section_offsets) {
+        memory[*offset..*offset + section.bytes.len()].copy_from_slice(&section.bytes);
+    }
+
+    let addresses = symbol_addresses(&object, &section_offsets, base);
+    let stubs = write_stubs(&object, &section_offsets, &externs, &addresses, memory);
+    relocate(&object, &section_offsets, &addresses, &stubs, memory);
+
+    for (section, offset) in object.sections.iter().zip(&section_offsets) {
+        let protection = match section.kind {
+            SectionKind::Text => libc::PROT_READ | libc::PROT_EXEC,
+            SectionKind::Data => libc::PROT_READ | libc::PROT_WRITE,
+            SectionKind::Rodata
+            | SectionKind::DebugAbbrev
+            | SectionKind::DebugInfo
+            | SectionKind::DebugLine
+            | SectionKind::DebugStr => libc::PROT_READ,
+        };
+        let section_size = section_offsets.iter().find(|next| *next > offset).unwrap_or(&size) - offset;
+
+        // SAFETY: the section is within the mapping, and starts on a page.
+        if unsafe { libc::mprotect(memory[*offset..].as_mut_ptr().cast(), section_size, protection) } != 0 {
+            internal_compiler_error("unable to protect the memory of the JIT")
+        }
+    }
+
+    let entry = addresses
+        .get("entry")
+        .unwrap_or_else(|| internal_compiler_error("unable to find `entry`"));
+
+    // SAFETY: `entry` is the compiled program, which follows the System V ABI.
+    let result = unsafe {
+        let entry: extern "C" fn() -> i64 = mem::transmute(*entry);
+        entry()
+    };
+
+    // SAFETY: the mapping is not used after the program returns.
+    unsafe { libc::munmap(memory.as_mut_ptr().cast(), size) };
+    result
+}
+
+// The address of each symbol, where `Extern` functions are resolved in the process of the compiler.
+fn symbol_addresses(object: &Object, section_offsets: &[usize], base: usize) -> HashMap<String, usize> {
+    object
+        .symbols
+        .iter()
+        .map(|symbol| {
+            let address = if let Some((kind, offset)) = symbol.definition {
+                base + section_offset(object, section_offsets, kind) + usize::try_from(offset).unwrap()
+            } else {
+                let name = CString::new(symbol.name.clone()).unwrap();
+
+                // SAFETY: the name is a null terminated string.
+                let address = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) };
+                if address.is_null() {
+                    internal_compiler_error(&format!("unable to resolve `{}`", symbol.name))
+                }
+                address as usize
+            };
+            (symbol.name.clone(), address)
+        })
+        .collect()
+}
+
+// Writes a stub for each `Extern` function after the text section.
+// * return - the address of the stub of each `Extern` function.
+fn write_stubs(
+    object: &Object,
+    section_offsets: &[usize],
+    externs: &[&String],
+    addresses: &HashMap<String, usize>,
+    memory: &mut [u8],
+) -> HashMap<String, usize> {
+    // The text section is always the first section (see `encode`).
+    let stubs_offset = section_offsets[0] + object.sections[0].bytes.len();
+
+    externs
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let offset = stubs_offset + index * STUB_SIZE;
+            memory[offset..offset + 6].copy_from_slice(&[0xff, 0x25, 0, 0, 0, 0]);
+            memory[offset + 6..offset + 14].copy_from_slice(&addresses[*name].to_le_bytes());
+            ((*name).clone(), memory.as_ptr() as usize + offset)
+        })
+        .collect()
+}
+
+// Applies the relocations of each section, like the linker.
+fn relocate(
+    object: &Object,
+    section_offsets: &[usize],
+    addresses: &HashMap<String, usize>,
+    stubs: &HashMap<String, usize>,
+    memory: &mut [u8],
+) {
+    let base = memory.as_ptr() as usize;
+
+    for (section, section_offset) in object.sections.iter().zip(section_offsets) {
+        for relocation in &section.relocations {
+            let offset = section_offset + usize::try_from(relocation.offset).unwrap();
+            let symbol = match relocation.kind {
+                RelocationKind::Plt32 => stubs
+                    .get(&relocation.symbol)
+                    .unwrap_or_else(|| &addresses[&relocation.symbol]),
+                RelocationKind::Pc32 | RelocationKind::Absolute64 | RelocationKind::Absolute32 => {
+                    &addresses[&relocation.symbol]
+                }
+            };
+            let value = i128::try_from(*symbol).unwrap() + i128::from(relocation.addend);
+
+            match relocation.kind {
+                RelocationKind::Pc32 | RelocationKind::Plt32 => {
+                    let value = i32::try_from(value - i128::try_from(base + offset).unwrap())
+                        .unwrap_or_else(|_| internal_compiler_error(&format!("`{}` is too far", relocation.symbol)));
+                    memory[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+                }
+                RelocationKind::Absolute64 => {
+                    memory[offset..offset + 8].copy_from_slice(&u64::try_from(value).unwrap().to_le_bytes());
+                }
+                RelocationKind::Absolute32 => {
+                    memory[offset..offset + 4].copy_from_slice(&u32::try_from(value).unwrap().to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+// The offset of a section in the memory of the JIT.
+fn section_offset(object: &Object, section_offsets: &[usize], kind: SectionKind) -> usize {
+    let index = object.sections.iter().position(|section| section.kind == kind).unwrap();
+    section_offsets[index]
+}
+
+fn page_size() -> usize {
+    // SAFETY: `sysconf` has no preconditions.
+    usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).unwrap()
+}
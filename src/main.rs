@@ -7,23 +7,35 @@ extern crate clap;
 extern crate colored;
 extern crate derive_more;
 extern crate lazy_static;
+extern crate libc;
 extern crate regex;
 
 pub mod asm;
 pub mod bootstrapper;
+pub mod c_backend;
 pub mod compiler;
+pub mod emitter;
 pub mod error_messages;
+pub mod interpreter;
 pub mod ir;
+pub mod jit;
+pub mod optimizer;
 pub mod parser;
 pub mod register_allocation;
 pub mod tokenizer;
+pub mod warnings;
 
-use clap::Parser;
+use asm::asm_writer::Assembler;
+use bootstrapper::Backend;
+use clap::{Parser, ValueEnum};
 use colored::Colorize;
+use emitter::{emit, Stage};
+use register_allocation::register_allocator::RegisterAllocator;
 
 use std::fs;
 use std::path::Path;
 use std::process::exit;
+use warnings::warnings::{report_warnings, WarningConfig, WarningKind};
 
 /// Information about the source Solis file, grouped together in a single struct to pass between stages of compilation.
 pub struct File {
@@ -43,6 +55,7 @@ fn read_file(file_name: &String) -> File {
 }
 
 // Describes the schema for the CLI for Solis.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Parser)]
 #[command(author = "Brandon Li <brandon.li@berkeley.edu>", version)]
 struct CLIArgs {
@@ -61,26 +74,265 @@ struct CLIArgs {
     #[arg(short, long)]
     run: bool,
 
+    /// Use to run the program with the IR interpreter, instead of creating an executable.
+    #[arg(short, long)]
+    interpret: bool,
+
+    /// Use to run the program in the process of the compiler, instead of creating an executable.
+    #[arg(long, conflicts_with = "interpret")]
+    jit: bool,
+
     /// Use to remove the contents in DESTINATION before compiling.
     #[arg(short, long)]
     clean: bool,
+
+    /// Silences a kind of warning. Can be used multiple times.
+    #[arg(short = 'A', long = "allow", value_name = "WARNING")]
+    allow: Vec<WarningKind>,
+
+    /// Reports a kind of warning, even if it was allowed with `-A`. Can be used multiple times.
+    #[arg(short = 'W', long = "warn", value_name = "WARNING")]
+    warn: Vec<WarningKind>,
+
+    /// Use to treat reported warnings as errors.
+    #[arg(long)]
+    deny_warnings: bool,
+
+    /// Use to skip the optimizations of the IR.
+    #[arg(long)]
+    no_optimize: bool,
+
+    /// Use to report what the optimizer did.
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// The register allocator to use.
+    #[arg(long, value_name = "ALLOCATOR", default_value = "graph")]
+    regalloc: RegisterAllocator,
+
+    /// The backend to create the executable with.
+    #[arg(long, value_name = "BACKEND", default_value = "asm")]
+    backend: Backend,
+
+    /// Use to create debug info, which maps the executable to the lines of FILE (for debuggers like `gdb`).
+    #[arg(short = 'g', long)]
+    debug: bool,
+
+    /// Use to annotate the assembly with the lines of FILE, and where the variables of each line are stored.
+    #[arg(long)]
+    annotate_asm: bool,
+
+    /// The assembler to create the executable with, which is also the syntax of the emitted assembly.
+    #[arg(long, value_name = "ASSEMBLER", default_value = "nasm")]
+    assembler: Assembler,
+
+    /// Intermediate stages of compilation to print, separated by commas.
+    #[arg(long, value_name = "STAGES", value_delimiter = ',')]
+    emit: Vec<Stage>,
+
+    /// Use to write the stages of `--emit` to files in DESTINATION, instead of printing them.
+    #[arg(long, requires = "emit")]
+    emit_to_dest: bool,
+
+    /// Use to stop after emitting the stages of `--emit`, without creating an executable.
+    #[arg(long, requires = "emit")]
+    emit_only: bool,
 }
 
 pub fn main() {
     let args = CLIArgs::parse();
-    let file_name = args.file;
+    let file_name = &args.file;
     let destination = Path::new(&args.destination);
-    let file = read_file(&file_name);
+    let file = read_file(file_name);
     let name = args
         .name
+        .clone()
         .unwrap_or_else(|| Path::new(&file_name).file_stem().unwrap().to_str().unwrap().to_string());
 
+    // The built-in assembler only creates ELF object files, which can't be linked on macOS.
+    if args.assembler == Assembler::Builtin && cfg!(target_os = "macos") {
+        println!(
+            "{}: the builtin assembler is not supported on macOS",
+            "Error".red().bold()
+        );
+        exit(exitcode::USAGE)
+    }
+
+    // The JIT runs the machine code of the built-in assembler, which is x86-64.
+    if args.jit && !cfg!(target_arch = "x86_64") {
+        println!("{}: the JIT is only supported on x86-64", "Error".red().bold());
+        exit(exitcode::USAGE)
+    }
+
+    // The JIT runs the instructions of the compiler, which the C backend doesn't create.
+    if args.jit && args.backend == Backend::C {
+        println!("{}: the JIT is not supported with the C backend", "Error".red().bold());
+        exit(exitcode::USAGE)
+    }
+
+    // Debug info is only created for the executables of the asm backend.
+    if args.debug && (args.interpret || args.jit || args.backend == Backend::C) {
+        let mode = if args.interpret {
+            "the interpreter"
+        } else if args.jit {
+            "the JIT"
+        } else {
+            "the C backend"
+        };
+        println!("{}: `-g` is not supported with {mode}", "Error".red().bold());
+        exit(exitcode::USAGE)
+    }
+
+    // The stages after the IR are only created by the backend that runs (or compiles) the program.
+    for stage in &args.emit {
+        let is_created = match stage {
+            Stage::Tokens | Stage::Ast | Stage::Ir => true,
+            Stage::SpillCosts | Stage::Regalloc | Stage::Asm => !args.interpret && args.backend == Backend::Asm,
+            Stage::C => !args.interpret && args.backend == Backend::C,
+        };
+
+        if !is_created {
+            let mode = match args.backend {
+                _ if args.interpret => "the interpreter",
+                Backend::Asm => "the asm backend",
+                Backend::C => "the C backend",
+            };
+            println!(
+                "{}: `--emit {}` is not supported with {mode}",
+                "Error".red().bold(),
+                stage.to_possible_value().unwrap().get_name()
+            );
+            exit(exitcode::USAGE)
+        }
+    }
+
+    // Clean the output directory before anything is written to it.
+    if args.clean {
+        bootstrapper::clean(destination);
+    }
+
+    // Emits a stage of compilation, if it was requested with `--emit`.
+    let emit_destination = if args.emit_to_dest { Some(destination) } else { None };
+    let emit_stage = |stage: Stage, contents: &dyn Fn() -> String| {
+        if args.emit.contains(&stage) {
+            emit(stage, &contents(), emit_destination, &name);
+        }
+    };
+
+    // With `--emit-only`, compilation stops after the last stage of `--emit` is emitted.
+    let last_stage = args.emit.iter().max().copied();
+    let is_done = |stage: Stage| args.emit_only && last_stage == Some(stage);
+
     let tokens = tokenizer::tokenizer::tokenize(&file);
+    emit_stage(Stage::Tokens, &|| {
+        tokens
+            .iter()
+            .fold(String::new(), |acc, token| acc + &format!("{token:?}\n"))
+    });
+    if is_done(Stage::Tokens) {
+        return;
+    }
+
     let program_ast = parser::parser::parse(&file, tokens);
+    emit_stage(Stage::Ast, &|| format!("{program_ast:#?}"));
+    if is_done(Stage::Ast) {
+        return;
+    }
+
+    let warnings = warnings::unused_analysis::unused_analysis(&program_ast);
+
+    let mut program_ir = ir::translator::translate_program(&file, program_ast);
+
+    // Report warnings after translating, so that warnings are only reported for programs without compilation errors.
+    report_warnings(
+        &file,
+        &warnings,
+        &WarningConfig::new(&args.allow, &args.warn, args.deny_warnings),
+    );
+
+    if !args.no_optimize {
+        let summary = optimizer::optimizer::optimize(&mut program_ir);
+
+        if args.verbose {
+            eprintln!("{}: inlined {} calls", "Info".bold(), summary.inlined_calls);
+            eprintln!(
+                "{}: removed {} dead expressions",
+                "Info".bold(),
+                summary.removed_dead_code
+            );
+        }
+    }
+
+    emit_stage(Stage::Ir, &|| ir::ir_printer::print_program(&program_ir));
+    if is_done(Stage::Ir) {
+        return;
+    }
 
-    let program_ir = ir::translator::translate_program(&file, program_ast);
+    // The interpreter runs the IR directly, so the rest of the stages are skipped.
+    if args.interpret {
+        if !args.emit_only {
+            interpreter::interpreter::run(&program_ir);
+        }
+        return;
+    }
+
+    // The C backend lowers the IR into C source, which is compiled with `cc` instead of the compiler.
+    if args.backend == Backend::C {
+        emit_stage(Stage::C, &|| c_backend::c_writer::write_program(&program_ir));
+
+        if !args.emit_only {
+            bootstrapper::bootstrap_c(&program_ir, destination, &name, args.run);
+        }
+        return;
+    }
+
+    emit_stage(Stage::SpillCosts, &|| emitter::format_spill_costs(&program_ir.body));
+    if is_done(Stage::SpillCosts) {
+        return;
+    }
+
+    let variable_assignment = compiler::compiler::allocate(&program_ir.body, args.regalloc);
+    emit_stage(Stage::Regalloc, &|| emitter::format_allocation(&variable_assignment));
+    if is_done(Stage::Regalloc) {
+        return;
+    }
+
+    let mut instructions = compiler::compiler::compile_with_allocation(&program_ir, &variable_assignment);
+    if args.annotate_asm {
+        instructions = compiler::annotations::annotate_asm(instructions, &file, &program_ir.body, &variable_assignment);
+    }
 
-    let instructions = compiler::compiler::compile(program_ir);
+    // The line markers of the compiler are only kept for debug info, which needs the file that the lines are in.
+    if args.debug {
+        instructions.insert(0, asm::asm::Instruction::SourceFile(file_name.clone()));
+    } else {
+        instructions.retain(|instruction| !matches!(instruction, asm::asm::Instruction::Line(..)));
+    }
 
-    bootstrapper::bootstrap(instructions, destination, &name, args.run, args.clean);
+    if !args.no_optimize {
+        instructions = asm::peephole::peephole_optimize(instructions);
+    }
+    emit_stage(Stage::Asm, &|| {
+        let mut buffer = vec![];
+        asm::asm_writer::write_instructions(instructions.clone(), &mut buffer, args.assembler);
+        String::from_utf8_lossy(&buffer).to_string()
+    });
+
+    if args.jit {
+        if !args.emit_only {
+            jit::run(&instructions);
+        }
+    } else if !args.emit_only {
+        let debug_info = args
+            .debug
+            .then(|| asm::debug_info::DebugInfo::new(file_name, &program_ir, &variable_assignment));
+        bootstrapper::bootstrap(
+            instructions,
+            destination,
+            &name,
+            args.run,
+            args.assembler,
+            debug_info.as_ref(),
+        );
+    }
 }
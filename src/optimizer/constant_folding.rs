@@ -0,0 +1,243 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Constant folding and propagation.
+//!
+//! Expressions whose operands are all constants are evaluated at compile time, and variables that are bound to a
+//! constant are replaced with the constant where they are referenced. For example:
+//!   let @temp0 = mul.int 2, 3        =>    let @temp0 = 6
+//!   let a = add.int @temp0, x        =>    let a = add.int 6, x
+//!
+//! Expressions are evaluated with the interpreter (see `interpreter.rs`), so that the folded value is the same as the
+//! value at runtime. Expressions that would have a runtime error (like dividing by zero) are not folded, so that the
+//! error still happens at runtime.
+//!
+//! Constants are only substituted where the IR allows them: operands can only be ints that fit in 32 bits, bools or
+//! floats (see `translator.rs`), and the conditions of `if` expressions must be variables. Larger ints can only be the
+//! init expression of a `let` (or the result of a block).
+//!
+//! `if` expressions whose condition is a constant are replaced with the branch that is taken. The variables that are
+//! bound in the branch are renamed to fresh temporary variables (see `Renamer`), since they could conflict with the
+//! variables of the enclosing block (like the variables of a sibling branch that was taken as well).
+
+use error_messages::internal_compiler_error;
+use interpreter::interpreter::{interpret_binary_expr, interpret_unary_expr, Value};
+use ir::ir::{Block, DirectExpr, Expr, Program, Type};
+use optimizer::inliner::Renamer;
+use register_allocation::register_allocator::Map;
+use std::convert::TryFrom;
+use std::iter;
+
+/// The variables that are bound to a constant, mapped to the value of the constant.
+type Constants = Map<String, Value>;
+
+/// Folds the constants of each function and the body of a program.
+pub fn fold_constants(program: &mut Program) {
+    for function in &mut program.functions {
+        let mut renamer = Renamer::new(iter::once(&function.body));
+        fold_block(&mut function.body, &Constants::new(), &mut renamer);
+    }
+
+    let mut renamer = Renamer::new(iter::once(&program.body));
+    fold_block(&mut program.body, &Constants::new(), &mut renamer);
+}
+
+// Folds the constants of a block, which is a new scope (constants that are bound in the block are not visible after).
+fn fold_block(block: &mut Block, constants: &Constants, renamer: &mut Renamer) {
+    let exprs = std::mem::take(&mut block.exprs);
+    fold_exprs(exprs, true, &mut constants.clone(), renamer, &mut block.exprs);
+}
+
+// Folds the constants of a sequence of expressions, pushing the folded expressions into `folded_exprs`.
+// * `is_result` - whether the last expression is the result of the enclosing block.
+fn fold_exprs(
+    exprs: Vec<Expr>,
+    is_result: bool,
+    constants: &mut Constants,
+    renamer: &mut Renamer,
+    folded_exprs: &mut Vec<Expr>,
+) {
+    let length = exprs.len();
+
+    for (i, expr) in exprs.into_iter().enumerate() {
+        let is_last = is_result && i + 1 == length;
+
+        match expr {
+            Expr::If { condition, then_block, else_block } => {
+                let Some(is_taken) = constant_condition(&condition, constants) else {
+                    folded_exprs.push(fold_expr(
+                        Expr::If { condition, then_block, else_block },
+                        constants,
+                        renamer,
+                    ));
+                    continue;
+                };
+
+                // Replace the `if` with the branch that is taken. If the `if` is the result of the block, and the branch
+                // is empty (or missing), the `if` is kept (without the other branch), so that the result is still unit.
+                match if is_taken { Some(then_block) } else { else_block } {
+                    Some(branch) if !is_last || !branch.exprs.is_empty() => {
+                        let exprs = renamer.rename_exprs(&branch.exprs, &mut Map::new());
+                        fold_exprs(exprs, is_last, constants, renamer, folded_exprs);
+                    }
+                    None if !is_last => {}
+                    _ => {
+                        folded_exprs.push(Expr::If {
+                            condition,
+                            then_block: Block { exprs: vec![] },
+                            else_block: None,
+                        });
+                    }
+                }
+            }
+            Expr::Let { id, init_expr, line } => {
+                // For `let a = if <constant> { ...; b } else { ... }`, the branch that is taken must have a result.
+                let taken_branch = match &*init_expr {
+                    Expr::If { condition, then_block, else_block } => {
+                        constant_condition(condition, constants).filter(|is_taken| {
+                            let branch = if *is_taken { Some(then_block) } else { else_block.as_ref() };
+                            branch.map_or(false, has_result)
+                        })
+                    }
+                    _ => None,
+                };
+
+                match (*init_expr, taken_branch) {
+                    // Replace `let a = if <constant> { ...; b } else { ... }` with `...; let a = b`.
+                    (Expr::If { then_block, else_block, .. }, Some(is_taken)) => {
+                        let branch = if is_taken { then_block.exprs } else { else_block.map_or(vec![], |b| b.exprs) };
+                        let mut exprs = renamer.rename_exprs(&branch, &mut Map::new());
+
+                        if let Some(result) = exprs.pop() {
+                            exprs.push(Expr::Let { id, init_expr: Box::new(result), line });
+                        }
+                        fold_exprs(exprs, false, constants, renamer, folded_exprs);
+                    }
+                    (init_expr, _) => {
+                        folded_exprs.push(fold_expr(
+                            Expr::Let { id, init_expr: Box::new(init_expr), line },
+                            constants,
+                            renamer,
+                        ));
+                    }
+                }
+            }
+            expr => folded_exprs.push(fold_expr(expr, constants, renamer)),
+        }
+    }
+}
+
+// Folds the constants of an expression.
+fn fold_expr(expr: Expr, constants: &mut Constants, renamer: &mut Renamer) -> Expr {
+    match expr {
+        Expr::Direct { expr } => Expr::Direct { expr: propagate(expr, constants, true) },
+        Expr::Let { id, init_expr, line } => {
+            let init_expr = fold_expr(*init_expr, constants, renamer);
+
+            match &init_expr {
+                Expr::Direct { expr } => match to_value(expr, constants) {
+                    Some(value) => constants.insert(id.clone(), value),
+                    None => constants.remove(&id),
+                },
+                _ => constants.remove(&id),
+            };
+
+            Expr::Let { id, init_expr: Box::new(init_expr), line }
+        }
+        Expr::If { condition, mut then_block, mut else_block } => {
+            fold_block(&mut then_block, constants, renamer);
+            if let Some(else_block) = &mut else_block {
+                fold_block(else_block, constants, renamer);
+            }
+
+            Expr::If { condition, then_block, else_block }
+        }
+        Expr::UnaryExpr { kind, operand, operand_type } => match to_value(&operand, constants) {
+            Some(value) => to_direct(interpret_unary_expr(&kind, value)),
+            None => Expr::UnaryExpr {
+                kind,
+                operand: Box::new(propagate(*operand, constants, false)),
+                operand_type,
+            },
+        },
+        Expr::BinaryExpr { kind, operand_1, operand_2, operand_type } => {
+            let value = to_value(&operand_1, constants)
+                .zip(to_value(&operand_2, constants))
+                .and_then(|(value_1, value_2)| interpret_binary_expr(&kind, value_1, value_2).ok());
+
+            // Expressions with a runtime error (like dividing by zero) are not folded.
+            match value {
+                Some(value) => to_direct(value),
+                None => Expr::BinaryExpr {
+                    kind,
+                    operand_1: Box::new(propagate(*operand_1, constants, false)),
+                    operand_2: Box::new(propagate(*operand_2, constants, false)),
+                    operand_type,
+                },
+            }
+        }
+        Expr::TypeCoercion { expr, from_type, to_type } => match (to_value(&expr, constants), &from_type, &to_type) {
+            #[allow(clippy::cast_precision_loss)]
+            (Some(Value::Int(value)), Type::Int, Type::Float) => to_direct(Value::Float(value as f64)),
+            _ => Expr::TypeCoercion { expr: Box::new(propagate(*expr, constants, false)), from_type, to_type },
+        },
+        Expr::Call { id, args, live_variables } => Expr::Call {
+            id,
+            args: args.into_iter().map(|arg| propagate(arg, constants, false)).collect(),
+            live_variables,
+        },
+    }
+}
+
+// Replaces a variable that is bound to a constant with the constant, if the constant is allowed where the direct is.
+// * `is_init_expr` - whether the direct is a whole expression (like the init expression of a let), where any constant
+//                    is allowed. Otherwise, the direct is an operand, which can't be an int that doesn't fit in 32 bits.
+fn propagate(direct: DirectExpr, constants: &Constants, is_init_expr: bool) -> DirectExpr {
+    let DirectExpr::Id { value, .. } = &direct else {
+        return direct;
+    };
+
+    match constants.get(value) {
+        Some(Value::Int(value)) if is_init_expr || i32::try_from(*value).is_ok() => DirectExpr::Int { value: *value },
+        Some(Value::Bool(value)) => DirectExpr::Bool { value: *value },
+        Some(Value::Float(value)) => DirectExpr::Float { value: *value },
+        _ => direct,
+    }
+}
+
+// The value of a direct, if it is a constant (or a variable that is bound to a constant).
+fn to_value(direct: &DirectExpr, constants: &Constants) -> Option<Value> {
+    match direct {
+        DirectExpr::Int { value } => Some(Value::Int(*value)),
+        DirectExpr::Bool { value } => Some(Value::Bool(*value)),
+        DirectExpr::Float { value } => Some(Value::Float(*value)),
+        DirectExpr::Id { value, .. } => constants.get(value).copied(),
+    }
+}
+
+// Converts a folded value into an expression.
+fn to_direct(value: Value) -> Expr {
+    let expr = match value {
+        Value::Int(value) => DirectExpr::Int { value },
+        Value::Bool(value) => DirectExpr::Bool { value },
+        Value::Float(value) => DirectExpr::Float { value },
+        Value::Unit => internal_compiler_error("unit is not a constant"),
+    };
+
+    Expr::Direct { expr }
+}
+
+// The value of the condition of an `if`, if it is a constant.
+fn constant_condition(condition: &DirectExpr, constants: &Constants) -> Option<bool> {
+    match to_value(condition, constants) {
+        Some(Value::Bool(value)) => Some(value),
+        _ => None,
+    }
+}
+
+// Whether a block has a result, which means that it is not empty and doesn't end in a let.
+fn has_result(block: &Block) -> bool {
+    block
+        .exprs
+        .last()
+        .map_or(false, |expr| !matches!(expr, Expr::Let { .. }))
+}
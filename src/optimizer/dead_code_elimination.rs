@@ -0,0 +1,139 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Dead code elimination.
+//!
+//! The translator creates a temporary variable for every sub expression, and constant folding (see
+//! `constant_folding.rs`) leaves behind the bindings of the constants that it propagated. This pass removes:
+//!   - `let` bindings whose variable is never referenced, and whose init expression has no side effects.
+//!   - expressions whose result is discarded (any expression of a block but the last), and that have no side effects.
+//!
+//! An expression has a side effect if it calls a function, or if it is an integer division (or modulo) that may crash
+//! at runtime (which is any integer division, unless the divisor is a constant other than 0 and -1).
+//!
+//! Removing an expression can make the variables that it referenced unused, so the pass is repeated until nothing is
+//! removed. The last expression of a block is never removed, since it is the result of the block.
+
+use ir::ir::{BinaryExprKind, Block, DirectExpr, Expr, Program, Type};
+use register_allocation::register_allocator::Map;
+
+/// Removes the dead code of each function and the body of a program.
+/// * return - the number of expressions that were removed.
+pub fn eliminate_dead_code(program: &mut Program) -> usize {
+    let mut removed_count = 0;
+
+    for block in program
+        .functions
+        .iter_mut()
+        .map(|function| &mut function.body)
+        .chain(std::iter::once(&mut program.body))
+    {
+        loop {
+            let mut references = Map::new();
+            count_references(block, &mut references);
+
+            let removed = eliminate_block(block, &references);
+            if removed == 0 {
+                break;
+            }
+            removed_count += removed;
+        }
+    }
+
+    removed_count
+}
+
+// Counts the number of times each variable is referenced in a block (including nested blocks). Variables are counted by
+// name, so this is conservative if two variables have the same name.
+fn count_references(block: &Block, references: &mut Map<String, usize>) {
+    for expr in &block.exprs {
+        count_references_expr(expr, references);
+    }
+}
+
+/// Counts the number of times each variable is referenced in an expression (including nested blocks), by name.
+pub fn count_references_expr(expr: &Expr, references: &mut Map<String, usize>) {
+    let mut count = |direct: &DirectExpr| {
+        if let DirectExpr::Id { value, .. } = direct {
+            *references.entry(value.clone()).or_default() += 1;
+        }
+    };
+
+    match expr {
+        Expr::Direct { expr } => count(expr),
+        Expr::Let { init_expr, .. } => count_references_expr(init_expr, references),
+        Expr::If { condition, then_block, else_block } => {
+            count(condition);
+            count_references(then_block, references);
+            if let Some(else_block) = else_block {
+                count_references(else_block, references);
+            }
+        }
+        Expr::UnaryExpr { operand: expr, .. } | Expr::TypeCoercion { expr, .. } => count(expr),
+        Expr::BinaryExpr { operand_1, operand_2, .. } => {
+            count(operand_1);
+            count(operand_2);
+        }
+        Expr::Call { args, .. } => args.iter().for_each(count),
+    }
+}
+
+// Removes the dead expressions of a block (and nested blocks), once.
+// * return - the number of expressions that were removed.
+fn eliminate_block(block: &mut Block, references: &Map<String, usize>) -> usize {
+    let length = block.exprs.len();
+    let mut removed_count = 0;
+
+    let exprs = std::mem::take(&mut block.exprs);
+    for (i, mut expr) in exprs.into_iter().enumerate() {
+        let is_dead = i + 1 != length
+            && is_pure(&expr)
+            && match &expr {
+                Expr::Let { id, .. } => !references.contains_key(id),
+                _ => true,
+            };
+
+        if is_dead {
+            removed_count += 1;
+            continue;
+        }
+
+        removed_count += eliminate_expr(&mut expr, references);
+        block.exprs.push(expr);
+    }
+
+    removed_count
+}
+
+// Removes the dead expressions of the blocks that are nested in an expression.
+fn eliminate_expr(expr: &mut Expr, references: &Map<String, usize>) -> usize {
+    match expr {
+        Expr::Let { init_expr, .. } => eliminate_expr(init_expr, references),
+        Expr::If { then_block, else_block, .. } => {
+            eliminate_block(then_block, references)
+                + else_block
+                    .as_mut()
+                    .map_or(0, |else_block| eliminate_block(else_block, references))
+        }
+        _ => 0,
+    }
+}
+
+// Whether an expression has no side effects.
+fn is_pure(expr: &Expr) -> bool {
+    match expr {
+        Expr::BinaryExpr {
+            kind: BinaryExprKind::Divide | BinaryExprKind::Mod,
+            operand_2,
+            operand_type,
+            ..
+        } if *operand_type != Type::Float => {
+            matches!(**operand_2, DirectExpr::Int { value } if value != 0 && value != -1)
+        }
+        Expr::Direct { .. } | Expr::UnaryExpr { .. } | Expr::BinaryExpr { .. } | Expr::TypeCoercion { .. } => true,
+        Expr::Let { init_expr, .. } => is_pure(init_expr),
+        Expr::If { then_block, else_block, .. } => std::iter::once(then_block)
+            .chain(else_block)
+            .all(|block| block.exprs.iter().all(is_pure)),
+        Expr::Call { .. } => false,
+    }
+}
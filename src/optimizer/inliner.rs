@@ -0,0 +1,328 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Function inlining.
+//!
+//! A call to a small function is replaced with the body of the function, which removes the overhead of the call
+//! (including saving the `live_variables` of the call), and exposes the body to the rest of the optimizer (like
+//! constant folding the arguments into the body).
+//!
+//! A function is inlined if its body has at most `INLINE_THRESHOLD` expressions (including nested blocks), and it can't
+//! (mutually) call itself, since inlining a recursive function would never end. This can be overridden with an
+//! annotation: `@inline` functions are always inlined (unless they are recursive), and `@noinline` functions are never
+//! inlined. Functions that evaluate to a `let` binding are not inlined, since the result of the call has no direct
+//! expression to bind to.
+//!
+//! For each inlined call, the parameters are bound to the arguments, and every variable of the body (including the
+//! parameters) is renamed to a fresh temporary variable, so that they don't conflict with the variables of the caller.
+//! For example, `let a = call sq(b: int)` with `fun sq(x: int): int { mul.int x, x }` becomes:
+//! ```text
+//! let %temp5 = b: int
+//! let a = mul.int %temp5, %temp5
+//! ```
+//! Note that the functions are kept, even if all of their calls were inlined.
+
+use ir::ir::{Block, DirectExpr, Expr, Function, Inline, Program};
+use register_allocation::register_allocator::{Map, Set};
+use std::cell::RefCell;
+
+// The maximum number of expressions of a function that is inlined without an annotation.
+const INLINE_THRESHOLD: usize = 8;
+
+/// Inlines the calls to small, non-recursive functions of a program.
+/// * return - the number of calls that were inlined.
+pub fn inline_functions(program: &mut Program) -> usize {
+    let recursive_functions = recursive_functions(&program.functions);
+
+    let inlinable_functions: Map<String, Function> = program
+        .functions
+        .iter()
+        .filter(|function| !recursive_functions.contains(&function.id) && is_inlinable(function))
+        .map(|function| (function.id.clone(), function.clone()))
+        .collect();
+
+    let renamer = Renamer::new(
+        program
+            .functions
+            .iter()
+            .map(|function| &function.body)
+            .chain(std::iter::once(&program.body)),
+    );
+    let mut inliner = Inliner { functions: &inlinable_functions, renamer, inlined_count: 0 };
+
+    for block in program
+        .functions
+        .iter_mut()
+        .map(|function| &mut function.body)
+        .chain(std::iter::once(&mut program.body))
+    {
+        inliner.inline_block(block);
+    }
+
+    inliner.inlined_count
+}
+
+// Whether a (non-recursive) function should be inlined.
+fn is_inlinable(function: &Function) -> bool {
+    let has_result = !matches!(function.body.exprs.last(), None | Some(Expr::Let { .. }));
+
+    has_result
+        && match function.inline {
+            Inline::Auto => size(&function.body) <= INLINE_THRESHOLD,
+            Inline::Always => true,
+            Inline::Never => false,
+        }
+}
+
+// The number of expressions of a block, including nested blocks.
+fn size(block: &Block) -> usize {
+    block
+        .exprs
+        .iter()
+        .map(|expr| match expr {
+            Expr::Let { init_expr, .. } => size_expr(init_expr),
+            _ => size_expr(expr),
+        })
+        .sum()
+}
+
+fn size_expr(expr: &Expr) -> usize {
+    match expr {
+        Expr::If { then_block, else_block, .. } => 1 + size(then_block) + else_block.as_ref().map_or(0, size),
+        _ => 1,
+    }
+}
+
+// Finds the functions that can (mutually) call themselves.
+fn recursive_functions(functions: &[Function]) -> Set<String> {
+    let call_graph: Map<&String, Set<&String>> = functions
+        .iter()
+        .map(|function| {
+            let mut callees = Set::new();
+            add_callees(&function.body, &mut callees);
+            (&function.id, callees)
+        })
+        .collect();
+
+    call_graph
+        .keys()
+        .filter(|function| {
+            // Search the functions that are reachable from the function.
+            let mut stack: Vec<&String> = call_graph[*function].iter().copied().collect();
+            let mut visited = Set::new();
+
+            while let Some(callee) = stack.pop() {
+                if callee == **function {
+                    return true;
+                }
+                if visited.insert(callee) {
+                    stack.extend(call_graph.get(callee).into_iter().flatten());
+                }
+            }
+            false
+        })
+        .map(|function| (*function).clone())
+        .collect()
+}
+
+// Adds the ids of the functions that are called in a block.
+fn add_callees<'a>(block: &'a Block, callees: &mut Set<&'a String>) {
+    for expr in &block.exprs {
+        let expr = match expr {
+            Expr::Let { init_expr, .. } => init_expr,
+            _ => expr,
+        };
+
+        match expr {
+            Expr::Call { id, .. } => {
+                callees.insert(id);
+            }
+            Expr::If { then_block, else_block, .. } => {
+                add_callees(then_block, callees);
+                if let Some(else_block) = else_block {
+                    add_callees(else_block, callees);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// The index of the next temporary variable that doesn't conflict with the temporary variables of a block.
+fn next_temp(block: &Block) -> usize {
+    block.exprs.iter().map(next_temp_expr).max().unwrap_or(0)
+}
+
+fn next_temp_expr(expr: &Expr) -> usize {
+    let temp_index = |id: &str| {
+        id.strip_prefix("@temp")
+            .and_then(|index| index.parse::<usize>().ok())
+            .map_or(0, |index| index + 1)
+    };
+
+    let direct_next_temp = |direct: &DirectExpr| match direct {
+        DirectExpr::Id { value, .. } => temp_index(value),
+        _ => 0,
+    };
+
+    match expr {
+        Expr::Direct { expr } => direct_next_temp(expr),
+        Expr::Let { id, init_expr, .. } => temp_index(id).max(next_temp_expr(init_expr)),
+        Expr::If { condition, then_block, else_block } => direct_next_temp(condition)
+            .max(next_temp(then_block))
+            .max(else_block.as_ref().map_or(0, next_temp)),
+        Expr::UnaryExpr { operand: expr, .. } | Expr::TypeCoercion { expr, .. } => direct_next_temp(expr),
+        Expr::BinaryExpr { operand_1, operand_2, .. } => direct_next_temp(operand_1).max(direct_next_temp(operand_2)),
+        Expr::Call { args, .. } => args.iter().map(direct_next_temp).max().unwrap_or(0),
+    }
+}
+
+// Inlines calls, while keeping track of the next fresh temporary variable.
+struct Inliner<'a> {
+    functions: &'a Map<String, Function>,
+    renamer: Renamer,
+    inlined_count: usize,
+}
+
+impl Inliner<'_> {
+    fn inline_block(&mut self, block: &mut Block) {
+        let exprs = std::mem::take(&mut block.exprs);
+        block.exprs = self.inline_exprs(exprs);
+    }
+
+    // Inlines the calls of a list of expressions, where the body of an inlined function is inlined as well.
+    fn inline_exprs(&mut self, exprs: Vec<Expr>) -> Vec<Expr> {
+        let mut inlined_exprs = vec![];
+
+        for mut expr in exprs {
+            let (call, result) = match &expr {
+                Expr::Let { id, init_expr, line } => (&**init_expr, Some((id, *line))),
+                _ => (&expr, None),
+            };
+
+            if let Expr::Call { id, args, .. } = call {
+                if let Some(function) = self.functions.get(id) {
+                    let mut body = self.instantiate(function, args);
+
+                    if let Some((result_id, line)) = result {
+                        let result = body.pop().unwrap();
+                        body.push(Expr::Let { id: result_id.clone(), init_expr: Box::new(result), line });
+                    }
+
+                    self.inlined_count += 1;
+                    inlined_exprs.extend(self.inline_exprs(body));
+                    continue;
+                }
+            }
+
+            let if_expr = match &mut expr {
+                Expr::Let { init_expr, .. } => &mut **init_expr,
+                _ => &mut expr,
+            };
+            if let Expr::If { then_block, else_block, .. } = if_expr {
+                self.inline_block(then_block);
+                if let Some(else_block) = else_block {
+                    self.inline_block(else_block);
+                }
+            }
+
+            inlined_exprs.push(expr);
+        }
+
+        inlined_exprs
+    }
+
+    // Creates the expressions of a call to a function, which binds the parameters to the arguments, followed by the
+    // body of the function, where each variable is renamed to a fresh temporary variable.
+    fn instantiate(&mut self, function: &Function, args: &[DirectExpr]) -> Vec<Expr> {
+        let mut renames = Map::new();
+
+        let mut exprs: Vec<Expr> = function
+            .params
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| Expr::Let {
+                id: self.renamer.bind(param, &mut renames),
+                init_expr: Box::new(Expr::Direct { expr: arg.clone() }),
+                line: None,
+            })
+            .collect();
+
+        exprs.extend(self.renamer.rename_exprs(&function.body.exprs, &mut renames));
+        exprs
+    }
+}
+
+/// Renames the variables that are bound by expressions to fresh temporary variables, so that the expressions can be
+/// moved into another block without conflicting with the variables of that block.
+pub struct Renamer {
+    next_temp: usize,
+}
+
+impl Renamer {
+    /// Creates a `Renamer` whose temporary variables don't conflict with the temporary variables of `blocks`.
+    pub fn new<'a>(blocks: impl IntoIterator<Item = &'a Block>) -> Self {
+        Self { next_temp: blocks.into_iter().map(next_temp).max().unwrap_or(0) }
+    }
+
+    /// Binds a variable to a fresh temporary variable, which its references are renamed to.
+    pub fn bind(&mut self, id: &str, renames: &mut Map<String, String>) -> String {
+        let renamed_id = format!("@temp{}", self.next_temp);
+        self.next_temp += 1;
+        renames.insert(id.to_string(), renamed_id.clone());
+        renamed_id
+    }
+
+    /// Renames the variables that are bound by expressions (including nested blocks), and the references to them (or
+    /// to the variables of `renames`). The references to other variables are kept.
+    pub fn rename_exprs(&mut self, exprs: &[Expr], renames: &mut Map<String, String>) -> Vec<Expr> {
+        exprs.iter().map(|expr| self.rename_expr(expr, renames)).collect()
+    }
+
+    fn rename_expr(&mut self, expr: &Expr, renames: &mut Map<String, String>) -> Expr {
+        let rename_direct = |direct: &DirectExpr| match direct {
+            DirectExpr::Id { value, id_type } => DirectExpr::Id {
+                value: renames.get(value).unwrap_or(value).clone(),
+                id_type: id_type.clone(),
+            },
+            _ => direct.clone(),
+        };
+
+        match expr {
+            Expr::Direct { expr } => Expr::Direct { expr: rename_direct(expr) },
+            Expr::UnaryExpr { kind, operand, operand_type } => Expr::UnaryExpr {
+                kind: kind.clone(),
+                operand: Box::new(rename_direct(operand)),
+                operand_type: operand_type.clone(),
+            },
+            Expr::BinaryExpr { kind, operand_1, operand_2, operand_type } => Expr::BinaryExpr {
+                kind: kind.clone(),
+                operand_1: Box::new(rename_direct(operand_1)),
+                operand_2: Box::new(rename_direct(operand_2)),
+                operand_type: operand_type.clone(),
+            },
+            Expr::TypeCoercion { expr, from_type, to_type } => Expr::TypeCoercion {
+                expr: Box::new(rename_direct(expr)),
+                from_type: from_type.clone(),
+                to_type: to_type.clone(),
+            },
+
+            // The live variables are filled in by the register allocator, after the optimizer.
+            Expr::Call { id, args, .. } => Expr::Call {
+                id: id.clone(),
+                args: args.iter().map(rename_direct).collect(),
+                live_variables: RefCell::new(Set::new()),
+            },
+            Expr::Let { id, init_expr, line } => {
+                let init_expr = Box::new(self.rename_expr(init_expr, renames));
+                Expr::Let { id: self.bind(id, renames), init_expr, line: *line }
+            }
+            Expr::If { condition, then_block, else_block } => Expr::If {
+                condition: Box::new(rename_direct(condition)),
+                then_block: Block { exprs: self.rename_exprs(&then_block.exprs, renames) },
+                else_block: else_block
+                    .as_ref()
+                    .map(|else_block| Block { exprs: self.rename_exprs(&else_block.exprs, renames) }),
+            },
+        }
+    }
+}
@@ -0,0 +1,8 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The `optimizer` module performs IR-to-IR optimizations.
+
+pub mod constant_folding;
+pub mod dead_code_elimination;
+pub mod inliner;
+pub mod optimizer;
@@ -0,0 +1,32 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The optimizer runs after the translator (see `translator.rs`), and rewrites the IR of a program into an equivalent
+//! program that is faster to run.
+//!
+//! Each pass must preserve the runtime semantics of the program (as defined by `interpreter.rs`), including runtime
+//! errors like dividing by zero. The passes are run in order:
+//!   - function inlining (see `inliner.rs`)
+//!   - constant folding and propagation (see `constant_folding.rs`)
+//!   - dead code elimination (see `dead_code_elimination.rs`)
+//!
+//! The optimizer can be disabled with `--no-optimize`.
+
+use ir::ir::Program;
+use optimizer::constant_folding::fold_constants;
+use optimizer::dead_code_elimination::eliminate_dead_code;
+use optimizer::inliner::inline_functions;
+
+/// What the optimizer did to a program, which is reported with `--verbose`.
+pub struct OptimizationSummary {
+    pub inlined_calls: usize,
+    pub removed_dead_code: usize,
+}
+
+/// Runs each optimization pass on a program.
+pub fn optimize(program: &mut Program) -> OptimizationSummary {
+    let inlined_calls = inline_functions(program);
+    fold_constants(program);
+    let removed_dead_code = eliminate_dead_code(program);
+
+    OptimizationSummary { inlined_calls, removed_dead_code }
+}
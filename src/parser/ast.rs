@@ -6,33 +6,43 @@
 
 use std::ops::Range;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Program {
     pub functions: Vec<Function>,
     pub body: Block,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Block {
     pub exprs: Vec<Expr>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Function {
     pub id: String,
     pub params: Vec<Param>,
     pub return_type: Type,
     pub body: Block,
+    pub inline: Inline,
     pub position: Range<usize>,
 }
 
-#[derive(Debug)]
+/// Whether a function should be inlined, as annotated with `@inline` or `@noinline`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inline {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone)]
 pub struct Param {
     pub id: String,
     pub type_reference: Type,
+    pub position: Range<usize>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Expr {
     pub kind: ExprKind,
 
@@ -42,10 +52,12 @@ pub struct Expr {
     pub position: Range<usize>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ExprKind {
     Let {
         id: String,
+        /// Where the identifier is in the source code (the position of the expression is its type reference).
+        id_position: Range<usize>,
         type_reference: Type,
         init_expr: Box<Expr>,
     },
@@ -81,7 +93,7 @@ pub enum ExprKind {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Type {
     Unit,
     Int,
@@ -89,13 +101,13 @@ pub enum Type {
     Float,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum UnaryExprKind {
     Not,
     Negative,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BinaryExprKind {
     Plus,
     Minus,
@@ -26,7 +26,7 @@ pub fn parse_let_expr(tokens_cursor: &mut TokensCursor) -> Expr {
 
     // Consume the let expression identifier
     tokens_cursor.consume_token(TokenKind::Id("identifier".to_string()));
-    let id_token_kind = &tokens_cursor.prev().kind;
+    let id_token = tokens_cursor.prev();
 
     tokens_cursor.consume_token(TokenKind::Colon);
 
@@ -39,9 +39,14 @@ pub fn parse_let_expr(tokens_cursor: &mut TokensCursor) -> Expr {
     // Binding initial expression
     let init_expr = parse_expr(tokens_cursor);
 
-    if let TokenKind::Id(id) = id_token_kind {
+    if let TokenKind::Id(id) = &id_token.kind {
         Expr {
-            kind: ExprKind::Let { id: id.to_string(), type_reference, init_expr: Box::new(init_expr) },
+            kind: ExprKind::Let {
+                id: id.to_string(),
+                id_position: id_token.position.clone(),
+                type_reference,
+                init_expr: Box::new(init_expr),
+            },
             position: type_reference_token.position.clone(),
         }
     } else {
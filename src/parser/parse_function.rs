@@ -3,7 +3,7 @@
 //! Responsible for parsing function declarations.
 
 use error_messages::internal_compiler_error;
-use parser::ast::{Block, Expr, ExprKind, Function, Param};
+use parser::ast::{Block, Expr, ExprKind, Function, Inline, Param};
 use parser::parse_expr::parse_expr;
 use parser::parser::{parse_closed_block, parse_type};
 use parser::tokens_cursor::TokensCursor;
@@ -15,7 +15,7 @@ use tokenizer::tokenizer::{Token, TokenKind};
 pub fn parse_functions(mut functions: Vec<Function>, tokens_cursor: &mut TokensCursor) -> Vec<Function> {
     let (next_token, tokens_cursor) = tokens_cursor.peek();
 
-    if let Some(Token { kind: TokenKind::Fun, .. }) = next_token {
+    if let Some(Token { kind: TokenKind::Fun | TokenKind::Inline | TokenKind::NoInline, .. }) = next_token {
         functions.push(parse_function(tokens_cursor));
 
         // Remove optional semicolons. See https://github.com/brandonLi8/solis/issues/28
@@ -30,6 +30,7 @@ pub fn parse_functions(mut functions: Vec<Function>, tokens_cursor: &mut TokensC
 
 // Corresponds to the `<function>` rule and parses into `ast::Function`
 fn parse_function(tokens_cursor: &mut TokensCursor) -> Function {
+    let inline = parse_annotation(tokens_cursor);
     tokens_cursor.consume_token(TokenKind::Fun);
 
     // Consume the function id
@@ -50,6 +51,7 @@ fn parse_function(tokens_cursor: &mut TokensCursor) -> Function {
             params,
             return_type,
             body,
+            inline,
             id: id.to_string(),
             position: id_token.position.clone(),
         }
@@ -58,16 +60,33 @@ fn parse_function(tokens_cursor: &mut TokensCursor) -> Function {
     }
 }
 
+// Corresponds to the optional `<annotation>` rule and parses into `ast::Inline`.
+fn parse_annotation(tokens_cursor: &mut TokensCursor) -> Inline {
+    let (next_token, tokens_cursor) = tokens_cursor.peek_unwrap();
+
+    let inline = match next_token.kind {
+        TokenKind::Inline => Inline::Always,
+        TokenKind::NoInline => Inline::Never,
+        _ => return Inline::Auto,
+    };
+    tokens_cursor.advance();
+    inline
+}
+
 // Corresponds to `<param>` rule and parses into `ast::Param`.
 fn parse_param(tokens_cursor: &mut TokensCursor) -> Param {
     tokens_cursor.consume_token(TokenKind::Id("identifier".to_string()));
-    let param_id_kind = &tokens_cursor.prev().kind;
+    let param_id_token = &tokens_cursor.prev();
 
     tokens_cursor.consume_token(TokenKind::Colon);
     let type_reference = parse_type(tokens_cursor);
 
-    if let TokenKind::Id(id) = param_id_kind {
-        Param { type_reference, id: id.to_string() }
+    if let TokenKind::Id(id) = &param_id_token.kind {
+        Param {
+            type_reference,
+            id: id.to_string(),
+            position: param_id_token.position.clone(),
+        }
     } else {
         internal_compiler_error("Unable to get id. Should have been consumed.")
     }
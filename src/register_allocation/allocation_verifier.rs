@@ -0,0 +1,147 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Verifies the result of the register allocator (see `register_allocator.rs`).
+//!
+//! Allocation bugs otherwise only surface as wrong results at runtime, so the verifier re-derives the liveness of each
+//! variable over the block, independently of `liveness_analysis.rs` and `conflict_analysis.rs`, and checks that:
+//!   - every variable that is referenced (or declared) has an `Assignment`.
+//!   - float variables are only assigned to float registers, and other variables are only assigned to registers.
+//!   - no two variables that are live at the same time are assigned to the same register (or stack slot).
+//!
+//! Violations raise an `internal_compiler_error`. The verifier runs after every allocation in debug builds (and with
+//! the `test` feature), see `should_verify_allocation`.
+
+use error_messages::internal_compiler_error;
+use ir::ir::{Block, DirectExpr, Expr, Type};
+use register_allocation::register_allocator::{Assignment, Map, Set};
+
+/// Whether the register allocator should verify its result, which is the case in debug builds and for testing.
+pub const fn should_verify_allocation() -> bool {
+    cfg!(any(debug_assertions, feature = "test"))
+}
+
+/// Verifies an allocation for a block, raising an `internal_compiler_error` if the allocation is invalid.
+/// * params - the parameters of the current function, which are not allocated (since they live on the stack).
+pub fn verify_allocation(block: &Block, params: &Set<&String>, allocation: &Map<&String, Assignment>) {
+    let verifier = AllocationVerifier { params, allocation };
+    verifier.verify_block(block, &mut Map::new());
+}
+
+// State that is kept while verifying an allocation.
+struct AllocationVerifier<'a, 'b> {
+    params: &'b Set<&'a String>,
+    allocation: &'b Map<&'a String, Assignment>,
+}
+
+impl<'a> AllocationVerifier<'a, '_> {
+    // Verifies a block in reverse order, where `live_variables` are the variables that are live after the block, mapped
+    // to their type. When done, `live_variables` are the variables that are live before the block.
+    fn verify_block(&self, block: &'a Block, live_variables: &mut Map<&'a String, &'a Type>) {
+        for expr in block.exprs.iter().rev() {
+            self.verify_expr(expr, live_variables);
+            self.verify_live_variables(live_variables);
+        }
+    }
+
+    // Updates `live_variables` from the variables that are live after the expression, to the variables that are live
+    // before the expression.
+    fn verify_expr(&self, expr: &'a Expr, live_variables: &mut Map<&'a String, &'a Type>) {
+        match expr {
+            Expr::Direct { expr } => self.verify_direct(expr, live_variables),
+            Expr::UnaryExpr { operand: expr, .. } | Expr::TypeCoercion { expr, .. } => {
+                self.verify_direct(expr, live_variables);
+            }
+            Expr::BinaryExpr { operand_1, operand_2, .. } => {
+                self.verify_direct(operand_1, live_variables);
+                self.verify_direct(operand_2, live_variables);
+            }
+            Expr::Let { id, init_expr, .. } => {
+                if !self.params.contains(id) {
+                    self.get_assignment(id);
+                }
+
+                live_variables.remove(id);
+                self.verify_expr(init_expr, live_variables);
+            }
+            Expr::If { condition, then_block, else_block } => {
+                let mut then_live_variables = live_variables.clone();
+                self.verify_block(then_block, &mut then_live_variables);
+
+                if let Some(else_block) = else_block {
+                    let mut else_live_variables = live_variables.clone();
+                    self.verify_block(else_block, &mut else_live_variables);
+                    live_variables.extend(else_live_variables);
+                }
+
+                live_variables.extend(then_live_variables);
+                self.verify_direct(condition, live_variables);
+            }
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.verify_direct(arg, live_variables);
+                }
+            }
+        }
+    }
+
+    // Marks a referenced variable as live, and verifies that it is assigned to the right kind of register.
+    fn verify_direct(&self, direct: &'a DirectExpr, live_variables: &mut Map<&'a String, &'a Type>) {
+        if let DirectExpr::Id { value, id_type } = direct {
+            if self.params.contains(value) {
+                return;
+            }
+
+            let is_float = matches!(id_type, Type::Float);
+            match self.get_assignment(value) {
+                Assignment::Register(register) if is_float => internal_compiler_error(&format!(
+                    "float variable `{value}` is assigned to the register `{register:?}`"
+                )),
+                Assignment::FloatRegister(register) if !is_float => internal_compiler_error(&format!(
+                    "{id_type} variable `{value}` is assigned to the float register `{register:?}`"
+                )),
+                _ => {}
+            }
+
+            live_variables.insert(value, id_type);
+        }
+    }
+
+    // Verifies that no two variables that are live at the same time are assigned to the same register or stack slot.
+    fn verify_live_variables(&self, live_variables: &Map<&'a String, &'a Type>) {
+        let mut assigned_variables: Vec<(&String, &Assignment)> = live_variables
+            .keys()
+            .map(|variable| (*variable, self.get_assignment(variable)))
+            .filter(|(_, assignment)| !matches!(assignment, Assignment::None))
+            .collect();
+
+        // Sort by name, so that the error message is deterministic.
+        assigned_variables.sort_by_key(|(variable, _)| *variable);
+
+        for (i, (variable_1, assignment_1)) in assigned_variables.iter().enumerate() {
+            for (variable_2, assignment_2) in &assigned_variables[i + 1..] {
+                let is_same_location = match (assignment_1, assignment_2) {
+                    (Assignment::Register(register_1), Assignment::Register(register_2)) => register_1 == register_2,
+                    (Assignment::FloatRegister(register_1), Assignment::FloatRegister(register_2)) => {
+                        register_1 == register_2
+                    }
+                    (Assignment::Spill(slot_1), Assignment::Spill(slot_2)) => slot_1 == slot_2,
+                    _ => false,
+                };
+
+                if is_same_location {
+                    internal_compiler_error(&format!(
+                        "variables `{variable_1}` and `{variable_2}` are live at the same time, but are both \
+                         assigned to `{assignment_1:?}`"
+                    ))
+                }
+            }
+        }
+    }
+
+    // Gets the assignment of a variable, raising an `internal_compiler_error` if there is none.
+    fn get_assignment(&self, variable: &String) -> &Assignment {
+        self.allocation
+            .get(variable)
+            .unwrap_or_else(|| internal_compiler_error(&format!("variable `{variable}` has no assignment")))
+    }
+}
@@ -61,6 +61,27 @@ impl<'a> InterferenceGraph<'a> {
         }
     }
 
+    /// Merges `variable` into `into`, where `into` gets all of the edges of `variable`, and `variable` is removed from
+    /// the graph. The nodes must not be neighbors.
+    pub fn merge_node(&mut self, variable: &'a String, into: &'a String) {
+        let neighbors = self
+            .nodes
+            .remove(variable)
+            .unwrap_or_else(|| internal_compiler_error(&format!("node {variable} not found")));
+
+        for neighbor in neighbors {
+            if let Some(node) = self.nodes.get_mut(neighbor) {
+                node.remove(variable);
+            }
+            self.add_edge(into, neighbor);
+        }
+    }
+
+    /// Gets the number of neighbors of a node.
+    pub fn degree(&self, variable: &String) -> usize {
+        self.nodes.get(variable).map_or(0, Set::len)
+    }
+
     /// Gets the size of the graph.
     pub fn size(&self) -> usize {
         self.nodes.len()
@@ -0,0 +1,281 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! A linear scan register allocator, which is a faster alternative to the graph coloring allocator of
+//! `register_allocator.rs` (see `--regalloc`).
+//!
+//! It creates the same kind of allocation, but doesn't build an interference graph, so it runs in (close to) linear
+//! time, at the cost of worse allocations.
+//!
+//! The expressions of the block (including nested blocks) are numbered in program order, and each variable gets a live
+//! interval from the position of its definition to the position of its last reference. Since the IR has no loops, a
+//! variable is live (see `liveness_analysis.rs`) only within its interval. Variables whose intervals overlap conflict,
+//! so the intervals are scanned in order of their start, and each interval is assigned a register that is not used by
+//! an interval that is still active. When there is no free register, the interval that ends last is spilled (Poletto
+//! and Sarkar). Spilled variables are then assigned to stack slots with a second scan, so that slots are reused.
+//!
+//! Both branches of an `if` are numbered one after the other, so a variable that is live in one branch is considered
+//! to be live in the other branch as well. This is conservative, but still correct.
+
+use asm::asm::{FloatRegister, Register};
+use ir::ir::{Block, DirectExpr, Expr, Type};
+use register_allocation::allocation_verifier::{should_verify_allocation, verify_allocation};
+use register_allocation::register_allocator::{Assignment, Map, Set};
+use std::cell::RefCell;
+
+/// Creates an assignment of registers for each variable in the block with linear scan. The parameters and result are
+/// the same as `allocate_registers`.
+pub fn allocate_registers_linear<'a>(
+    block: &'a Block,
+    params: &Set<&'a String>,
+    registers: &[Register],
+    float_registers: &[FloatRegister],
+) -> Map<&'a String, Assignment> {
+    let mut interval_builder = IntervalBuilder { params, position: 0, intervals: Map::new(), calls: vec![] };
+    interval_builder.build_block(block);
+
+    let IntervalBuilder { intervals, calls, .. } = interval_builder;
+
+    // Fill in the variables that are live before each call, like `liveness_analysis` does.
+    for (position, call_live_variables) in calls {
+        for (variable, interval) in &intervals {
+            if interval.start < position && interval.end.map_or(false, |end| end >= position) {
+                call_live_variables.borrow_mut().insert((*variable).clone());
+            }
+        }
+    }
+
+    let mut allocation = Map::new();
+
+    // Variables that are never referenced after assignment get Assignment::None (like `allocate_registers`). See #41
+    let mut intervals: Vec<(&String, usize, usize, bool)> = intervals
+        .into_iter()
+        .filter_map(|(variable, interval)| {
+            if interval.end.is_none() {
+                allocation.insert(variable, Assignment::None);
+            }
+            interval
+                .end
+                .map(|end| (variable, interval.start, end, interval.is_float))
+        })
+        .collect();
+
+    intervals.sort_by_key(|(variable, start, ..)| (*start, *variable));
+
+    let mut register_pool = RegisterPool::new(registers.iter().map(|r| Assignment::Register(*r)).collect());
+    let mut float_register_pool =
+        RegisterPool::new(float_registers.iter().map(|r| Assignment::FloatRegister(*r)).collect());
+    let mut spilled_intervals = vec![];
+
+    for (variable, start, end, is_float) in intervals {
+        let pool = if is_float { &mut float_register_pool } else { &mut register_pool };
+        pool.expire(start);
+
+        if let Some(register) = pool.take_free() {
+            pool.activate(variable, start, end, register);
+            continue;
+        }
+
+        // Spill the interval that ends last, which is either an active interval (whose register is taken) or this one.
+        let is_active_spilled = pool.active.last().map_or(false, |(active_end, ..)| *active_end > end);
+
+        match pool.active.pop() {
+            Some((active_end, active_variable, active_start, register)) if is_active_spilled => {
+                spilled_intervals.push((active_variable, active_start, active_end));
+                pool.activate(variable, start, end, register);
+            }
+            active_interval => {
+                pool.active.extend(active_interval);
+                spilled_intervals.push((variable, start, end));
+            }
+        }
+    }
+
+    for pool in [register_pool, float_register_pool] {
+        for (_, variable, _, register) in pool.assigned.into_iter().chain(pool.active) {
+            allocation.insert(variable, pool.registers[register].clone());
+        }
+    }
+
+    // Assign each spilled variable to a stack slot that is not used by another spilled variable that is still live.
+    spilled_intervals.sort_by_key(|(variable, start, _)| (*start, *variable));
+
+    let mut active_slots: Vec<(usize, usize)> = vec![];
+    let mut free_slots: Vec<usize> = vec![];
+    let mut slot_count = 0;
+
+    for (variable, start, end) in spilled_intervals {
+        active_slots.retain(|(active_end, slot)| {
+            let is_active = *active_end >= start;
+            if !is_active {
+                free_slots.push(*slot);
+            }
+            is_active
+        });
+
+        free_slots.sort_unstable_by(|slot_1, slot_2| slot_2.cmp(slot_1));
+        let slot = free_slots.pop().unwrap_or_else(|| {
+            slot_count += 1;
+            slot_count - 1
+        });
+
+        active_slots.push((end, slot));
+        allocation.insert(variable, Assignment::Spill(slot));
+    }
+
+    if should_verify_allocation() {
+        verify_allocation(block, params, &allocation);
+    }
+    allocation
+}
+
+// The live interval of a variable, from the position of its definition to the position of its last reference (or
+// None, if the variable is never referenced).
+struct LiveInterval {
+    start: usize,
+    end: Option<usize>,
+    is_float: bool,
+}
+
+// Numbers the expressions of a block in program order, and creates the live interval of each variable.
+struct IntervalBuilder<'a, 'b> {
+    params: &'b Set<&'a String>,
+    position: usize,
+    intervals: Map<&'a String, LiveInterval>,
+
+    // The position of each call, and the variables that are live before the call.
+    calls: Vec<(usize, &'a RefCell<Set<String>>)>,
+}
+
+impl<'a> IntervalBuilder<'a, '_> {
+    // Advances to the next position.
+    fn next_position(&mut self) -> usize {
+        self.position += 1;
+        self.position
+    }
+
+    fn build_block(&mut self, block: &'a Block) {
+        for expr in &block.exprs {
+            self.build_expr(expr);
+        }
+    }
+
+    fn build_expr(&mut self, expr: &'a Expr) {
+        let position = self.next_position();
+
+        match expr {
+            Expr::Direct { expr } => self.reference(expr, position),
+            Expr::Let { id, init_expr, .. } => {
+                if let Expr::If { condition, then_block, else_block } = &**init_expr {
+                    // The branches write to the variable, so it is defined before the branches.
+                    self.reference(condition, position);
+                    self.define(id);
+                    self.build_branches(then_block, else_block.as_ref());
+                } else {
+                    self.build_expr(init_expr);
+                    self.define(id);
+                }
+            }
+            Expr::If { condition, then_block, else_block } => {
+                self.reference(condition, position);
+                self.build_branches(then_block, else_block.as_ref());
+            }
+            Expr::UnaryExpr { operand: expr, .. } | Expr::TypeCoercion { expr, .. } => self.reference(expr, position),
+            Expr::BinaryExpr { operand_1, operand_2, .. } => {
+                self.reference(operand_1, position);
+                self.reference(operand_2, position);
+            }
+            Expr::Call { args, live_variables, .. } => {
+                for arg in args {
+                    self.reference(arg, position);
+                }
+                self.calls.push((position, live_variables));
+            }
+        }
+    }
+
+    fn build_branches(&mut self, then_block: &'a Block, else_block: Option<&'a Block>) {
+        self.build_block(then_block);
+        if let Some(else_block) = else_block {
+            self.build_block(else_block);
+        }
+    }
+
+    // Starts the interval of a variable at the next position.
+    fn define(&mut self, id: &'a String) {
+        if self.params.contains(id) {
+            return;
+        }
+
+        let position = self.next_position();
+        self.intervals
+            .entry(id)
+            .or_insert(LiveInterval { start: position, end: None, is_float: false });
+    }
+
+    // Extends the interval of a referenced variable to `position`.
+    fn reference(&mut self, direct: &'a DirectExpr, position: usize) {
+        if let DirectExpr::Id { value, id_type } = direct {
+            if self.params.contains(value) {
+                return;
+            }
+
+            // Variables that are referenced before they are defined (like parameters that are not in `params`) are live
+            // from the start of the block.
+            let interval = self
+                .intervals
+                .entry(value)
+                .or_insert(LiveInterval { start: 0, end: None, is_float: false });
+
+            interval.end = Some(position);
+            interval.is_float = matches!(id_type, Type::Float);
+        }
+    }
+}
+
+// The registers of one kind (registers or float registers) while scanning, where registers are referred to by their
+// index in the pool.
+struct RegisterPool<'a> {
+    // The pool of registers, in the order that they are preferred.
+    registers: Vec<Assignment>,
+
+    // The registers that are not assigned to an active interval.
+    free: Vec<usize>,
+
+    // The active intervals, sorted by their end, as (end, variable, start, register).
+    active: Vec<(usize, &'a String, usize, usize)>,
+
+    // The intervals that are no longer active, and have kept their register.
+    assigned: Vec<(usize, &'a String, usize, usize)>,
+}
+
+impl<'a> RegisterPool<'a> {
+    fn new(registers: Vec<Assignment>) -> Self {
+        let free = (0..registers.len()).collect();
+        RegisterPool { registers, free, active: vec![], assigned: vec![] }
+    }
+
+    // Takes the free register that comes first in the pool, if there is one.
+    fn take_free(&mut self) -> Option<usize> {
+        let index = (0..self.free.len()).min_by_key(|index| self.free[*index])?;
+        Some(self.free.swap_remove(index))
+    }
+
+    // Frees the registers of the active intervals that end before `position`.
+    fn expire(&mut self, position: usize) {
+        while let Some((end, ..)) = self.active.first() {
+            if *end >= position {
+                break;
+            }
+
+            let interval = self.active.remove(0);
+            self.free.push(interval.3);
+            self.assigned.push(interval);
+        }
+    }
+
+    // Assigns a register to an interval, and makes it active.
+    fn activate(&mut self, variable: &'a String, start: usize, end: usize, register: usize) {
+        let index = self.active.partition_point(|(active_end, ..)| *active_end <= end);
+        self.active.insert(index, (end, variable, start, register));
+    }
+}
@@ -41,7 +41,7 @@ pub fn liveness_analysis<'a>(
 ) {
     match expr {
         Expr::Direct { expr } => liveness_analysis_direct(expr, live_variables, variable_frequencies, params),
-        Expr::Let { id, init_expr } => {
+        Expr::Let { id, init_expr, .. } => {
             live_variables.remove(id);
 
             // For variables that are created but never referenced after. These variables still need to be considered
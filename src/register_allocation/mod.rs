@@ -2,6 +2,9 @@
 
 //! The `register_allocation` module performs analysis needed for the register allocation optimization.
 
+pub mod allocation_verifier;
 pub mod conflict_analysis;
+pub mod linear_scan;
 pub mod liveness_analysis;
 pub mod register_allocator;
+pub mod spill_cost;
@@ -13,31 +13,56 @@
 //! a graph where each variable is a node and each edge represents a conflict between the variables. A conflict
 //! is when both variable's lifetimes intersect at some point, meaning they cannot be assigned the same register. Then,
 //! we k-color the graph to achieve an assignment where neighboring edges have different assignments.
+//!
+//! Variables that cannot be colored are spilled to the stack. Spilled variables are then colored again, using the
+//! same interference graph, onto a minimal number of stack slots, so that spilled variables that are never live at the
+//! same time share a slot. The compiler reserves a frame that is large enough for every slot (see `frame_size`).
+//!
+//! Before coloring, variables that are copies of each other (like `let a = b`) are coalesced, which means that they
+//! are merged into a single node, so that they get the same assignment and the copy becomes a no-op. Coalescing is
+//! conservative (Briggs and George tests), so it never makes a colorable graph uncolorable.
+//!
+//! When a variable must be spilled, the variable with the smallest spill cost is chosen (see `spill_cost.rs`).
+//!
+//! In debug builds, the allocation is then checked by `allocation_verifier.rs`.
 
 use asm::asm::{FloatRegister, Register};
+use clap::ValueEnum;
 use error_messages::internal_compiler_error;
-use ir::ir::Block;
+use ir::ir::{Block, DirectExpr, Expr};
+use register_allocation::allocation_verifier::{should_verify_allocation, verify_allocation};
 use register_allocation::conflict_analysis::{conflict_analysis, InterferenceGraph};
+use register_allocation::spill_cost::{spill_costs, SpillCost};
 
 /// An assignment of where to evaluate an variable.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Assignment {
     Register(Register),
     FloatRegister(FloatRegister),
-    Spill,
-    None, // Meaning to not store the result of the expression somewhere. See #41
+    Spill(usize), // The index of the stack slot that the variable is spilled to.
+    None,         // Meaning to not store the result of the expression somewhere. See #41
+}
+
+/// The register allocators that can be used. The names of each allocator are used in the CLI (`--regalloc`).
+#[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RegisterAllocator {
+    /// Graph coloring (see `allocate_registers`), which creates better allocations.
+    Graph,
+
+    /// Linear scan (see `linear_scan.rs`), which is faster for large programs.
+    Linear,
 }
 
 /// Creates an assignment of registers for each variable in the block.
 /// * block - the block to create the allocation for
-/// * registers - the pool of registers to assign variables to
-/// * `float_registers` - the pool of float registers to assign float variables to
+/// * registers - the pool of registers to assign variables to, where registers that come first are preferred
+/// * `float_registers` - the pool of float registers to assign float variables to, in the same way
 /// Returns a map of variable names to the corresponding assignment.
 pub fn allocate_registers<'a>(
     block: &'a Block,
     params: &Set<&'a String>,
-    registers: Set<&'a Register>,
-    float_registers: Set<&'a FloatRegister>,
+    registers: &[Register],
+    float_registers: &[FloatRegister],
 ) -> Map<&'a String, Assignment> {
     // Create a common type of register (base register)
     #[derive(Eq, Hash, PartialEq, Ord, PartialOrd)]
@@ -45,14 +70,12 @@ pub fn allocate_registers<'a>(
         Register(Register),
         FloatRegister(FloatRegister),
     }
-    let registers = registers.into_iter().map(|r| X86Register::Register(*r)).collect();
-    let float_registers = float_registers
-        .into_iter()
-        .map(|r| X86Register::FloatRegister(*r))
-        .collect();
+    let registers: Vec<X86Register> = registers.iter().map(|r| X86Register::Register(*r)).collect();
+    let float_registers: Vec<X86Register> = float_registers.iter().map(|r| X86Register::FloatRegister(*r)).collect();
 
     // Create a interference graph and frequency map
-    let (interference_graph, float_interference_graph, mut variable_frequencies) = conflict_analysis(block, params);
+    let (interference_graph, float_interference_graph, variable_frequencies) = conflict_analysis(block, params);
+    let mut spill_costs = spill_costs(block);
 
     // Result allocation.
     let mut allocation = Map::new();
@@ -64,13 +87,39 @@ pub fn allocate_registers<'a>(
         }
     }
 
+    // Coalesce copy related variables, where each coalesced variable is mapped to the variable it was merged into.
+    let mut copies = vec![];
+    find_copies(block, &mut copies);
+
+    let (mut interference_graph, mut float_interference_graph) = (interference_graph, float_interference_graph);
+    let mut coalesced_variables = Map::new();
+
+    coalesce(
+        &mut interference_graph,
+        &copies,
+        registers.len(),
+        &mut spill_costs,
+        &mut coalesced_variables,
+    );
+    coalesce(
+        &mut float_interference_graph,
+        &copies,
+        float_registers.len(),
+        &mut spill_costs,
+        &mut coalesced_variables,
+    );
+
+    // The number of stack slots that spilled variables have been assigned to so far.
+    let mut slot_count = 0;
+
     // Creates an assignment of registers for each variable in the block, for a given interference graph
     let mut allocate_registers_internal = |mut interference_graph: InterferenceGraph<'a>,
-                                           registers: Set<X86Register>| {
+                                           registers: Vec<X86Register>| {
         // A stack that contains variables that are color-able. Please see
         // https://stackoverflow.com/questions/14399608/chaitin-briggs-algorithm-explanation for an overview of the
         // algorithm.
         let mut colorable_nodes_stack: Vec<&String> = vec![];
+        let mut spilled_nodes: Vec<&String> = vec![];
 
         while interference_graph.size() > 0 {
             // Find a node N with degree less than R = registers.length
@@ -80,27 +129,50 @@ pub fn allocate_registers<'a>(
                 .find(|(_, neighbors)| neighbors.len() < registers.len())
             {
                 // Remove N and its associated edges from G and push N on a stack S
-                variable_frequencies.remove(node);
                 colorable_nodes_stack.push(node);
                 interference_graph.remove_node(node);
             } else {
                 // Otherwise the graph cannot be colored with R colors. Simplify the graph G by choosing a variable to
                 // spill and remove its node N from G.
-                // We spill the node that has the minimum heuristic.
+                // We spill the node that has the minimum spill cost, for its current degree.
                 let (&spilled_node, _) = interference_graph
                     .nodes
-                    .iter()
-                    .min_by_key(|(node, _)| spill_heuristic(variable_frequencies[*node]))
+                    .keys()
+                    .map(|node| (node, spill_costs[node].cost(interference_graph.degree(node))))
+                    .min_by(|(_, cost_1), (_, cost_2)| cost_1.total_cmp(cost_2))
                     .unwrap_or_else(|| internal_compiler_error("no variable to spill"));
 
                 // Remove the spilled node.
                 interference_graph.remove_node(spilled_node);
-                variable_frequencies.remove(&spilled_node);
 
-                allocation.insert(spilled_node, Assignment::Spill);
+                spilled_nodes.push(spilled_node);
             }
         }
 
+        // Assign each spilled node the first stack slot that none of the spilled nodes it conflicts with are assigned
+        // to. Slots of other interference graphs are not reused, since their variables do not have edges to this graph.
+        let first_slot = slot_count;
+        for (i, node) in spilled_nodes.iter().enumerate() {
+            let neighbor_slots: Set<usize> = spilled_nodes[..i]
+                .iter()
+                .filter(|neighbor| {
+                    interference_graph.neighbors_when_removed(node).contains(*neighbor)
+                        || interference_graph.neighbors_when_removed(neighbor).contains(*node)
+                })
+                .filter_map(|neighbor| match allocation.get(*neighbor) {
+                    Some(Assignment::Spill(slot)) => Some(*slot),
+                    _ => None,
+                })
+                .collect();
+
+            let slot = (first_slot..=first_slot + neighbor_slots.len())
+                .find(|slot| !neighbor_slots.contains(slot))
+                .unwrap_or_else(|| internal_compiler_error("no available stack slot"));
+
+            slot_count = slot_count.max(slot + 1);
+            allocation.insert(node, Assignment::Spill(slot));
+        }
+
         // While stack S contains a node N, Add N to graph G and assign it a color from the R colors
         while let Some(node) = colorable_nodes_stack.pop() {
             // Set of the registers that it's neighbors were assigned to.
@@ -115,10 +187,10 @@ pub fn allocate_registers<'a>(
                 }
             }
 
-            // Assign the node any register in the available pool (all registers - neighbor registers)
+            // Assign the node the first register in the available pool (all registers - neighbor registers)
             let register = registers
-                .difference(&neighbor_registers)
-                .next()
+                .iter()
+                .find(|register| !neighbor_registers.contains(register))
                 .unwrap_or_else(|| internal_compiler_error("no available register for color-able node"));
 
             match register {
@@ -130,15 +202,114 @@ pub fn allocate_registers<'a>(
 
     allocate_registers_internal(interference_graph, registers);
     allocate_registers_internal(float_interference_graph, float_registers);
+
+    // Coalesced variables get the same assignment as the variable that they were merged into.
+    for variable in coalesced_variables.keys() {
+        let assignment = allocation[resolve_coalesced(variable, &coalesced_variables)].clone();
+        allocation.insert(variable, assignment);
+    }
+
+    if should_verify_allocation() {
+        verify_allocation(block, params, &allocation);
+    }
     allocation
 }
 
-// Defines the Spill metric of a variable. See https://en.wikipedia.org/wiki/Spill_metric. Smaller means more likely to be spilled.
-// * frequency - the frequency (# of times it is referenced after definition) of the variable
-const fn spill_heuristic(frequency: usize) -> usize {
-    // Currently, our spill metric spills the variable that is referenced the least. In the future, consider
-    // adding a weighted sum of the frequency (normalized) and the inverse degree of the variable.
-    frequency
+// Finds the pairs of variables that are copies of each other, which are lets that are initialized to a variable
+// (`let a = b`), or to an if expression where a branch results in a variable (`let a = if c { ...; b } else { ... }`).
+fn find_copies<'a>(block: &'a Block, copies: &mut Vec<(&'a String, &'a String)>) {
+    for expr in &block.exprs {
+        let (id, expr) = match expr {
+            Expr::Let { id, init_expr, .. } => (Some(id), &**init_expr),
+            _ => (None, expr),
+        };
+
+        match (id, expr) {
+            (Some(id), Expr::Direct { expr: DirectExpr::Id { value, .. } }) => copies.push((id, value)),
+            (_, Expr::If { then_block, else_block, .. }) => {
+                for branch in std::iter::once(then_block).chain(else_block) {
+                    if let (Some(id), Some(Expr::Direct { expr: DirectExpr::Id { value, .. } })) =
+                        (id, branch.exprs.last())
+                    {
+                        copies.push((id, value));
+                    }
+                    find_copies(branch, copies);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Conservatively coalesces the copies that are in the interference graph, until no more copies can be coalesced.
+// * `register_count` - the number of registers that the graph is colored with.
+// * `coalesced_variables` - where each coalesced variable is mapped to the variable that it was merged into.
+fn coalesce<'a>(
+    interference_graph: &mut InterferenceGraph<'a>,
+    copies: &[(&'a String, &'a String)],
+    register_count: usize,
+    spill_costs: &mut Map<&'a String, SpillCost>,
+    coalesced_variables: &mut Map<&'a String, &'a String>,
+) {
+    let mut is_changed = true;
+
+    while is_changed {
+        is_changed = false;
+
+        for (variable_1, variable_2) in copies {
+            let variable_1 = resolve_coalesced(variable_1, coalesced_variables);
+            let variable_2 = resolve_coalesced(variable_2, coalesced_variables);
+
+            let (Some(neighbors_1), Some(neighbors_2)) = (
+                interference_graph.nodes.get(variable_1),
+                interference_graph.nodes.get(variable_2),
+            ) else {
+                continue;
+            };
+
+            if variable_1 == variable_2 || neighbors_1.contains(variable_2) {
+                continue;
+            }
+
+            // Briggs: the merged node has less than `register_count` neighbors of significant degree.
+            let significant_neighbors = neighbors_1
+                .union(neighbors_2)
+                .filter(|neighbor| {
+                    let is_common_neighbor = neighbors_1.contains(*neighbor) && neighbors_2.contains(*neighbor);
+                    interference_graph.degree(neighbor) - usize::from(is_common_neighbor) >= register_count
+                })
+                .count();
+
+            // George: every neighbor of one node is either a neighbor of the other node, or has insignificant degree.
+            let george = |neighbors: &Set<&String>, other_neighbors: &Set<&String>| {
+                neighbors.iter().all(|neighbor| {
+                    other_neighbors.contains(neighbor) || interference_graph.degree(neighbor) < register_count
+                })
+            };
+
+            if significant_neighbors < register_count
+                || george(neighbors_1, neighbors_2)
+                || george(neighbors_2, neighbors_1)
+            {
+                interference_graph.merge_node(variable_1, variable_2);
+                coalesced_variables.insert(variable_1, variable_2);
+
+                if let (Some(spill_cost_1), Some(spill_cost_2)) =
+                    (spill_costs.remove(variable_1), spill_costs.get_mut(variable_2))
+                {
+                    spill_cost_2.merge(&spill_cost_1);
+                }
+                is_changed = true;
+            }
+        }
+    }
+}
+
+// Gets the variable that a (possibly) coalesced variable was eventually merged into.
+fn resolve_coalesced<'a>(variable: &'a String, coalesced_variables: &Map<&'a String, &'a String>) -> &'a String {
+    coalesced_variables
+        .get(variable)
+        .map_or(variable, |into| resolve_coalesced(into, coalesced_variables))
 }
 
 // For the compiler, we want to use Hash tables for performance. However for testing, we need the result of the
@@ -0,0 +1,159 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Computes the cost of spilling each variable, which the register allocator uses to decide which variable to spill
+//! when the interference graph cannot be colored.
+//!
+//! See <https://en.wikipedia.org/wiki/Spill_metric>.
+//!
+//! The cost of a variable is its number of references divided by its degree in the interference graph, so that
+//! variables that are rarely needed, but conflict with many other variables, are spilled first. Each reference is
+//! weighted by how often it is estimated to run: a reference inside a branch of an `if` runs about half as often as the
+//! `if` itself, so it is weighted by `BRANCH_FREQUENCY` for each branch that it is nested in.
+//!
+//! Temporary variables that the translator creates for sub-expressions are usually defined right before their only
+//! use. Spilling these short-lived temporaries only adds a store and a load, without freeing a register for any other
+//! variable, so they are never spilled (unless every other variable is spilled too).
+
+use ir::ir::{Block, DirectExpr, Expr};
+use ir::translator::is_temp_identifier;
+use register_allocation::register_allocator::Map;
+
+/// The estimated fraction of the executions of an `if` in which a branch runs.
+const BRANCH_FREQUENCY: f64 = 0.5;
+
+/// The cost of spilling a variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpillCost {
+    /// The number of references of the variable, weighted by their estimated execution frequency.
+    pub weighted_references: f64,
+
+    /// Whether the variable is a temporary variable that is only referenced by the expression right after it is
+    /// defined.
+    pub is_short_lived: bool,
+}
+
+impl SpillCost {
+    /// The cost of spilling the variable, given its current degree in the interference graph. Smaller means more
+    /// likely to be spilled.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn cost(&self, degree: usize) -> f64 {
+        if self.is_short_lived {
+            f64::INFINITY
+        } else {
+            self.weighted_references / degree.max(1) as f64
+        }
+    }
+
+    /// Merges the cost of a variable that is coalesced into this variable (see `register_allocator.rs`). The merged
+    /// variable is only short-lived if both variables were.
+    pub fn merge(&mut self, other: &Self) {
+        self.weighted_references += other.weighted_references;
+        self.is_short_lived &= other.is_short_lived;
+    }
+}
+
+/// Computes the spill cost of each variable that is defined in a block (including variables of nested blocks).
+pub fn spill_costs(block: &Block) -> Map<&String, SpillCost> {
+    let mut spill_costs = Map::new();
+    let mut references = Map::new();
+
+    weigh_block(block, 1.0, &mut spill_costs, &mut references);
+    find_short_lived_block(block, &references, &mut spill_costs);
+    spill_costs
+}
+
+// Adds the weighted references of each variable in a block, where each reference has a weight of `frequency`. The
+// (unweighted) number of references of each variable is also counted in `references`.
+fn weigh_block<'a>(
+    block: &'a Block,
+    frequency: f64,
+    spill_costs: &mut Map<&'a String, SpillCost>,
+    references: &mut Map<&'a String, usize>,
+) {
+    for expr in &block.exprs {
+        weigh_expr(expr, frequency, spill_costs, references);
+    }
+}
+
+fn weigh_expr<'a>(
+    expr: &'a Expr,
+    frequency: f64,
+    spill_costs: &mut Map<&'a String, SpillCost>,
+    references: &mut Map<&'a String, usize>,
+) {
+    match expr {
+        Expr::Let { id, init_expr, .. } => {
+            spill_costs
+                .entry(id)
+                .or_insert(SpillCost { weighted_references: 0.0, is_short_lived: false });
+            return weigh_expr(init_expr, frequency, spill_costs, references);
+        }
+        Expr::If { then_block, else_block, .. } => {
+            for branch in std::iter::once(then_block).chain(else_block) {
+                weigh_block(branch, frequency * BRANCH_FREQUENCY, spill_costs, references);
+            }
+        }
+        _ => {}
+    }
+
+    for direct in operands(expr) {
+        if let DirectExpr::Id { value, .. } = direct {
+            *references.entry(value).or_default() += 1;
+            spill_costs
+                .entry(value)
+                .or_insert(SpillCost { weighted_references: 0.0, is_short_lived: false })
+                .weighted_references += frequency;
+        }
+    }
+}
+
+// Marks the temporary variables of a block that are only referenced by the expression right after they are defined.
+fn find_short_lived_block<'a>(
+    block: &'a Block,
+    references: &Map<&'a String, usize>,
+    spill_costs: &mut Map<&'a String, SpillCost>,
+) {
+    for (i, expr) in block.exprs.iter().enumerate() {
+        if let (Expr::Let { id, .. }, Some(next_expr)) = (expr, block.exprs.get(i + 1)) {
+            let next_references = operands(next_expr)
+                .filter(|direct| matches!(direct, DirectExpr::Id { value, .. } if value == id))
+                .count();
+
+            if is_temp_identifier(id) && next_references > 0 && references.get(id) == Some(&next_references) {
+                if let Some(spill_cost) = spill_costs.get_mut(id) {
+                    spill_cost.is_short_lived = true;
+                }
+            }
+        }
+
+        match expr {
+            Expr::Let { init_expr, .. } => {
+                if let Expr::If { then_block, else_block, .. } = &**init_expr {
+                    for branch in std::iter::once(then_block).chain(else_block) {
+                        find_short_lived_block(branch, references, spill_costs);
+                    }
+                }
+            }
+            Expr::If { then_block, else_block, .. } => {
+                for branch in std::iter::once(then_block).chain(else_block) {
+                    find_short_lived_block(branch, references, spill_costs);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// The directs that an expression references, not including the expressions of nested blocks.
+fn operands(expr: &Expr) -> Box<dyn Iterator<Item = &DirectExpr> + '_> {
+    match expr {
+        Expr::Direct { expr } => Box::new(std::iter::once(expr)),
+        Expr::Let { init_expr, .. } => operands(init_expr),
+        Expr::If { condition, .. } => Box::new(std::iter::once(&**condition)),
+        Expr::UnaryExpr { operand: expr, .. } | Expr::TypeCoercion { expr, .. } => Box::new(std::iter::once(&**expr)),
+        Expr::BinaryExpr { operand_1, operand_2, .. } => {
+            Box::new(std::iter::once(&**operand_1).chain(std::iter::once(&**operand_2)))
+        }
+        Expr::Call { args, .. } => Box::new(args.iter()),
+    }
+}
@@ -49,6 +49,12 @@ pub enum TokenKind {
     #[display(fmt = "fun")]
     Fun,
 
+    #[display(fmt = "@inline")]
+    Inline,
+
+    #[display(fmt = "@noinline")]
+    NoInline,
+
     #[display(fmt = ",")]
     Comma,
 
@@ -174,6 +180,8 @@ lazy_static! {
         token_pattern!(TokenKind::If,                r"if\b"),
         token_pattern!(TokenKind::Else,              r"else\b"),
         token_pattern!(TokenKind::Fun,               r"fun\b"),
+        token_pattern!(TokenKind::Inline,            r"@inline\b"),
+        token_pattern!(TokenKind::NoInline,          r"@noinline\b"),
         token_pattern!(TokenKind::Comma,             r","),
 
         // Arithmetic
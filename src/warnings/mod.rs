@@ -0,0 +1,6 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! The `warnings` module detects code in the input program that compiles, but is likely a mistake.
+
+pub mod unused_analysis;
+pub mod warnings;
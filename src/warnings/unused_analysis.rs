@@ -0,0 +1,199 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Detects unused code in the AST of a Solis program. Specifically, this analysis finds:
+//!   - `let` bindings that are never read. The register allocator gives these bindings `Assignment::None` (see #41),
+//!     so they are never stored anywhere at runtime.
+//!   - function parameters that are never read.
+//!   - functions that are never called (other than by themselves).
+//!   - expressions whose result is discarded and have no side effects, like `a + 1;` in the middle of a block. The
+//!     translator (and compiler) drop these expressions entirely.
+//!
+//! The analysis is done on the AST instead of the IR, since the IR does not have `position` ranges for pin pointing
+//! warnings in the source code. It walks the AST once, keeping a stack of scopes to resolve each identifier reference
+//! to its binding, and reports the bindings that were never referenced when each scope ends.
+
+use parser::ast::{BinaryExprKind, Block, Expr, ExprKind, Function, Program};
+use register_allocation::register_allocator::Set;
+use std::ops::Range;
+use warnings::warnings::{Warning, WarningKind};
+
+/// Finds the unused code of a program.
+/// * return - the warnings of the program, in the order that they appear in the source code.
+pub fn unused_analysis(program: &Program) -> Vec<Warning> {
+    let mut analysis = UnusedAnalysis {
+        scopes: vec![],
+        called_functions: Set::new(),
+        current_function: None,
+        warnings: vec![],
+    };
+
+    for function in &program.functions {
+        analysis.analyze_function(function);
+    }
+
+    analysis.analyze_scope(&program.body, true, vec![]);
+
+    for function in &program.functions {
+        if !analysis.called_functions.contains(&&function.id) {
+            analysis.warnings.push(Warning {
+                kind: WarningKind::UnusedFunctions,
+                position: function.position.clone(),
+                message: format!("Function `{}` is never called", function.id),
+            });
+        }
+    }
+
+    analysis.warnings.sort_by_key(|warning| warning.position.start);
+    analysis.warnings
+}
+
+// A binding (`let` or parameter) that is in scope.
+struct Binding<'a> {
+    id: &'a String,
+    position: &'a Range<usize>,
+    kind: WarningKind,
+    used: bool,
+}
+
+// State that is kept while traveling down the AST.
+struct UnusedAnalysis<'a> {
+    // Stack of scopes, where each scope is the bindings declared in that scope (in order of declaration).
+    scopes: Vec<Vec<Binding<'a>>>,
+
+    // Functions that have been called, excluding recursive calls of a function to itself.
+    called_functions: Set<&'a String>,
+
+    // The function that is currently being analyzed, if any.
+    current_function: Option<&'a String>,
+
+    warnings: Vec<Warning>,
+}
+
+impl<'a> UnusedAnalysis<'a> {
+    // Analyzes a function declaration, where the parameters are bound in the scope of the function body.
+    fn analyze_function(&mut self, function: &'a Function) {
+        self.current_function = Some(&function.id);
+
+        let params = function
+            .params
+            .iter()
+            .map(|param| Binding {
+                id: &param.id,
+                position: &param.position,
+                kind: WarningKind::UnusedParameters,
+                used: false,
+            })
+            .collect();
+
+        self.analyze_scope(&function.body, true, params);
+        self.current_function = None;
+    }
+
+    // Analyzes a block in a new scope.
+    // * `is_result_used` - true if the value that the block evaluates to is used.
+    // * bindings - bindings to start the scope with.
+    fn analyze_scope(&mut self, block: &'a Block, is_result_used: bool, bindings: Vec<Binding<'a>>) {
+        self.scopes.push(bindings);
+
+        for (i, expr) in block.exprs.iter().enumerate() {
+            let is_expr_result_used = is_result_used && i == block.exprs.len() - 1;
+
+            if !is_expr_result_used && !has_effect(expr) {
+                self.warnings.push(Warning {
+                    kind: WarningKind::NoEffect,
+                    position: expr.position.clone(),
+                    message: "Expression has no effect".to_string(),
+                });
+            }
+            self.analyze_expr(expr, is_expr_result_used);
+        }
+
+        // Report every binding that was never referenced, now that it is out of scope.
+        for binding in self.scopes.pop().unwrap_or_default() {
+            if !binding.used {
+                self.warnings.push(Warning {
+                    kind: binding.kind,
+                    position: binding.position.clone(),
+                    message: match binding.kind {
+                        WarningKind::UnusedParameters => format!("Unused parameter `{}`", binding.id),
+                        _ => format!("Unused variable `{}`", binding.id),
+                    },
+                });
+            }
+        }
+    }
+
+    // Analyzes an expression.
+    // * `is_result_used` - true if the value that the expression evaluates to is used.
+    fn analyze_expr(&mut self, expr: &'a Expr, is_result_used: bool) {
+        match &expr.kind {
+            ExprKind::Int { .. } | ExprKind::Bool { .. } | ExprKind::Float { .. } => (),
+            ExprKind::Id { value } => self.reference(value),
+            ExprKind::Let { id, id_position, init_expr, .. } => {
+                self.analyze_expr(init_expr, true);
+
+                // The binding is declared *after* the init_expr.
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.push(Binding {
+                        id,
+                        position: id_position,
+                        kind: WarningKind::UnusedVariables,
+                        used: false,
+                    });
+                }
+            }
+            ExprKind::If { condition, then_block, else_block } => {
+                self.analyze_expr(condition, true);
+                self.analyze_scope(then_block, is_result_used, vec![]);
+
+                if let Some(else_block) = else_block {
+                    self.analyze_scope(else_block, is_result_used, vec![]);
+                }
+            }
+            ExprKind::UnaryExpr { operand, .. } => self.analyze_expr(operand, true),
+            ExprKind::BinaryExpr { operand_1, operand_2, .. } => {
+                self.analyze_expr(operand_1, true);
+                self.analyze_expr(operand_2, true);
+            }
+            ExprKind::Call { id, args } => {
+                if self.current_function != Some(id) {
+                    self.called_functions.insert(id);
+                }
+
+                for arg in args {
+                    self.analyze_expr(arg, true);
+                }
+            }
+        }
+    }
+
+    // Marks the binding that `id` resolves to as used.
+    fn reference(&mut self, id: &String) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(binding) = scope.iter_mut().rev().find(|binding| binding.id == id) {
+                binding.used = true;
+                return;
+            }
+        }
+    }
+}
+
+// Returns true if evaluating the expression does something other than compute a value. Note that `if` expressions are
+// considered to have an effect, since each branch is analyzed separately. Like the dead code elimination of the IR
+// (see `is_pure`), int division and modulo have an effect (they crash on `0`, and on `i64::MIN / -1`), unless the
+// divisor is a constant other than `0` and `-1`. Since the AST has no types, only float literals make a division
+// a float division.
+fn has_effect(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Int { .. } | ExprKind::Bool { .. } | ExprKind::Float { .. } | ExprKind::Id { .. } => false,
+        ExprKind::UnaryExpr { operand, .. } => has_effect(operand),
+        ExprKind::BinaryExpr { kind: BinaryExprKind::Divide | BinaryExprKind::Mod, operand_2, .. }
+            if !matches!(operand_2.kind, ExprKind::Int { value } if value != 0 && value != -1)
+                && !matches!(operand_2.kind, ExprKind::Float { .. }) =>
+        {
+            true
+        }
+        ExprKind::BinaryExpr { operand_1, operand_2, .. } => has_effect(operand_1) || has_effect(operand_2),
+        ExprKind::Let { .. } | ExprKind::If { .. } | ExprKind::Call { .. } => true,
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Definitions for compiler warnings.
+//!
+//! Unlike a `compilation_error`, a warning does not stop compilation. Instead, it points at code that is valid Solis,
+//! but is most likely not what the user intended, like a `let` binding that is never read. Warnings are collected by
+//! analysis passes (see `unused_analysis.rs`) and reported all at once.
+//!
+//! Each kind of warning can be allowed (silenced) or warned individually, and all warnings can be turned into errors
+//! (`--deny-warnings`), which is useful for CI.
+
+use clap::ValueEnum;
+use colored::Colorize;
+use error_messages::compilation_warning;
+use register_allocation::register_allocator::Set;
+use std::ops::Range;
+use File;
+
+/// Different kinds of warnings. The names of each kind are used in the CLI (`-W <kind>` and `-A <kind>`).
+#[derive(ValueEnum, Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum WarningKind {
+    /// `let` bindings that are never read.
+    UnusedVariables,
+
+    /// Function parameters that are never read.
+    UnusedParameters,
+
+    /// Functions that are never called.
+    UnusedFunctions,
+
+    /// Expressions whose result is discarded and that have no side effects, like `a + 1;`.
+    NoEffect,
+}
+
+/// A warning within the Solis input program.
+#[derive(Debug)]
+pub struct Warning {
+    pub kind: WarningKind,
+
+    /// Where the warning is in the source code, for pin pointing.
+    pub position: Range<usize>,
+
+    pub message: String,
+}
+
+/// Describes which warnings to report, and how to report them.
+#[derive(Default)]
+pub struct WarningConfig {
+    /// Kinds of warnings that are silenced.
+    pub allowed: Set<WarningKind>,
+
+    /// If true, reporting any warning is a compilation error.
+    pub deny_warnings: bool,
+}
+
+impl WarningConfig {
+    /// `WarningConfig` constructor.
+    /// * allow - kinds of warnings to silence
+    /// * warn - kinds of warnings to report, which takes precedence over `allow`
+    /// * `deny_warnings` - if true, reporting any warning is a compilation error
+    pub fn new(allow: &[WarningKind], warn: &[WarningKind], deny_warnings: bool) -> Self {
+        Self {
+            allowed: allow.iter().filter(|kind| !warn.contains(kind)).copied().collect(),
+            deny_warnings,
+        }
+    }
+}
+
+/// Reports the warnings that are not allowed in `config`.
+///
+/// If `config.deny_warnings` is set and there were any warnings reported, we exit with an error (after every warning
+/// has been reported). Warnings are reported to stderr, so that they don't mix with the output of the program when
+/// using `--run`.
+/// * file: the original Solis file
+/// * warnings: the warnings to report
+pub fn report_warnings(file: &File, warnings: &[Warning], config: &WarningConfig) {
+    let reported_warnings: Vec<&Warning> = warnings
+        .iter()
+        .filter(|warning| !config.allowed.contains(&warning.kind))
+        .collect();
+
+    for warning in &reported_warnings {
+        eprintln!("{}", compilation_warning(file, &warning.position, &warning.message));
+    }
+
+    if config.deny_warnings && !reported_warnings.is_empty() {
+        let message = format!("{} warning(s) reported with `--deny-warnings`", reported_warnings.len());
+
+        if cfg!(feature = "test") {
+            panic!("Error: {}", message);
+        } else {
+            eprintln!("{}: {}", "Error".red().bold(), message.bold());
+            std::process::exit(exitcode::DATAERR)
+        }
+    }
+}
@@ -4,7 +4,7 @@
 
 use expect_test::expect;
 use solis::asm::asm::{FloatRegister::*, Instruction::*, Operand::*, Register::*};
-use solis::asm::asm_writer::write_instructions_to_file;
+use solis::asm::asm_writer::{write_instructions_to_file, Assembler};
 use std::fs;
 use std::path::Path;
 
@@ -95,7 +95,7 @@ fn test_basic() {
     ];
 
     let temporary_file = "./build/solis_tests/asm_writer_test.s";
-    write_instructions_to_file(instructions, Path::new(temporary_file));
+    write_instructions_to_file(instructions, Path::new(temporary_file), Assembler::Nasm);
     expect![[r#"
         global _some_label
         extern _some_label
@@ -123,7 +123,7 @@ fn test_basic() {
         	dq `some_label2`, 0
         	dq 24
         align 24
-        	lea 2, [_some_label2]
+        	lea 2, [rel _some_label2]
         	mov QWORD [rax + 1], rax
         	mov rax, QWORD [rax + 1]
         	mov QWORD [rax + 1], 1
@@ -0,0 +1,175 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the NASM and GNU assembler writers, on the same instructions.
+
+use expect_test::expect;
+use solis::asm::asm::{FloatRegister::*, Instruction::*, Operand, Operand::*, Register::*};
+use test_utils::asm_writers_check;
+
+fn stack(offset: i64) -> Operand {
+    MemOffset(Box::new(Reg(Rsp)), Box::new(Imm(offset)))
+}
+
+#[test]
+fn test_directives() {
+    asm_writers_check(
+        &[
+            SourceFile("main.sol".to_string()),
+            Global("entry".to_string()),
+            Extern("print".to_string()),
+            Section("text".to_string()),
+            Label("entry".to_string()),
+            Line(3),
+            Ret,
+            Section("rodata".to_string()),
+            Align(8),
+            Label("data".to_string()),
+            DqLabel("entry".to_string()),
+            DqInt(-24),
+            DqString("abc".to_string()),
+        ],
+        expect![[r#"
+            %line 1+0 main.sol
+            global entry
+            extern print
+            	section .text
+            entry:
+            %line 3+0
+            	ret
+            	section .rodata
+            align 8
+            data:
+            	dq entry
+            	dq -24
+            	dq `abc`, 0
+        "#]],
+        expect![[r#"
+            .intel_syntax noprefix
+            	.section .note.GNU-stack,"",@progbits
+            	.file 1 "main.sol"
+            	.globl entry
+            	.extern print
+            	.text
+            entry:
+            	.loc 1 3
+            	ret
+            	.section .rodata
+            	.balign 8
+            data:
+            	.quad entry
+            	.quad -24
+            1:
+            	.ascii "abc"
+            	.skip (8 - (. - 1b) % 8) % 8
+            	.quad 0
+        "#]],
+    );
+}
+
+#[test]
+fn test_operands() {
+    asm_writers_check(
+        &[
+            Label("entry".to_string()),
+            Mov(Reg(Rax), Imm(-123)),
+            Mov(stack(8), Reg(Rbx)),
+            Mov(Reg(R15), stack(0)),
+            Mov(stack(16), Imm(1)),
+            MovByte(stack(8), Reg(Rax)),
+            Setle(Reg(R8)),
+            Mov(Reg(R14), FloatImm(1.5)),
+            Movq(FloatReg(Xmm1), MemLabel("float__3ff8000000000000".to_string())),
+            MovByte(Reg(Rax), MemLabel("flag".to_string())),
+            LeaLabel(Reg(Rax), "entry".to_string()),
+            ComputedJmp(Reg(Rax)),
+        ],
+        expect![[r#"
+            entry:
+            	mov rax, -123
+            	mov QWORD [rsp + 8], rbx
+            	mov r15, QWORD [rsp + 0]
+            	mov QWORD [rsp + 16], 1
+            	mov BYTE [rsp + 8], al
+            	setle r8b
+            	mov r14, __?float64?__(1.5)
+            	movq xmm1, QWORD [rel float__3ff8000000000000]
+            	mov al, BYTE [rel flag]
+            	lea rax, [rel entry]
+            	jmp rax
+        "#]],
+        expect![[r#"
+            .intel_syntax noprefix
+            	.section .note.GNU-stack,"",@progbits
+            entry:
+            	mov rax, -123
+            	mov QWORD PTR [rsp + 8], rbx
+            	mov r15, QWORD PTR [rsp + 0]
+            	mov QWORD PTR [rsp + 16], 1
+            	mov BYTE PTR [rsp + 8], al
+            	setle r8b
+            	mov r14, 4609434218613702656
+            	movq xmm1, QWORD PTR [rip + float__3ff8000000000000]
+            	mov al, BYTE PTR [rip + flag]
+            	lea rax, [rip + entry]
+            	jmp rax
+        "#]],
+    );
+}
+
+#[test]
+fn test_instructions() {
+    asm_writers_check(
+        &[
+            Push(Reg(Rbp)),
+            Mul3(Reg(R8), Reg(R9), Imm(10)),
+            Mul1(stack(0)),
+            Cqo,
+            Div(Reg(R8)),
+            Sar(Reg(Rdx), Imm(63)),
+            Cmp(Reg(R8), Imm(4)),
+            Jnl("else__0".to_string()),
+            Ucomisd(FloatReg(Xmm1), FloatReg(Xmm2)),
+            Jp("else__0".to_string()),
+            Cmpsd(FloatReg(Xmm14), MemLabel("float__0".to_string()), 1),
+            Call("print".to_string()),
+            Comment("some comment".to_string()),
+            Pop(Reg(Rbp)).annotated("some annotation"),
+        ],
+        expect![[r#"
+            	push rbp
+            	imul r8, r9, 10
+            	imul QWORD [rsp + 0]
+            	cqo
+            	idiv r8
+            	sar rdx, 63
+            	cmp r8, 4
+            	jnl else__0
+            	ucomisd xmm1, xmm2
+            	jp else__0
+            	cmpsd xmm14, QWORD [rel float__0], 1
+            	call print
+
+            ; some comment
+            	pop rbp                                 ; some annotation
+        "#]],
+        expect![[r#"
+            .intel_syntax noprefix
+            	.section .note.GNU-stack,"",@progbits
+            	push rbp
+            	imul r8, r9, 10
+            	imul QWORD PTR [rsp + 0]
+            	cqo
+            	idiv r8
+            	sar rdx, 63
+            	cmp r8, 4
+            	jnl else__0
+            	ucomisd xmm1, xmm2
+            	jp else__0
+            	cmpsd xmm14, QWORD PTR [rip + float__0], 1
+            	call print
+
+            # some comment
+            	pop rbp                                 # some annotation
+        "#]],
+    );
+}
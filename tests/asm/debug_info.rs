@@ -0,0 +1,171 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the debug info of the built-in assembler (with `-g`).
+
+use expect_test::expect;
+use test_utils::debug_info_check;
+
+#[test]
+fn test_lets() {
+    debug_info_check(
+        "debug_info_lets",
+        r"
+            let a: int = 1 + 2
+            let b: bool = a < 3
+            let c: float = 2.5
+
+            let d: int = a * 4
+            a + d
+        ",
+        expect![[r#"
+            <1><44>: Abbrev Number: 2 (DW_TAG_subprogram)
+            <45>   DW_AT_name        : entry
+            <4b>   DW_AT_external    : 1
+            <4b>   DW_AT_low_pc      : 0
+            <53>   DW_AT_high_pc     : 0x32
+            <2><5b>: Abbrev Number: 4 (DW_TAG_variable)
+            <5c>   DW_AT_name        : a
+            <5e>   DW_AT_decl_line   : 2
+            <5f>   DW_AT_type        : <0x2c>
+            <63>   DW_AT_location    : 1 byte block: 59 	(DW_OP_reg9 (r9))
+            <2><65>: Abbrev Number: 5 (DW_TAG_variable)
+            <66>   DW_AT_name        : b
+            <68>   DW_AT_decl_line   : 3
+            <69>   DW_AT_type        : <0x33>
+            <2><6d>: Abbrev Number: 5 (DW_TAG_variable)
+            <6e>   DW_AT_name        : c
+            <70>   DW_AT_decl_line   : 4
+            <71>   DW_AT_type        : <0x3b>
+            <2><75>: Abbrev Number: 4 (DW_TAG_variable)
+            <76>   DW_AT_name        : d
+            <78>   DW_AT_decl_line   : 6
+            <79>   DW_AT_type        : <0x2c>
+            <7d>   DW_AT_location    : 1 byte block: 58 	(DW_OP_reg8 (r8))
+            <2><7f>: Abbrev Number: 0
+            <1><80>: Abbrev Number: 0"#]],
+    );
+}
+
+#[test]
+fn test_if() {
+    debug_info_check(
+        "debug_info_if",
+        r"
+            let a: int = 5
+            let b: int = if a > 2 {
+                let c: int = a / 2
+                c - 1
+            } else {
+                let d: int = a * 3
+                d
+            }
+            b
+        ",
+        expect![[r#"
+            <1><44>: Abbrev Number: 2 (DW_TAG_subprogram)
+            <45>   DW_AT_name        : entry
+            <4b>   DW_AT_external    : 1
+            <4b>   DW_AT_low_pc      : 0
+            <53>   DW_AT_high_pc     : 0x55
+            <2><5b>: Abbrev Number: 4 (DW_TAG_variable)
+            <5c>   DW_AT_name        : a
+            <5e>   DW_AT_decl_line   : 2
+            <5f>   DW_AT_type        : <0x2c>
+            <63>   DW_AT_location    : 1 byte block: 58 	(DW_OP_reg8 (r8))
+            <2><65>: Abbrev Number: 4 (DW_TAG_variable)
+            <66>   DW_AT_name        : b
+            <68>   DW_AT_decl_line   : 3
+            <69>   DW_AT_type        : <0x2c>
+            <6d>   DW_AT_location    : 1 byte block: 58 	(DW_OP_reg8 (r8))
+            <2><6f>: Abbrev Number: 4 (DW_TAG_variable)
+            <70>   DW_AT_name        : c
+            <72>   DW_AT_decl_line   : 4
+            <73>   DW_AT_type        : <0x2c>
+            <77>   DW_AT_location    : 1 byte block: 58 	(DW_OP_reg8 (r8))
+            <2><79>: Abbrev Number: 4 (DW_TAG_variable)
+            <7a>   DW_AT_name        : d
+            <7c>   DW_AT_decl_line   : 7
+            <7d>   DW_AT_type        : <0x2c>
+            <81>   DW_AT_location    : 1 byte block: 58 	(DW_OP_reg8 (r8))
+            <2><83>: Abbrev Number: 0
+            <1><84>: Abbrev Number: 0"#]],
+    );
+}
+
+#[test]
+fn test_spills() {
+    debug_info_check(
+        "debug_info_spills",
+        r"
+            let a: int = 1
+            let b: int = 2
+            let c: int = 3
+            let d: int = 4
+            let e: int = 5
+            let f: int = 6
+            let g: int = 7
+            let h: int = 8
+            let i: int = 9
+            let j: int = 10
+            a + b + c + d + e + f + g + h + i + j
+        ",
+        expect![[r#"
+            <1><44>: Abbrev Number: 2 (DW_TAG_subprogram)
+            <45>   DW_AT_name        : entry
+            <4b>   DW_AT_external    : 1
+            <4b>   DW_AT_low_pc      : 0
+            <53>   DW_AT_high_pc     : 0xb4
+            <2><5b>: Abbrev Number: 4 (DW_TAG_variable)
+            <5c>   DW_AT_name        : a
+            <5e>   DW_AT_decl_line   : 2
+            <5f>   DW_AT_type        : <0x2c>
+            <63>   DW_AT_location    : 2 byte block: 77 8 	(DW_OP_breg7 (rsp): 8)
+            <2><66>: Abbrev Number: 4 (DW_TAG_variable)
+            <67>   DW_AT_name        : b
+            <69>   DW_AT_decl_line   : 3
+            <6a>   DW_AT_type        : <0x2c>
+            <6e>   DW_AT_location    : 1 byte block: 5f 	(DW_OP_reg15 (r15))
+            <2><70>: Abbrev Number: 4 (DW_TAG_variable)
+            <71>   DW_AT_name        : c
+            <73>   DW_AT_decl_line   : 4
+            <74>   DW_AT_type        : <0x2c>
+            <78>   DW_AT_location    : 2 byte block: 77 0 	(DW_OP_breg7 (rsp): 0)
+            <2><7b>: Abbrev Number: 4 (DW_TAG_variable)
+            <7c>   DW_AT_name        : d
+            <7e>   DW_AT_decl_line   : 5
+            <7f>   DW_AT_type        : <0x2c>
+            <83>   DW_AT_location    : 1 byte block: 5d 	(DW_OP_reg13 (r13))
+            <2><85>: Abbrev Number: 4 (DW_TAG_variable)
+            <86>   DW_AT_name        : e
+            <88>   DW_AT_decl_line   : 6
+            <89>   DW_AT_type        : <0x2c>
+            <8d>   DW_AT_location    : 1 byte block: 5c 	(DW_OP_reg12 (r12))
+            <2><8f>: Abbrev Number: 4 (DW_TAG_variable)
+            <90>   DW_AT_name        : f
+            <92>   DW_AT_decl_line   : 7
+            <93>   DW_AT_type        : <0x2c>
+            <97>   DW_AT_location    : 1 byte block: 53 	(DW_OP_reg3 (rbx))
+            <2><99>: Abbrev Number: 4 (DW_TAG_variable)
+            <9a>   DW_AT_name        : g
+            <9c>   DW_AT_decl_line   : 8
+            <9d>   DW_AT_type        : <0x2c>
+            <a1>   DW_AT_location    : 1 byte block: 5b 	(DW_OP_reg11 (r11))
+            <2><a3>: Abbrev Number: 4 (DW_TAG_variable)
+            <a4>   DW_AT_name        : h
+            <a6>   DW_AT_decl_line   : 9
+            <a7>   DW_AT_type        : <0x2c>
+            <ab>   DW_AT_location    : 1 byte block: 5a 	(DW_OP_reg10 (r10))
+            <2><ad>: Abbrev Number: 4 (DW_TAG_variable)
+            <ae>   DW_AT_name        : i
+            <b0>   DW_AT_decl_line   : 10
+            <b1>   DW_AT_type        : <0x2c>
+            <b5>   DW_AT_location    : 1 byte block: 59 	(DW_OP_reg9 (r9))
+            <2><b7>: Abbrev Number: 4 (DW_TAG_variable)
+            <b8>   DW_AT_name        : j
+            <ba>   DW_AT_decl_line   : 11
+            <bb>   DW_AT_type        : <0x2c>
+            <bf>   DW_AT_location    : 1 byte block: 58 	(DW_OP_reg8 (r8))
+            <2><c1>: Abbrev Number: 0
+            <1><c2>: Abbrev Number: 0"#]],
+    );
+}
@@ -0,0 +1,263 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Basic tests for the emulator, on hand written instructions.
+
+use expect_test::expect;
+use solis::asm::asm::{FloatRegister::*, Instruction, Instruction::*, Operand::*, Register::*};
+use test_utils::emulate_check;
+
+// Wraps instructions in the `entry` label, with a return at the end.
+fn entry(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    let mut entry = vec![Global("entry".to_string()), Label("entry".to_string())];
+    entry.extend(instructions);
+    entry.push(Ret);
+    entry
+}
+
+fn stack(offset: i64) -> solis::asm::asm::Operand {
+    MemOffset(Box::new(Reg(Rsp)), Box::new(Imm(offset)))
+}
+
+#[test]
+fn test_arithmetic() {
+    emulate_check(
+        &entry(vec![
+            Mov(Reg(Rax), Imm(5)),
+            Mov(Reg(R8), Imm(3)),
+            Add(Reg(Rax), Reg(R8)),
+            Sub(Reg(Rax), Imm(1)),
+            Mul(Reg(Rax), Reg(R8)),
+            Mul3(Reg(Rax), Reg(Rax), Imm(2)),
+            Neg(Reg(Rax)),
+        ]),
+        expect!["Ok(-42)"],
+    );
+}
+
+#[test]
+fn test_division() {
+    emulate_check(
+        &entry(vec![
+            Mov(Reg(Rax), Imm(-7)),
+            Cqo,
+            Mov(Reg(R8), Imm(2)),
+            Div(Reg(R8)),
+            Mul3(Reg(Rax), Reg(Rax), Imm(10)),
+            Add(Reg(Rax), Reg(Rdx)),
+        ]),
+        expect!["Ok(-31)"],
+    );
+}
+
+#[test]
+fn test_stack() {
+    emulate_check(
+        &entry(vec![
+            Mov(stack(-8), Imm(12)),
+            Push(Imm(30)),
+            Mov(Reg(Rax), stack(0)),
+            Pop(Reg(R8)),
+            Add(Reg(Rax), stack(-8)),
+        ]),
+        expect!["Ok(60)"],
+    );
+}
+
+#[test]
+fn test_branches() {
+    emulate_check(
+        &entry(vec![
+            Mov(Reg(Rax), Imm(0)),
+            Mov(Reg(R8), Imm(3)),
+            Label("loop".to_string()),
+            Add(Reg(Rax), Reg(R8)),
+            Sub(Reg(R8), Imm(1)),
+            Cmp(Reg(R8), Imm(0)),
+            Jg("loop".to_string()),
+        ]),
+        expect!["Ok(6)"],
+    );
+}
+
+#[test]
+fn test_setcc() {
+    emulate_check(
+        &entry(vec![
+            Mov(Reg(R8), Imm(-1)),
+            Cmp(Reg(R8), Imm(2)),
+            Mov(Reg(Rax), Imm(0)),
+            Setl(Reg(Rax)),
+            Mov(stack(-8), Imm(0)),
+            Setz(stack(-8)),
+            Add(Reg(Rax), stack(-8)),
+        ]),
+        expect!["Ok(1)"],
+    );
+}
+
+#[test]
+fn test_call() {
+    let instructions = vec![
+        Label("entry".to_string()),
+        Mov(Reg(Rax), Imm(1)),
+        Call("double".to_string()),
+        Call("double".to_string()),
+        Ret,
+        Label("double".to_string()),
+        Add(Reg(Rax), Reg(Rax)),
+        Ret,
+    ];
+    emulate_check(&instructions, expect!["Ok(4)"]);
+}
+
+#[test]
+fn test_computed_jump() {
+    emulate_check(
+        &entry(vec![
+            LeaLabel(Reg(R8), "end".to_string()),
+            Mov(Reg(Rax), Imm(1)),
+            ComputedJmp(Reg(R8)),
+            Mov(Reg(Rax), Imm(2)),
+            Label("end".to_string()),
+        ]),
+        expect!["Ok(1)"],
+    );
+}
+
+#[test]
+fn test_floats() {
+    emulate_check(
+        &entry(vec![
+            Mov(Reg(R8), FloatImm(1.5)),
+            Movq(FloatReg(Xmm1), Reg(R8)),
+            Mov(Reg(R8), Imm(3)),
+            Cvtsi2sd(FloatReg(Xmm2), Reg(R8)),
+            Mulsd(FloatReg(Xmm2), FloatReg(Xmm1)),
+            Xorpd(FloatReg(Xmm3), FloatReg(Xmm3)),
+            Subsd(FloatReg(Xmm3), FloatReg(Xmm2)),
+            Cvttsd2si(Reg(Rax), FloatReg(Xmm3)),
+        ]),
+        expect!["Ok(-4)"],
+    );
+}
+
+#[test]
+fn test_float_comparison() {
+    // NLE (6) is true for NaN.
+    emulate_check(
+        &entry(vec![
+            Xorpd(FloatReg(Xmm1), FloatReg(Xmm1)),
+            Divsd(FloatReg(Xmm1), FloatReg(Xmm1)),
+            Movq(FloatReg(Xmm2), FloatReg(Xmm1)),
+            Cmpsd(FloatReg(Xmm2), FloatReg(Xmm1), 6),
+            Movq(Reg(Rax), FloatReg(Xmm2)),
+            And(Reg(Rax), Imm(1)),
+        ]),
+        expect!["Ok(1)"],
+    );
+}
+
+#[test]
+fn test_invalid_mem_mem() {
+    emulate_check(
+        &entry(vec![Mov(stack(-8), Imm(1)), Mov(stack(-16), stack(-8))]),
+        expect![[r#"Err(InvalidOperands("mov QWORD [rsp + -16], QWORD [rsp + -8]"))"#]],
+    );
+}
+
+#[test]
+fn test_invalid_imm() {
+    emulate_check(
+        &entry(vec![Mov(Reg(Rax), Imm(1)), Add(Reg(Rax), Imm(1 << 40))]),
+        expect![[r#"Err(InvalidOperands("add rax, 1099511627776"))"#]],
+    );
+    emulate_check(
+        &entry(vec![Cmp(Imm(1), Imm(1))]),
+        expect![[r#"Err(InvalidOperands("cmp 1, 1"))"#]],
+    );
+    emulate_check(
+        &entry(vec![Mov(Reg(Rax), Imm(1)), Div(Imm(1))]),
+        expect![[r#"Err(InvalidOperands("idiv 1"))"#]],
+    );
+}
+
+#[test]
+fn test_undefined_register() {
+    emulate_check(
+        &entry(vec![Mov(Reg(Rax), Reg(R8))]),
+        expect!["Err(UndefinedRegister(R8))"],
+    );
+}
+
+#[test]
+fn test_undefined_memory() {
+    emulate_check(
+        &entry(vec![Mov(Reg(Rax), stack(-8))]),
+        expect!["Err(UndefinedMemory(2147418096))"],
+    );
+}
+
+#[test]
+fn test_undefined_flags() {
+    emulate_check(
+        &entry(vec![
+            Mov(Reg(Rax), Imm(2)),
+            Mul(Reg(Rax), Reg(Rax)),
+            Je("entry".to_string()),
+        ]),
+        expect!["Err(UndefinedFlags)"],
+    );
+}
+
+#[test]
+fn test_divide_error() {
+    emulate_check(
+        &entry(vec![Mov(Reg(Rax), Imm(1)), Cqo, Mov(Reg(R8), Imm(0)), Div(Reg(R8))]),
+        expect!["Err(DivideError)"],
+    );
+}
+
+#[test]
+fn test_unknown_label() {
+    emulate_check(
+        &entry(vec![Jmp("nowhere".to_string())]),
+        expect![[r#"Err(UnknownLabel("nowhere"))"#]],
+    );
+}
+
+#[test]
+fn test_unbalanced_stack() {
+    emulate_check(
+        &entry(vec![Mov(Reg(Rax), Imm(1)), Push(Reg(Rax))]),
+        expect!["Err(InvalidJump(1))"],
+    );
+}
+
+#[test]
+fn test_infinite_loop() {
+    emulate_check(
+        &entry(vec![Label("loop".to_string()), Jmp("loop".to_string())]),
+        expect!["Err(StepLimitExceeded)"],
+    );
+}
+
+#[test]
+fn test_clobbered_register() {
+    emulate_check(
+        &entry(vec![Mov(Reg(Rax), Imm(1)), Mov(Reg(Rbx), Reg(Rax))]),
+        expect!["Err(ClobberedRegister(Rbx))"],
+    );
+}
+
+#[test]
+fn test_saved_register() {
+    emulate_check(
+        &entry(vec![
+            Push(Reg(Rbx)),
+            Mov(Reg(Rbx), Imm(1)),
+            Mov(Reg(Rax), Reg(Rbx)),
+            Pop(Reg(Rbx)),
+        ]),
+        expect!["Ok(1)"],
+    );
+}
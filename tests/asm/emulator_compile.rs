@@ -0,0 +1,185 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests that emulate the output of the compiler, where the result must match the result of the interpreter.
+
+use solis::asm::emulator::{emulate, EmulatorError};
+use solis::compiler::compiler::compile;
+use solis::ir::translator::translate_program;
+use solis::parser::parser::parse;
+use solis::tokenizer::tokenizer::tokenize;
+use solis::File;
+use std::fs;
+use test_utils::compile_emulate_check;
+
+#[test]
+fn test_int_expressions() {
+    compile_emulate_check("let a: int = 1 + 2 * 3 - -4; let b: int = a / 3 + a % 4; b * a - 7");
+}
+
+#[test]
+fn test_bool_expressions() {
+    compile_emulate_check("let a: bool = !(1 < 2) == false; let b: int = 3; a != (b >= 4)");
+}
+
+#[test]
+fn test_float_expressions() {
+    compile_emulate_check("let a: float = 1.5 * 2 + 1 / 4.0; let b: bool = a > 2.0; -a");
+}
+
+#[test]
+fn test_if() {
+    compile_emulate_check(
+        "
+        let a: int = 3
+        let b: int = if a > 2 { let c: int = a * 2; c } else { 0 }
+        if b == 6 { b + 1 } else { b }
+        ",
+    );
+}
+
+#[test]
+fn test_spills() {
+    // More variables are live than there are registers.
+    compile_emulate_check(
+        "
+        let a: int = 1; let b: int = 2; let c: int = 3; let d: int = 4; let e: int = 5
+        let f: int = 6; let g: int = 7; let h: int = 8; let i: int = 9
+        let j: int = a * b + c * d - e / (f % 4 + 1) + g * h % (i + 1)
+        a + b + c + d + e + f + g + h + i + j
+        ",
+    );
+}
+
+#[test]
+fn test_fused_comparisons() {
+    compile_emulate_check(
+        "
+        let a: int = 3
+        let b: int = if a < 3 { 1 } else { 2 }
+        let c: int = if a <= 3 { b * 10 } else { b }
+        let d: int = if 4 > a { c * 10 } else { c }
+        let e: int = if a >= 4 { d * 10 } else { d }
+        let f: int = if a == e { e * 10 } else { e }
+        if f != 3 { f + 1 } else { f }
+        ",
+    );
+}
+
+#[test]
+fn test_fused_float_comparisons() {
+    // Comparisons with NaN are false, except for `>`, `>=` and `!=` (which match the predicates of `cmpsd`).
+    for value in ["1.5", "2.5", "3.5", "0.0 / 0.0"] {
+        compile_emulate_check(&format!(
+            "
+            let zero: float = 0.0
+            let a: float = {value} + zero
+            let b: float = 2.5
+            let c: int = if a < b {{ 1 }} else {{ 2 }}
+            let d: int = if a <= b {{ c * 10 }} else {{ c }}
+            let e: int = if a > b {{ d * 10 }} else {{ d }}
+            let f: int = if a >= b {{ e * 10 }} else {{ e }}
+            let g: int = if a == b {{ f * 10 }} else {{ f }}
+            if a != b {{ g + 1 }} else {{ g }}
+            "
+        ));
+    }
+}
+
+#[test]
+fn test_strength_reduction() {
+    let constants = [
+        -9,
+        -8,
+        -7,
+        -2,
+        -1,
+        0,
+        1,
+        2,
+        3,
+        5,
+        6,
+        7,
+        8,
+        9,
+        10,
+        15,
+        16,
+        17,
+        100,
+        641,
+        1 << 20,
+        2_147_483_647,
+    ];
+    let dividends = [
+        "-9223372036854775807 - 1",
+        "-1000003",
+        "-17",
+        "-1",
+        "0",
+        "1",
+        "17",
+        "1000003",
+        "9223372036854775807",
+    ];
+
+    for constant in constants {
+        for dividend in dividends {
+            // Division by 0 (and `i64::MIN / -1`) crash, and are not reduced, so they are not tested here.
+            let division = if matches!(constant, 0 | -1) {
+                String::new()
+            } else {
+                format!("a / {constant} * 3 + a % {constant}")
+            };
+
+            compile_emulate_check(&format!(
+                "
+                let a: int = {dividend}
+                let b: int = a * {constant}
+                b + {}
+                ",
+                if division.is_empty() { "0" } else { &division }
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_integration_programs() {
+    let names = [
+        "basic_1", "random_1", "random_2", "random_3", "random_4", "random_5", "random_6", "random_7", "random_8",
+        "random_9",
+    ];
+
+    for name in names {
+        compile_emulate_check(&fs::read_to_string(format!("./tests/integration/{name}.sol")).unwrap());
+    }
+}
+
+#[test]
+fn test_int_operand_over_32_bits() {
+    // Most x86 instructions only have 32 bit immediates, so `sub r14, 9223372036854775807` can't be encoded.
+    compile_emulate_check("let a: int = 3864086198765546641 + 262; a - 9223372036854775807");
+}
+
+#[test]
+fn test_spilled_int_over_32_bits() {
+    // Immediates moved to memory must fit in 32 bits, so a spilled variable can't be bound with a single `mov`.
+    compile_emulate_check(
+        "
+        let a: int = 1; let b: int = 2; let c: int = 3; let d: int = 4; let e: int = 5
+        let f: int = 6; let g: int = 7; let h: int = 453069727781343277
+        a + b + c + d + e + f + g + h
+        ",
+    );
+}
+
+#[test]
+fn test_discarded_division_by_zero() {
+    // Int divisions whose result is unused must still be compiled, so that dividing by zero crashes like it does in the
+    // interpreter.
+    let file = File { name: String::new(), contents: "let a: int = 0; 5 % a; a".to_string() };
+    let instructions = compile(translate_program(&file, parse(&file, tokenize(&file))));
+
+    assert_eq!(emulate(&instructions), Err(EmulatorError::DivideError));
+}
@@ -0,0 +1,274 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the built-in encoder, on the encodings of instructions, jumps, sections and relocations.
+
+use expect_test::expect;
+use solis::asm::asm::{FloatRegister::*, Instruction::*, Operand, Operand::*, Register, Register::*};
+use test_utils::{encoder_check, encodings_check};
+
+fn mem(register: Register, offset: i64) -> Operand {
+    MemOffset(Box::new(Reg(register)), Box::new(Imm(offset)))
+}
+
+#[test]
+fn test_moves() {
+    encodings_check(
+        &[
+            Mov(Reg(Rax), Reg(Rbx)),
+            Mov(Reg(R8), Reg(R15)),
+            Mov(Reg(Rax), mem(Rsp, 0)),
+            Mov(Reg(Rax), mem(Rsp, 8)),
+            Mov(Reg(Rax), mem(Rsp, 1000)),
+            Mov(Reg(R9), mem(Rbp, 0)),
+            Mov(Reg(R9), mem(Rbp, -16)),
+            Mov(mem(R12, 0), Reg(R14)),
+            Mov(mem(R13, 0), Reg(Rdx)),
+            Mov(Reg(Rax), Imm(5)),
+            Mov(Reg(R10), Imm(3_000_000_000)),
+            Mov(Reg(Rax), Imm(-5)),
+            Mov(Reg(R14), Imm(0x1_2345_6789)),
+            Mov(Reg(R14), FloatImm(1.5)),
+            Mov(mem(Rsp, 16), Imm(-1)),
+            MovByte(Reg(Rax), Reg(Rcx)),
+            MovByte(Reg(Rsi), Reg(R8)),
+            MovByte(mem(Rsp, 8), Reg(Rdi)),
+            MovByte(Reg(R9), mem(Rsp, 8)),
+            MovByte(mem(Rsp, 0), Imm(1)),
+        ],
+        expect![[r#"
+            mov rax, rbx                            48 89 d8
+            mov r8, r15                             4d 89 f8
+            mov rax, QWORD [rsp + 0]                48 8b 04 24
+            mov rax, QWORD [rsp + 8]                48 8b 44 24 08
+            mov rax, QWORD [rsp + 1000]             48 8b 84 24 e8 03 00 00
+            mov r9, QWORD [rbp + 0]                 4c 8b 4d 00
+            mov r9, QWORD [rbp + -16]               4c 8b 4d f0
+            mov QWORD [r12 + 0], r14                4d 89 34 24
+            mov QWORD [r13 + 0], rdx                49 89 55 00
+            mov rax, 5                              b8 05 00 00 00
+            mov r10, 3000000000                     41 ba 00 5e d0 b2
+            mov rax, -5                             48 c7 c0 fb ff ff ff
+            mov r14, 4886718345                     49 be 89 67 45 23 01 00 00 00
+            mov r14, __?float64?__(1.5)             49 be 00 00 00 00 00 00 f8 3f
+            mov QWORD [rsp + 16], -1                48 c7 44 24 10 ff ff ff ff
+            mov al, cl                              88 c8
+            mov sil, r8b                            44 88 c6
+            mov BYTE [rsp + 8], dil                 40 88 7c 24 08
+            mov r9b, BYTE [rsp + 8]                 44 8a 4c 24 08
+            mov BYTE [rsp + 0], 1                   c6 04 24 01"#]],
+    );
+}
+
+#[test]
+fn test_arithmetic() {
+    encodings_check(
+        &[
+            Add(Reg(R8), Reg(R9)),
+            Add(Reg(Rsp), Imm(8)),
+            Add(Reg(Rax), Imm(1000)),
+            Add(Reg(R8), Imm(1000)),
+            Add(Reg(Rdx), mem(Rsp, 8)),
+            Sub(mem(Rsp, 0), Reg(Rbx)),
+            Sub(Reg(Rsp), Imm(-128)),
+            Sub(Reg(Rsp), Imm(128)),
+            And(Reg(Rax), Imm(1)),
+            Or(Reg(R14), Reg(R15)),
+            Cmp(Reg(R8), Imm(4)),
+            Cmp(mem(Rsp, 8), Imm(0)),
+            Mul(Reg(R8), Reg(R9)),
+            Mul(Reg(R8), mem(Rsp, 0)),
+            Mul(Reg(Rax), Imm(7)),
+            Mul3(Reg(R8), Reg(R9), Imm(10)),
+            Mul3(Reg(Rdx), Reg(Rdx), Imm(1000)),
+            Mul1(mem(Rsp, 0)),
+            Div(Reg(R8)),
+            Neg(Reg(R14)),
+            Cqo,
+            Shl(Reg(R14), Imm(1)),
+            Shr(Reg(R14), Imm(63)),
+            Sar(Reg(Rdx), Imm(3)),
+            Setz(Reg(Rax)),
+            Setnz(Reg(Rsi)),
+            Setl(Reg(R8)),
+            Setle(mem(Rsp, 8)),
+        ],
+        expect![[r#"
+            add r8, r9                              4d 01 c8
+            add rsp, 8                              48 83 c4 08
+            add rax, 1000                           48 05 e8 03 00 00
+            add r8, 1000                            49 81 c0 e8 03 00 00
+            add rdx, QWORD [rsp + 8]                48 03 54 24 08
+            sub QWORD [rsp + 0], rbx                48 29 1c 24
+            sub rsp, -128                           48 83 ec 80
+            sub rsp, 128                            48 81 ec 80 00 00 00
+            and rax, 1                              48 83 e0 01
+            or r14, r15                             4d 09 fe
+            cmp r8, 4                               49 83 f8 04
+            cmp QWORD [rsp + 8], 0                  48 83 7c 24 08 00
+            imul r8, r9                             4d 0f af c1
+            imul r8, QWORD [rsp + 0]                4c 0f af 04 24
+            imul rax, 7                             48 6b c0 07
+            imul r8, r9, 10                         4d 6b c1 0a
+            imul rdx, rdx, 1000                     48 69 d2 e8 03 00 00
+            imul QWORD [rsp + 0]                    48 f7 2c 24
+            idiv r8                                 49 f7 f8
+            neg r14                                 49 f7 de
+            cqo                                     48 99
+            shl r14, 1                              49 d1 e6
+            shr r14, 63                             49 c1 ee 3f
+            sar rdx, 3                              48 c1 fa 03
+            setz al                                 0f 94 c0
+            setnz sil                               40 0f 95 c6
+            setl r8b                                41 0f 9c c0
+            setle BYTE [rsp + 8]                    0f 9e 44 24 08"#]],
+    );
+}
+
+#[test]
+fn test_floats() {
+    encodings_check(
+        &[
+            Movq(FloatReg(Xmm0), FloatReg(Xmm14)),
+            Movq(FloatReg(Xmm1), mem(Rsp, 8)),
+            Movq(FloatReg(Xmm15), Reg(R14)),
+            Movq(Reg(Rax), FloatReg(Xmm0)),
+            Movq(mem(Rsp, 0), FloatReg(Xmm9)),
+            Cvttsd2si(Reg(R14), FloatReg(Xmm2)),
+            Cvtsi2sd(FloatReg(Xmm14), Reg(R14)),
+            Xorpd(FloatReg(Xmm1), FloatReg(Xmm1)),
+            Addsd(FloatReg(Xmm14), FloatReg(Xmm15)),
+            Subsd(FloatReg(Xmm14), mem(Rsp, 16)),
+            Mulsd(FloatReg(Xmm3), FloatReg(Xmm4)),
+            Divsd(FloatReg(Xmm8), FloatReg(Xmm0)),
+            Cmpsd(FloatReg(Xmm14), FloatReg(Xmm15), 2),
+            Ucomisd(FloatReg(Xmm1), FloatReg(Xmm2)),
+        ],
+        expect![[r#"
+            movq xmm0, xmm14                        f3 41 0f 7e c6
+            movq xmm1, QWORD [rsp + 8]              f3 0f 7e 4c 24 08
+            movq xmm15, r14                         66 4d 0f 6e fe
+            movq rax, xmm0                          66 48 0f 7e c0
+            movq QWORD [rsp + 0], xmm9              66 44 0f d6 0c 24
+            cvttsd2si r14, xmm2                     f2 4c 0f 2c f2
+            cvtsi2sd xmm14, r14                     f2 4d 0f 2a f6
+            xorpd xmm1, xmm1                        66 0f 57 c9
+            addsd xmm14, xmm15                      f2 45 0f 58 f7
+            subsd xmm14, QWORD [rsp + 16]           f2 44 0f 5c 74 24 10
+            mulsd xmm3, xmm4                        f2 0f 59 dc
+            divsd xmm8, xmm0                        f2 44 0f 5e c0
+            cmpsd xmm14, xmm15, 2                   f2 45 0f c2 f7 02
+            ucomisd xmm1, xmm2                      66 0f 2e ca"#]],
+    );
+}
+
+#[test]
+fn test_stack() {
+    encodings_check(
+        &[
+            Push(Reg(Rbp)),
+            Push(Reg(R12)),
+            Push(Imm(1)),
+            Push(Imm(1000)),
+            Push(mem(Rsp, 8)),
+            Pop(Reg(Rbx)),
+            Pop(Reg(R15)),
+            Pop(mem(Rsp, 0)),
+            ComputedJmp(Reg(Rax)),
+            ComputedJmp(Reg(R8)),
+            Ret,
+        ],
+        expect![[r#"
+            push rbp                                55
+            push r12                                41 54
+            push 1                                  6a 01
+            push 1000                               68 e8 03 00 00
+            push QWORD [rsp + 8]                    ff 74 24 08
+            pop rbx                                 5b
+            pop r15                                 41 5f
+            pop QWORD [rsp + 0]                     8f 04 24
+            jmp rax                                 ff e0
+            jmp r8                                  41 ff e0
+            ret                                     c3"#]],
+    );
+}
+
+#[test]
+fn test_jumps() {
+    // The first jump is short, and the second jump is near (since it jumps over 250 bytes).
+    let mut instructions = vec![
+        Label("loop".to_string()),
+        Jne("loop".to_string()),
+        Je("end".to_string()),
+    ];
+    instructions.extend((0..50).map(|_| Mov(Reg(Rax), Imm(1))));
+    instructions.extend([Jmp("loop".to_string()), Label("end".to_string()), Ret]);
+
+    encoder_check(
+        &instructions,
+        expect![[r#"
+            .text (align 1)
+              0000: 75 fe 0f 84 ff 00 00 00 b8 01 00 00 00 b8 01 00
+              0010: 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00
+              0020: 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00
+              0030: b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8
+              0040: 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01
+              0050: 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00
+              0060: 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00
+              0070: 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00
+              0080: b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8
+              0090: 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01
+              00a0: 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00
+              00b0: 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00
+              00c0: 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00
+              00d0: b8 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8
+              00e0: 01 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01
+              00f0: 00 00 00 b8 01 00 00 00 b8 01 00 00 00 b8 01 00
+              0100: 00 00 e9 f9 fe ff ff c3
+            symbols
+              loop local .text 0x0
+              end local .text 0x107"#]],
+    );
+}
+
+#[test]
+fn test_sections() {
+    encoder_check(
+        &[
+            Global("entry".to_string()),
+            Extern("print".to_string()),
+            Section("text".to_string()),
+            Label("entry".to_string()),
+            Movq(FloatReg(Xmm0), MemLabel("float__0".to_string())),
+            Cmpsd(FloatReg(Xmm0), MemLabel("float__0".to_string()), 1),
+            LeaLabel(Reg(Rax), "function".to_string()),
+            Call("print".to_string()),
+            Label("function".to_string()),
+            Ret,
+            Section("data".to_string()),
+            DqLabel("function".to_string()),
+            DqString("a\\n\\x41\\\\".to_string()),
+            Section("rodata".to_string()),
+            Align(8),
+            Label("float__0".to_string()),
+            DqInt(-2),
+        ],
+        expect![[r#"
+            .text (align 1)
+              0000: f3 0f 7e 05 00 00 00 00 f2 0f c2 05 00 00 00 00
+              0010: 01 48 8d 05 05 00 00 00 e8 00 00 00 00 c3
+              relocation 0x4: Pc32 float__0 -4
+              relocation 0xc: Pc32 float__0 -5
+              relocation 0x19: Plt32 print -4
+            .data (align 1)
+              0000: 00 00 00 00 00 00 00 00 61 0a 41 5c 00 00 00 00
+              0010: 00 00 00 00 00 00 00 00
+              relocation 0x0: Absolute64 function 0
+            .rodata (align 8)
+              0000: fe ff ff ff ff ff ff ff
+            symbols
+              entry global .text 0x0
+              function local .text 0x1d
+              float__0 local .rodata 0x0
+              print global undefined"#]],
+    );
+}
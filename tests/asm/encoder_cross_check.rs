@@ -0,0 +1,112 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests that the built-in encoder creates the same machine code as the GNU assembler and `nasm` (if it is installed).
+
+use solis::asm::asm::{FloatRegister::*, Instruction::*, Operand, Operand::*, Register, Register::*};
+use std::fs;
+use test_utils::{encoder_cross_check, encoder_cross_check_program};
+
+fn mem(register: Register, offset: i64) -> Operand {
+    MemOffset(Box::new(Reg(register)), Box::new(Imm(offset)))
+}
+
+#[test]
+fn test_instructions() {
+    let registers = [
+        Rax, Rcx, Rdx, Rbx, Rsp, Rbp, Rsi, Rdi, R8, R9, R10, R11, R12, R13, R14, R15,
+    ];
+
+    let mut instructions = vec![
+        Global("entry".to_string()),
+        Extern("print".to_string()),
+        Section("text".to_string()),
+        Label("entry".to_string()),
+    ];
+
+    // Every register as each operand, and as the base of memory operands.
+    for register in registers {
+        instructions.extend([
+            Mov(Reg(register), Reg(R9)),
+            Mov(Reg(R9), Reg(register)),
+            Mov(Reg(register), mem(register, 0)),
+            Mov(mem(register, -8), Reg(register)),
+            Mov(Reg(register), Imm(1 << 40)),
+            Mov(Reg(register), Imm(-1)),
+            MovByte(Reg(register), Reg(Rsi)),
+            MovByte(mem(register, 200), Imm(-1)),
+            Add(Reg(register), Imm(300)),
+            Cmp(mem(register, 8), Reg(R14)),
+            Mul3(Reg(register), mem(register, 8), Imm(-3)),
+            Setl(Reg(register)),
+            Push(Reg(register)),
+            Pop(Reg(register)),
+        ]);
+    }
+    for register in [Xmm0, Xmm7, Xmm8, Xmm15] {
+        instructions.extend([
+            Movq(FloatReg(register), FloatReg(Xmm9)),
+            Movq(Reg(R10), FloatReg(register)),
+            Movq(mem(Rbp, 8), FloatReg(register)),
+            Cvtsi2sd(FloatReg(register), Reg(R12)),
+            Divsd(FloatReg(register), mem(R13, 0)),
+            Ucomisd(FloatReg(register), MemLabel("float__0".to_string())),
+        ]);
+    }
+
+    instructions.extend([
+        Label("loop".to_string()),
+        Sub(Reg(Rsp), Imm(16)),
+        Shl(Reg(R14), Imm(1)),
+        Sar(mem(Rsp, 0), Imm(5)),
+        Neg(Reg(Rax)),
+        Cqo,
+        Div(mem(Rsp, 8)),
+        Cmpsd(FloatReg(Xmm14), MemLabel("float__0".to_string()), 1),
+        Cvttsd2si(Reg(R14), FloatReg(Xmm14)),
+        Jl("loop".to_string()),
+        Jp("end".to_string()),
+        Call("print".to_string()),
+        LeaLabel(Reg(Rax), "loop".to_string()),
+        ComputedJmp(Reg(Rax)),
+        Label("end".to_string()),
+        Ret,
+        Section("data".to_string()),
+        DqLabel("loop".to_string()),
+        DqString("abc\\n".to_string()),
+        Section("rodata".to_string()),
+        Align(8),
+        Label("float__0".to_string()),
+        DqInt(0x3ff8_0000_0000_0000),
+    ]);
+
+    encoder_cross_check("instructions", &instructions);
+}
+
+#[test]
+fn test_jumps() {
+    // Jumps over a growing number of instructions, where some jumps are short and some are near.
+    let mut instructions = vec![Section("text".to_string())];
+    for index in 0..40 {
+        instructions.push(Jne(format!("label__{index}")));
+        instructions.extend((0..index).map(|_| Add(Reg(R8), Imm(1000))));
+        instructions.push(Label(format!("label__{index}")));
+        instructions.push(Jmp(format!("label__{}", index / 2)));
+    }
+    encoder_cross_check("jumps", &instructions);
+}
+
+#[test]
+fn test_integration_programs() {
+    for name in ["basic_1", "random_1", "random_2", "random_3", "random_4", "random_5"] {
+        let program = fs::read_to_string(format!("./tests/integration/{name}.sol")).unwrap();
+        encoder_cross_check_program(name, &program);
+    }
+}
+
+#[test]
+fn test_more_integration_programs() {
+    for name in ["random_6", "random_7", "random_8", "random_9"] {
+        let program = fs::read_to_string(format!("./tests/integration/{name}.sol")).unwrap();
+        encoder_cross_check_program(name, &program);
+    }
+}
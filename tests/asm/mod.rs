@@ -3,3 +3,10 @@
 //! Unit tests for the asm module.
 
 mod asm_comprehensive;
+mod asm_writers;
+mod debug_info;
+mod emulator_basic;
+mod emulator_compile;
+mod encoder_basic;
+mod encoder_cross_check;
+mod peephole_rules;
@@ -0,0 +1,212 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for each rule of the peephole optimizer, on hand written instructions.
+
+use expect_test::expect;
+use solis::asm::asm::{FloatRegister::*, Instruction::*, Operand, Operand::*, Register::*};
+use solis::asm::peephole::{PeepholeRule, PEEPHOLE_RULES};
+use test_utils::peephole_check;
+
+fn stack(offset: i64) -> Operand {
+    MemOffset(Box::new(Reg(Rsp)), Box::new(Imm(offset)))
+}
+
+#[test]
+fn test_self_move() {
+    peephole_check(
+        &[PeepholeRule::SelfMove],
+        vec![
+            Mov(Reg(R8), Reg(R8)),
+            Movq(FloatReg(Xmm1), FloatReg(Xmm1)),
+            Mov(stack(8), stack(8)),
+            Mov(Reg(R8), Reg(R9)),
+        ],
+        expect![[r#"
+            	mov r8, r9
+        "#]],
+    );
+}
+
+#[test]
+fn test_move_through_scratch() {
+    // `r15` is not a scratch register (the register allocator assigns variables to it), so moves through it are kept.
+    peephole_check(
+        &[PeepholeRule::MoveThroughScratch],
+        vec![
+            Mov(Reg(R14), Imm(1)),
+            Mov(stack(0), Reg(R14)),
+            Mov(Reg(R14), stack(8)),
+            Mov(Reg(R8), Reg(R14)),
+            Mov(Reg(R15), Reg(R9)),
+            Mov(stack(16), Reg(R15)),
+            Label("end".to_string()),
+        ],
+        expect![[r#"
+            mov QWORD [rsp + 0], 1
+            mov r8, QWORD [rsp + 8]
+            mov r15, r9
+            mov QWORD [rsp + 16], r15
+            end:
+        "#]],
+    );
+}
+
+#[test]
+fn test_move_through_scratch_illegal() {
+    // Memory to memory moves, and 64 bit immediates (or floats) moved to memory are not legal.
+    peephole_check(
+        &[PeepholeRule::MoveThroughScratch],
+        vec![
+            Mov(Reg(R14), stack(8)),
+            Mov(stack(0), Reg(R14)),
+            Mov(Reg(R14), Imm(1 << 40)),
+            Mov(stack(0), Reg(R14)),
+            Mov(Reg(R14), FloatImm(2.5)),
+            Mov(stack(0), Reg(R14)),
+        ],
+        expect![[r#"
+            	mov r14, QWORD [rsp + 8]
+            	mov QWORD [rsp + 0], r14
+            	mov r14, 1099511627776
+            	mov QWORD [rsp + 0], r14
+            	mov r14, __?float64?__(2.5)
+            	mov QWORD [rsp + 0], r14
+        "#]],
+    );
+}
+
+#[test]
+fn test_move_through_scratch_live() {
+    // The scratch register is read afterwards, or is not a scratch register.
+    peephole_check(
+        &[PeepholeRule::MoveThroughScratch],
+        vec![
+            Mov(Reg(R14), Imm(1)),
+            Mov(Reg(R8), Reg(R14)),
+            Add(Reg(R9), Reg(R14)),
+            Mov(Reg(R10), Imm(1)),
+            Mov(Reg(R8), Reg(R10)),
+            Ret,
+        ],
+        expect![[r#"
+            	mov r14, 1
+            	mov r8, r14
+            	add r9, r14
+            	mov r10, 1
+            	mov r8, r10
+            	ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_jump_after_set() {
+    peephole_check(
+        &[PeepholeRule::JumpAfterSet],
+        vec![
+            Cmp(Reg(R8), Reg(R9)),
+            Mov(Reg(R10), Imm(0)),
+            Setl(Reg(R10)),
+            Cmp(Reg(R10), Imm(1)),
+            Jne("else__0".to_string()),
+            Setle(Reg(R10)),
+            Cmp(Reg(R10), Imm(1)),
+            Jne("else__1".to_string()),
+            Setz(Reg(R10)),
+            Cmp(Reg(R10), Imm(1)),
+            Jne("else__2".to_string()),
+            Setnz(Reg(R10)),
+            Cmp(Reg(R10), Imm(1)),
+            Jne("else__3".to_string()),
+        ],
+        expect![[r#"
+            	cmp r8, r9
+            	mov r10, 0
+            	setl r10b
+            	jnl else__0
+            	setle r10b
+            	jg else__1
+            	setz r10b
+            	jne else__2
+            	setnz r10b
+            	je else__3
+        "#]],
+    );
+}
+
+#[test]
+fn test_jump_after_set_other_operand() {
+    // The compared operand is not the result of the `set<cc>`.
+    peephole_check(
+        &[PeepholeRule::JumpAfterSet],
+        vec![Setl(Reg(R10)), Cmp(Reg(R11), Imm(1)), Jne("else__0".to_string())],
+        expect![[r#"
+            	setl r10b
+            	cmp r11, 1
+            	jne else__0
+        "#]],
+    );
+}
+
+#[test]
+fn test_multiply_power_of_two() {
+    peephole_check(
+        &[PeepholeRule::MultiplyPowerOfTwo],
+        vec![
+            Mul(Reg(R14), Imm(8)),
+            Mul(Reg(R14), Imm(1)),
+            Mul(Reg(R14), Imm(6)),
+            Mul(Reg(R14), Imm(-4)),
+            Mul3(Reg(R8), Reg(R9), Imm(16)),
+            Mul3(Reg(R8), Reg(R8), Imm(2)),
+            Mul3(Reg(R8), stack(8), Imm(1)),
+        ],
+        expect![[r#"
+            	shl r14, 3
+            	imul r14, 6
+            	imul r14, -4
+            	mov r8, r9
+            	shl r8, 4
+            	shl r8, 1
+            	mov r8, QWORD [rsp + 8]
+        "#]],
+    );
+}
+
+#[test]
+fn test_comments() {
+    // Comments don't prevent a match, and the comments of annotated instructions are kept.
+    peephole_check(
+        &PEEPHOLE_RULES,
+        vec![
+            Comment("a".to_string()),
+            Mov(Reg(R14), Imm(1)),
+            Comment("b".to_string()),
+            Mov(Reg(R8), Reg(R14)).annotated("c"),
+            Mov(Reg(R9), Reg(R9)).annotated("d"),
+            Ret,
+        ],
+        expect![[r#"
+
+            ; a
+
+            ; b
+            mov r8, 1                               ; c
+
+            ; d
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_fixed_point() {
+    // Moving through a scratch register creates a self move, which is then removed.
+    peephole_check(
+        &PEEPHOLE_RULES,
+        vec![Mov(Reg(R14), Reg(R8)), Mov(Reg(R8), Reg(R14)), Ret],
+        expect![[r#"
+            	ret
+        "#]],
+    );
+}
@@ -0,0 +1,106 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests that programs compiled with the C backend have the same result as the interpreter.
+
+use std::fs;
+use test_utils::c_backend_cross_check;
+
+#[test]
+fn test_functions() {
+    c_backend_cross_check(
+        "fib",
+        "
+        fun fib(n: int) : int {
+          if n <= 1 {
+            1
+          }
+          else {
+            fib(n - 1) + fib(n - 2)
+          }
+        }
+
+        fib(15)
+        ",
+    );
+    c_backend_cross_check(
+        "params",
+        "
+        fun f(a: int, b: float) : float {
+          a * b
+        }
+
+        fun g(a: bool) : () {
+          if a {
+            f(1, 2.0)
+          }
+        }
+
+        let a: int = 10
+        let c: float = f(2, 1.5)
+        g(c > 1.0)
+        c + a
+        ",
+    );
+}
+
+#[test]
+fn test_arithmetic() {
+    c_backend_cross_check(
+        "wrapping",
+        "
+        let a: int = 9223372036854775807
+        let b: int = a + 1
+        let c: int = b * 3 - a
+        let d: int = -b
+        c / 7 + d % 5 + (-17) / 5 + (-17) % 5
+        ",
+    );
+    c_backend_cross_check(
+        "floats",
+        "
+        let nan: float = 0.0 / 0.0
+        let a: bool = (nan > 1.0) == (nan >= 1.0)
+        let b: bool = (nan < 1.0) == (nan <= 1.0)
+        if a != b { -(1.0 / 0.0) + 0.1 } else if nan != nan { 1.0 } else { 0.0 }
+        ",
+    );
+    c_backend_cross_check("nan", "let a: int = 3; (a * 0.0) / 0.0");
+}
+
+#[test]
+fn test_runtime_errors() {
+    c_backend_cross_check("division_by_zero", "let a: int = 0; 1 / a");
+    c_backend_cross_check(
+        "division_overflow",
+        "let a: int = -9223372036854775807 - 1; let b: int = -1; a % b",
+    );
+}
+
+#[test]
+fn test_integration_programs() {
+    for name in [
+        "basic_1", "random_1", "random_2", "random_3", "random_4", "random_5", "random_6", "random_7", "random_8",
+        "random_9",
+    ] {
+        let program = fs::read_to_string(format!("./tests/integration/{name}.sol")).unwrap();
+        c_backend_cross_check(name, &program);
+    }
+}
+
+#[test]
+fn test_constant_branches() {
+    // Constant folding moves the branches that are taken into the enclosing block, which must not declare the same C
+    // variable twice.
+    c_backend_cross_check(
+        "constant_branches",
+        "
+        @noinline fun f(n: int) : int {
+          let x: int = if true { let a: int = n + 1; a * 2 } else { 0 }
+          let y: int = if true { let a: int = n * 3; a + 5 } else { 0 }
+          x + y
+        }
+
+        f(4)
+        ",
+    );
+}
@@ -0,0 +1,250 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for lowering the IR into C source.
+
+use expect_test::expect;
+use test_utils::c_writer_ir_check;
+
+#[test]
+fn test_empty() {
+    c_writer_ir_check(
+        "",
+        expect![[r#"
+            static int64_t body(void) {
+              return 0;
+            }
+
+            long entry(void) {
+              return body();
+            }"#]],
+    );
+}
+
+#[test]
+fn test_lets() {
+    c_writer_ir_check(
+        "
+        let a = 1
+        let %temp0 = add.int a, 2147483648
+        let b = mul.int %temp0, -9223372036854775808
+        let %temp1 = div.int b, a
+        let c = mod.int %temp1, 3
+        let d = neg.int c
+        let e = lt.int d, 0
+        not.bool e
+        ",
+        expect![[r#"
+            static bool body(void) {
+              int64_t v_a = 1;
+              int64_t temp0 = (int64_t) ((uint64_t) v_a + (uint64_t) INT64_C(2147483648));
+              int64_t v_b = (int64_t) ((uint64_t) temp0 * (uint64_t) INT64_MIN);
+              int64_t temp1 = divide(v_b, v_a);
+              int64_t v_c = modulo(temp1, 3);
+              int64_t v_d = (int64_t) (0 - (uint64_t) v_c);
+              bool v_e = v_d < 0;
+              return !v_e;
+            }
+
+            long entry(void) {
+              return body();
+            }"#]],
+    );
+}
+
+#[test]
+fn test_floats() {
+    c_writer_ir_check(
+        "
+        let a = coerce.int.float 3
+        let b = div.float a, 1e100
+        let c = neg.float b
+        let d = gt.float c, +inf
+        let e = ge.float +NaN, -0.0
+        let f = eq.bool d, e
+        let g = ne.float -inf, 2.5
+        add.float c, 0.1
+        ",
+        expect![[r#"
+            static double body(void) {
+              double v_a = (double) 3;
+              double v_b = v_a / 1e100;
+              double v_c = 0.0 - v_b;
+              bool v_d = !(v_c <= float_from_bits(0x7ff0000000000000));
+              bool v_e = !(float_from_bits(0x7ff8000000000000) < -0.0);
+              bool v_f = v_d == v_e;
+              bool v_g = float_from_bits(0xfff0000000000000) != 2.5;
+              return v_c + 0.1;
+            }
+
+            long entry(void) {
+              return float_to_bits(body());
+            }"#]],
+    );
+}
+
+#[test]
+fn test_if() {
+    c_writer_ir_check(
+        "
+        let a = if true {
+          let %temp0 = add.int 1, 2
+          if false {
+            %temp0: int
+          } else {
+            3
+          }
+        } else {
+          4
+        }
+        let b = if true {
+          1
+        }
+        if true {
+          let c = a: int
+        } else {}
+        ",
+        expect![[r#"
+            static int64_t body(void) {
+              int64_t v_a;
+              if (true) {
+                int64_t temp0 = (int64_t) ((uint64_t) 1 + (uint64_t) 2);
+                if (false) {
+                  v_a = temp0;
+                } else {
+                  v_a = 3;
+                }
+              } else {
+                v_a = 4;
+              }
+              int64_t v_b;
+              if (true) {
+              }
+              v_b = 0;
+              if (true) {
+                int64_t v_c = v_a;
+                return 0;
+              } else {
+                return 0;
+              }
+            }
+
+            long entry(void) {
+              return body();
+            }"#]],
+    );
+}
+
+#[test]
+fn test_functions() {
+    c_writer_ir_check(
+        "
+        fun is_even(n: int): bool {
+          let %temp0 = eq.int n, 0
+          if %temp0 {
+            true
+          } else {
+            let %temp1 = sub.int n, 1
+            call is_odd(%temp1: int)
+          }
+        }
+
+        fun is_odd(n: int): bool {
+          let %temp2 = eq.int n, 0
+          if %temp2 {
+            false
+          } else {
+            let %temp3 = sub.int n, 1
+            call is_even(%temp3: int)
+          }
+        }
+
+        fun unused(a: float, b: int): unit {
+          call unused(a: float, 1)
+        }
+
+        fun print(): unit {}
+
+        call print()
+        call is_even(10)
+        ",
+        expect![[r#"
+            static bool f_is_even(int64_t v_n);
+            static bool f_is_odd(int64_t v_n);
+            static int64_t f_unused(double v_a, int64_t v_b);
+            static int64_t f_print(void);
+
+            static bool f_is_even(int64_t v_n) {
+              bool temp0 = v_n == 0;
+              if (temp0) {
+                return true;
+              } else {
+                int64_t temp1 = (int64_t) ((uint64_t) v_n - (uint64_t) 1);
+                return f_is_odd(temp1);
+              }
+            }
+
+            static bool f_is_odd(int64_t v_n) {
+              bool temp2 = v_n == 0;
+              if (temp2) {
+                return false;
+              } else {
+                int64_t temp3 = (int64_t) ((uint64_t) v_n - (uint64_t) 1);
+                return f_is_even(temp3);
+              }
+            }
+
+            static int64_t f_unused(double v_a, int64_t v_b) {
+              return f_unused(v_a, 1);
+            }
+
+            static int64_t f_print(void) {
+              return 0;
+            }
+
+            static bool body(void) {
+              f_print();
+              return f_is_even(10);
+            }
+
+            long entry(void) {
+              return body();
+            }"#]],
+    );
+}
+
+#[test]
+fn test_function_types() {
+    // Functions are declared with their types, even if the parameters are never used and the function is never called.
+    c_writer_ir_check(
+        "
+        fun f(a: float, b: bool): float {
+          1.5
+        }
+
+        fun g(n: int): int {
+          call g(n: int)
+        }
+
+        0
+        ",
+        expect![[r#"
+            static double f_f(double v_a, bool v_b);
+            static int64_t f_g(int64_t v_n);
+
+            static double f_f(double v_a, bool v_b) {
+              return 1.5;
+            }
+
+            static int64_t f_g(int64_t v_n) {
+              return f_g(v_n);
+            }
+
+            static int64_t body(void) {
+              return 0;
+            }
+
+            long entry(void) {
+              return body();
+            }"#]],
+    );
+}
@@ -0,0 +1,6 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Unit tests for the C backend.
+
+mod c_backend_cross_check;
+mod c_writer_basic;
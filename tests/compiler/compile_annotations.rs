@@ -0,0 +1,214 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the annotations of the assembly with the lines of the source (with `--annotate-asm`).
+
+use expect_test::expect;
+use test_utils::compile_annotated_check;
+
+#[test]
+fn test_lets() {
+    compile_annotated_check(
+        r"
+        let a: int = 1 + 2
+        let b: int = a * 4 let c: bool = b < a
+        let d: float = 2.5
+        if c { a } else { b }
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+
+            ; 2: let a: int = 1 + 2 (a: r10)
+            mov r14, 1
+            add r14, 2
+            mov r10, r14
+
+            ; 3: let b: int = a * 4 let c: bool = b < a (b: r9, c: r8)
+            mov r14, r10
+            shl r14, 2
+            mov r9, r14
+            cmp r9, r10
+            mov r8, 0
+            setl r8b
+
+            ; 4: let d: float = 2.5 (d: not stored)
+
+            ; 5: if c { a } else { b }
+            cmp r8, 1
+            jne else__0
+            mov rax, r10
+            jmp continue__1
+            else__0:
+            mov rax, r9
+            continue__1:
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_blocks() {
+    compile_annotated_check(
+        r"
+        let a: int = 5
+        let b: int = if a > 2 {
+            let c: int = a / 2
+            c - 1
+        } else {
+            let d: int = a * 3
+            d
+        }
+        b
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+
+            ; 2: let a: int = 5 (a: r8)
+            mov r8, 5
+
+            ; 3: let b: int = if a > 2 { (b: r8)
+            mov r14, 2
+            cmp r14, r8
+            jnl else__0
+
+            ; 4: let c: int = a / 2 (c: r8)
+            mov r14, r8
+            mov rdx, r14
+            sar rdx, 63
+            shr rdx, 63
+            add r14, rdx
+            sar r14, 1
+            mov r8, r14
+            mov r14, r8
+            sub r14, 1
+            mov r8, r14
+            jmp continue__1
+            else__0:
+
+            ; 7: let d: int = a * 3 (d: r8)
+            mov r14, r8
+            shl r14, 1
+            add r14, r8
+            mov r8, r14
+            continue__1:
+            mov rax, r8
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_spills() {
+    compile_annotated_check(
+        r"
+        let a: int = 1
+        let b: int = 2
+        let c: int = 3
+        let d: int = 4
+        let e: int = 5
+        let f: int = 6
+        let g: int = 7
+        let h: int = 8
+        let i: int = 9
+        let j: int = 10
+        a + b + c + d + e + f + g + h + i + j
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push rbx
+            push r12
+            push r13
+            push r14
+            push r15
+            sub rsp, 24
+
+            ; 2: let a: int = 1 (a: [rsp + 8])
+            mov QWORD [rsp + 8], 1
+
+            ; 3: let b: int = 2 (b: r15)
+            mov r15, 2
+
+            ; 4: let c: int = 3 (c: [rsp + 0])
+            mov QWORD [rsp + 0], 3
+
+            ; 5: let d: int = 4 (d: r13)
+            mov r13, 4
+
+            ; 6: let e: int = 5 (e: r12)
+            mov r12, 5
+
+            ; 7: let f: int = 6 (f: rbx)
+            mov rbx, 6
+
+            ; 8: let g: int = 7 (g: r11)
+            mov r11, 7
+
+            ; 9: let h: int = 8 (h: r10)
+            mov r10, 8
+
+            ; 10: let i: int = 9 (i: r9)
+            mov r9, 9
+
+            ; 11: let j: int = 10 (j: r8)
+            mov r8, 10
+
+            ; 12: a + b + c + d + e + f + g + h + i + j
+            mov r14, QWORD [rsp + 8]
+            add r14, r15
+            mov r15, r14
+            mov r14, r15
+            add r14, QWORD [rsp + 0]
+            mov r15, r14
+            mov r14, r15
+            add r14, r13
+            mov r13, r14
+            mov r14, r13
+            add r14, r12
+            mov r12, r14
+            mov r14, r12
+            add r14, rbx
+            mov rbx, r14
+            mov r14, rbx
+            add r14, r11
+            mov r11, r14
+            mov r14, r11
+            add r14, r10
+            mov r10, r14
+            mov r14, r10
+            add r14, r9
+            mov r9, r14
+            mov r14, r9
+            add r14, r8
+            mov rax, r14
+            add rsp, 24
+            pop r15
+            pop r14
+            pop r13
+            pop r12
+            pop rbx
+            pop rbp
+            ret
+        "#]],
+    );
+}
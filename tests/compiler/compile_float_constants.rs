@@ -0,0 +1,121 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the constant pool of float literals.
+
+use expect_test::expect;
+use solis::asm::asm::FloatRegister::Xmm1;
+use solis::compiler::compiler::compile_with_allocation;
+use solis::ir::ir_parser::parse_ir;
+use solis::register_allocation::register_allocator::{Assignment, Map};
+use solis::File;
+use std::panic::{self, AssertUnwindSafe};
+use test_utils::compile_ir_check;
+
+#[test]
+fn test_operands() {
+    compile_ir_check(
+        r"
+        let a = coerce.int.float 3
+        let b = add.float a, 1.5
+        let c = mul.float 2.5, b
+        let d = lt.float c, 1.5
+        d: bool
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+            mov r14, 3
+            cvtsi2sd xmm14, r14
+            movq xmm1, xmm14
+            movq xmm15, xmm1
+            addsd xmm15, QWORD [rel float__3ff8000000000000]
+            movq xmm1, xmm15
+            movq xmm14, QWORD [rel float__4004000000000000]
+            movq xmm15, xmm14
+            mulsd xmm15, xmm1
+            movq xmm1, xmm15
+            movq xmm14, xmm1
+            cmpsd xmm14, QWORD [rel float__3ff8000000000000], 1
+            movq r8, xmm14
+            and r8, 1
+            mov rax, r8
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+            section .rodata
+            align 8
+            float__3ff8000000000000:
+            dq 4609434218613702656
+            float__4004000000000000:
+            dq 4612811918334230528
+        "#]],
+    );
+}
+
+#[test]
+fn test_no_constants() {
+    compile_ir_check(
+        r"
+        let a = 3
+        add.int a, 4
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+            mov r8, 3
+            mov r14, r8
+            add r14, 4
+            mov rax, r14
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_pool_per_compilation() {
+    // The float constants of a compilation that panicked (here, since `b` is not allocated) are not emitted by the next
+    // compilation.
+    let program = parse_ir(&File {
+        name: String::new(),
+        contents: "let a = add.float 1.5, 2.5\nlet b = coerce.int.float 1\nb: float".to_string(),
+    });
+    let a = "a".to_string();
+    let variable_assignment = Map::from([(&a, Assignment::FloatRegister(Xmm1))]);
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| compile_with_allocation(
+        &program,
+        &variable_assignment
+    )))
+    .is_err());
+
+    compile_ir_check(
+        "
+        let a = 1
+        a: int
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            mov r8, 1
+            mov rax, r8
+            pop rbp
+            ret
+        "#]],
+    );
+}
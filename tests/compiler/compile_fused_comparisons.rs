@@ -0,0 +1,168 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests that comparisons which are only used as the condition of an `if` are compiled directly into the jump.
+
+use expect_test::expect;
+use test_utils::compile_ir_check;
+
+#[test]
+fn test_int_comparison() {
+    compile_ir_check(
+        r"
+        let a = 3
+        let %temp0 = lt.int a, 4
+        if %temp0 { 1 } else { 2 }
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            mov r8, 3
+            cmp r8, 4
+            jnl else__0
+            mov rax, 1
+            jmp continue__1
+            else__0:
+            mov rax, 2
+            continue__1:
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_let_if() {
+    compile_ir_check(
+        r"
+        let a = 3
+        let %temp0 = eq.int 4, a
+        let b = if %temp0 { 1 } else { 2 }
+        b: int
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            mov r8, 3
+            cmp r8, 4
+            jne else__0
+            mov r8, 1
+            jmp continue__1
+            else__0:
+            mov r8, 2
+            continue__1:
+            mov rax, r8
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_comparison_used_later() {
+    // The comparison is needed after the `if`, so it is not fused.
+    compile_ir_check(
+        r"
+        let a = 3
+        let %temp0 = lt.int a, 4
+        if %temp0 { 1 } else { 2 }
+        %temp0: bool
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            mov r8, 3
+            cmp r8, 4
+            mov r8, 0
+            setl r8b
+            cmp r8, 1
+            jne else__0
+            jmp continue__1
+            else__0:
+            continue__1:
+            mov rax, r8
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_float_comparisons() {
+    compile_ir_check(
+        r"
+        let a = 1.5
+        let b = 2.5
+        let %temp0 = lt.float a, b
+        let c = if %temp0 { 1 } else { 2 }
+        let %temp1 = ge.float a, b
+        let d = if %temp1 { 1 } else { 2 }
+        let %temp2 = eq.float a, b
+        let e = if %temp2 { 1 } else { 2 }
+        let %temp3 = ne.float a, b
+        if %temp3 { add.int c, d } else { e: int }
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+            movq xmm2, QWORD [rel float__3ff8000000000000]
+            movq xmm1, QWORD [rel float__4004000000000000]
+            ucomisd xmm1, xmm2
+            jbe else__0
+            mov r10, 1
+            jmp continue__1
+            else__0:
+            mov r10, 2
+            continue__1:
+            ucomisd xmm1, xmm2
+            ja else__2
+            mov r9, 1
+            jmp continue__3
+            else__2:
+            mov r9, 2
+            continue__3:
+            ucomisd xmm2, xmm1
+            jne else__4
+            jp else__4
+            mov r8, 1
+            jmp continue__5
+            else__4:
+            mov r8, 2
+            continue__5:
+            ucomisd xmm2, xmm1
+            jp unordered__8
+            je else__6
+            unordered__8:
+            mov r14, r10
+            add r14, r9
+            mov rax, r14
+            jmp continue__7
+            else__6:
+            mov rax, r8
+            continue__7:
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+            section .rodata
+            align 8
+            float__3ff8000000000000:
+            dq 4609434218613702656
+            float__4004000000000000:
+            dq 4612811918334230528
+        "#]],
+    );
+}
@@ -0,0 +1,184 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the prologue and epilogue of `entry`, which follow the System V ABI.
+
+use expect_test::expect;
+use test_utils::compile_ir_check;
+
+#[test]
+fn test_no_registers() {
+    compile_ir_check(
+        r"
+        1
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            mov rax, 1
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_callee_saved_registers() {
+    // More variables are live than there are caller saved registers, so `rbx`, `r12`, `r13` and `r15` are used.
+    compile_ir_check(
+        r"
+        let a = 1
+        let b = 2
+        let c = 3
+        let d = 4
+        let e = 5
+        let f = 6
+        let g = 7
+        let h = 8
+        let %temp0 = add.int a, b
+        let %temp1 = add.int %temp0, c
+        let %temp2 = add.int %temp1, d
+        let %temp3 = add.int %temp2, e
+        let %temp4 = add.int %temp3, f
+        let %temp5 = add.int %temp4, g
+        add.int %temp5, h
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push rbx
+            push r12
+            push r13
+            push r14
+            push r15
+            sub rsp, 8
+            mov r15, 1
+            mov r13, 2
+            mov r12, 3
+            mov rbx, 4
+            mov r11, 5
+            mov r10, 6
+            mov r9, 7
+            mov r8, 8
+            mov r14, r15
+            add r14, r13
+            mov r13, r14
+            mov r14, r13
+            add r14, r12
+            mov r12, r14
+            mov r14, r12
+            add r14, rbx
+            mov rbx, r14
+            mov r14, rbx
+            add r14, r11
+            mov r11, r14
+            mov r14, r11
+            add r14, r10
+            mov r10, r14
+            mov r14, r10
+            add r14, r9
+            mov r9, r14
+            mov r14, r9
+            add r14, r8
+            mov rax, r14
+            add rsp, 8
+            pop r15
+            pop r14
+            pop r13
+            pop r12
+            pop rbx
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_spills() {
+    compile_ir_check(
+        r"
+        let a = 1
+        let b = 2
+        let c = 3
+        let d = 4
+        let e = 5
+        let f = 6
+        let g = 7
+        let h = 8
+        let i = 9
+        let j = 10
+        let %temp0 = add.int a, b
+        let %temp1 = add.int %temp0, c
+        let %temp2 = add.int %temp1, d
+        let %temp3 = add.int %temp2, e
+        let %temp4 = add.int %temp3, f
+        let %temp5 = add.int %temp4, g
+        let %temp6 = add.int %temp5, h
+        let %temp7 = add.int %temp6, i
+        add.int %temp7, j
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push rbx
+            push r12
+            push r13
+            push r14
+            push r15
+            sub rsp, 24
+            mov QWORD [rsp + 8], 1
+            mov r15, 2
+            mov QWORD [rsp + 0], 3
+            mov r13, 4
+            mov r12, 5
+            mov rbx, 6
+            mov r11, 7
+            mov r10, 8
+            mov r9, 9
+            mov r8, 10
+            mov r14, QWORD [rsp + 8]
+            add r14, r15
+            mov r15, r14
+            mov r14, r15
+            add r14, QWORD [rsp + 0]
+            mov r15, r14
+            mov r14, r15
+            add r14, r13
+            mov r13, r14
+            mov r14, r13
+            add r14, r12
+            mov r12, r14
+            mov r14, r12
+            add r14, rbx
+            mov rbx, r14
+            mov r14, rbx
+            add r14, r11
+            mov r11, r14
+            mov r14, r11
+            add r14, r10
+            mov r10, r14
+            mov r14, r10
+            add r14, r9
+            mov r9, r14
+            mov r14, r9
+            add r14, r8
+            mov rax, r14
+            add rsp, 24
+            pop r15
+            pop r14
+            pop r13
+            pop r12
+            pop rbx
+            pop rbp
+            ret
+        "#]],
+    );
+}
@@ -0,0 +1,173 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the strength reduction of multiplication, division and modulo by constants.
+
+use expect_test::expect;
+use test_utils::compile_ir_check;
+
+#[test]
+fn test_multiply() {
+    compile_ir_check(
+        r"
+        let a = 3
+        let b = mul.int a, 8
+        let c = mul.int 9, b
+        let d = mul.int c, -7
+        let e = mul.int d, 10
+        e: int
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+            mov r8, 3
+            mov r14, r8
+            shl r14, 3
+            mov r8, r14
+            mov r14, r8
+            shl r14, 3
+            add r14, r8
+            mov r8, r14
+            mov r14, r8
+            shl r14, 3
+            sub r14, r8
+            neg r14
+            mov r8, r14
+            imul r8, r8, 10
+            mov rax, r8
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_divide_power_of_two() {
+    compile_ir_check(
+        r"
+        let a = 3
+        let b = div.int a, 4
+        let c = mod.int b, -16
+        c: int
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+            mov r8, 3
+            mov r14, r8
+            mov rdx, r14
+            sar rdx, 63
+            shr rdx, 62
+            add r14, rdx
+            sar r14, 2
+            mov r8, r14
+            mov r14, r8
+            mov rdx, r14
+            sar rdx, 63
+            shr rdx, 60
+            add rdx, r14
+            sar rdx, 4
+            shl rdx, 4
+            sub r14, rdx
+            mov r8, r14
+            mov rax, r8
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_divide_magic_number() {
+    compile_ir_check(
+        r"
+        let a = 3
+        let b = div.int a, 7
+        let c = mod.int b, -3
+        c: int
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+            mov r8, 3
+            mov rax, 5270498306774157605
+            imul r8
+            sar rdx, 1
+            mov r14, rdx
+            shr r14, 63
+            add rdx, r14
+            mov r14, rdx
+            mov r8, r14
+            mov rax, 6148914691236517206
+            imul r8
+            mov r14, rdx
+            shr r14, 63
+            add rdx, r14
+            imul rdx, rdx, 3
+            mov r14, r8
+            sub r14, rdx
+            mov r8, r14
+            mov rax, r8
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+        "#]],
+    );
+}
+
+#[test]
+fn test_divide_not_reduced() {
+    // Division by 0 and -1 must crash like `idiv`.
+    compile_ir_check(
+        r"
+        let a = 3
+        let b = div.int a, 0
+        let c = mod.int b, -1
+        c: int
+        ",
+        expect![[r#"
+            global entry
+            section .text
+            entry:
+            push rbp
+            mov rbp, rsp
+            push r14
+            sub rsp, 8
+            mov r8, 3
+            mov rax, r8
+            cqo
+            mov r14, 0
+            idiv r14
+            mov r8, rax
+            mov rax, r8
+            cqo
+            mov r14, -1
+            idiv r14
+            mov r8, rdx
+            mov rax, r8
+            add rsp, 8
+            pop r14
+            pop rbp
+            ret
+        "#]],
+    );
+}
@@ -0,0 +1,9 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Unit tests for the compiler module.
+
+mod compile_annotations;
+mod compile_float_constants;
+mod compile_fused_comparisons;
+mod compile_prologue;
+mod compile_strength_reduction;
@@ -0,0 +1,9 @@
+# Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+##
+Basic program for testing `--emit`.
+##
+
+let a: int = 3
+let b: float = 2.5 * 2.0
+if a < 4 { a + 1 } else { 2 }
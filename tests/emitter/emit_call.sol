@@ -0,0 +1,11 @@
+# Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+##
+Program with a call, which the compiler doesn't support, for testing that `--emit-only` stops after the stages of `--emit`.
+##
+
+fun double(a: int) : int {
+  a * 2
+}
+
+double(3)
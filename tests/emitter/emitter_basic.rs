@@ -0,0 +1,312 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Basic tests for `--emit`, which run the solis binary.
+
+use assert_cmd::Command;
+use expect_test::expect;
+use std::fs;
+use std::path::Path;
+
+// Runs the solis binary on `emit_basic.sol` with the given arguments, returning stdout.
+fn run_emit(args: &[&str]) -> String {
+    let output = Command::cargo_bin("solis")
+        .unwrap()
+        .arg("./tests/emitter/emit_basic.sol")
+        .args(args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_emit_tokens() {
+    let output = run_emit(&["--emit", "tokens", "--emit-only"]);
+    expect![[r#"
+        --- emit_basic Tokens ---
+        Token { kind: Let, position: 102..105 }
+        Token { kind: Id("a"), position: 106..107 }
+        Token { kind: Colon, position: 107..108 }
+        Token { kind: Id("int"), position: 109..112 }
+        Token { kind: Equals, position: 113..114 }
+        Token { kind: Int(3), position: 115..116 }
+        Token { kind: Let, position: 117..120 }
+        Token { kind: Id("b"), position: 121..122 }
+        Token { kind: Colon, position: 122..123 }
+        Token { kind: Id("float"), position: 124..129 }
+        Token { kind: Equals, position: 130..131 }
+        Token { kind: Float(2.5), position: 132..135 }
+        Token { kind: Times, position: 136..137 }
+        Token { kind: Float(2.0), position: 138..141 }
+        Token { kind: If, position: 142..144 }
+        Token { kind: Id("a"), position: 145..146 }
+        Token { kind: LessThan, position: 147..148 }
+        Token { kind: Int(4), position: 149..150 }
+        Token { kind: OpenBrace, position: 151..152 }
+        Token { kind: Id("a"), position: 153..154 }
+        Token { kind: Plus, position: 155..156 }
+        Token { kind: Int(1), position: 157..158 }
+        Token { kind: CloseBrace, position: 159..160 }
+        Token { kind: Else, position: 161..165 }
+        Token { kind: OpenBrace, position: 166..167 }
+        Token { kind: Int(2), position: 168..169 }
+        Token { kind: CloseBrace, position: 170..171 }
+
+    "#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn test_emit_spill_costs() {
+    let output = run_emit(&["--emit", "spill-costs", "--emit-only", "--no-optimize"]);
+    expect![[r#"
+        --- emit_basic SpillCosts ---
+        @temp0: references 1, degree 1, never spilled (short-lived)
+        a: references 1.5, degree 1, cost 1.500
+        b: references 0, degree 0, cost 0.000
+
+    "#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn test_emit_regalloc_asm() {
+    let output = run_emit(&["--emit", "regalloc,asm", "--emit-only", "--no-optimize"]);
+    expect![[r#"
+        --- emit_basic Regalloc ---
+        @temp0: Register(R9)
+        a: Register(R8)
+        b: None
+
+        --- emit_basic Asm ---
+        global entry
+        	section .text
+        entry:
+        	push rbp
+        	mov rbp, rsp
+        	push r14
+        	sub rsp, 8
+        	mov r8, 3
+        	cmp r8, 4
+        	jnl else__0
+        	mov r14, r8
+        	add r14, 1
+        	mov rax, r14
+        	jmp continue__1
+        else__0:
+        	mov rax, 2
+        continue__1:
+        	add rsp, 8
+        	pop r14
+        	pop rbp
+        	ret
+
+    "#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn test_emit_to_dest() {
+    let destination = "./build/solis_tests/emit_to_dest/";
+    let output = run_emit(&[
+        "--emit",
+        "ast,ir,asm",
+        "--emit-to-dest",
+        "--emit-only",
+        "--no-optimize",
+        "--clean",
+        "-d",
+        destination,
+    ]);
+    assert_eq!(output, "");
+
+    for extension in ["ast", "ir", "s"] {
+        assert!(Path::new(destination).join(format!("emit_basic.{extension}")).exists());
+    }
+
+    // No executable is created with `--emit-only`.
+    assert!(!Path::new(destination).join("emit_basic").exists());
+
+    expect![[r#"
+        global entry
+        	section .text
+        entry:
+        	push rbp
+        	mov rbp, rsp
+        	push r14
+        	sub rsp, 8
+        	mov r8, 3
+        	cmp r8, 4
+        	jnl else__0
+        	mov r14, r8
+        	add r14, 1
+        	mov rax, r14
+        	jmp continue__1
+        else__0:
+        	mov rax, 2
+        continue__1:
+        	add rsp, 8
+        	pop r14
+        	pop rbp
+        	ret
+    "#]]
+    .assert_eq(&fs::read_to_string(Path::new(destination).join("emit_basic.s")).unwrap());
+}
+
+#[test]
+fn test_emit_regalloc_linear() {
+    let output = run_emit(&[
+        "--emit",
+        "regalloc",
+        "--emit-only",
+        "--no-optimize",
+        "--regalloc",
+        "linear",
+    ]);
+    expect![[r#"
+        --- emit_basic Regalloc ---
+        @temp0: Register(R9)
+        a: Register(R8)
+        b: None
+
+    "#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn test_emit_ir_optimized() {
+    let output = run_emit(&["--emit", "ir,asm", "--emit-only"]);
+    expect![[r#"
+        --- emit_basic Ir ---
+        4
+
+        --- emit_basic Asm ---
+        global entry
+        	section .text
+        entry:
+        	push rbp
+        	mov rbp, rsp
+        	mov rax, 4
+        	pop rbp
+        	ret
+
+    "#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn test_verbose() {
+    // The optimizer summary is reported to stderr, so that stdout is unchanged.
+    let output = Command::cargo_bin("solis")
+        .unwrap()
+        .arg("./tests/emitter/emit_basic.sol")
+        .args(["--emit", "ir", "--emit-only", "--verbose", "-A", "unused-variables"])
+        .env("CLICOLOR", "0")
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        run_emit(&["--emit", "ir", "--emit-only"])
+    );
+    expect![[r#"
+        Info: inlined 0 calls
+        Info: removed 3 dead expressions
+    "#]]
+    .assert_eq(&String::from_utf8(output.stderr).unwrap());
+}
+
+#[test]
+fn test_emit_unavailable_stage() {
+    // Stages of a backend that doesn't run are rejected, instead of being silently left out.
+    let output = |args: &[&str]| {
+        let output = Command::cargo_bin("solis")
+            .unwrap()
+            .arg("./tests/emitter/emit_basic.sol")
+            .args(args)
+            .env("CLICOLOR", "0")
+            .assert()
+            .code(exitcode::USAGE)
+            .get_output()
+            .stdout
+            .clone();
+
+        String::from_utf8(output).unwrap()
+    };
+
+    expect![[r#"
+        Error: `--emit asm` is not supported with the C backend
+    "#]]
+    .assert_eq(&output(&["--emit", "ir,asm", "--backend", "c"]));
+    expect![[r#"
+        Error: `--emit regalloc` is not supported with the interpreter
+    "#]]
+    .assert_eq(&output(&["--emit", "regalloc", "--interpret"]));
+    expect![[r#"
+        Error: `--emit c` is not supported with the asm backend
+    "#]]
+    .assert_eq(&output(&["--emit", "c"]));
+}
+
+#[test]
+fn test_debug_info_unavailable() {
+    // Debug info is only created by the asm backend, so `-g` is rejected instead of being silently ignored.
+    let output = |args: &[&str]| {
+        let output = Command::cargo_bin("solis")
+            .unwrap()
+            .arg("./tests/emitter/emit_basic.sol")
+            .arg("-g")
+            .args(args)
+            .env("CLICOLOR", "0")
+            .assert()
+            .code(exitcode::USAGE)
+            .get_output()
+            .stdout
+            .clone();
+
+        String::from_utf8(output).unwrap()
+    };
+
+    expect![[r#"
+        Error: `-g` is not supported with the C backend
+    "#]]
+    .assert_eq(&output(&["--backend", "c"]));
+    expect![[r#"
+        Error: `-g` is not supported with the JIT
+    "#]]
+    .assert_eq(&output(&["--jit"]));
+    expect![[r#"
+        Error: `-g` is not supported with the interpreter
+    "#]]
+    .assert_eq(&output(&["--interpret"]));
+}
+
+#[test]
+fn test_emit_only_stops() {
+    // The compiler doesn't support calls yet, so it must not run after the IR is emitted.
+    let output = Command::cargo_bin("solis")
+        .unwrap()
+        .arg("./tests/emitter/emit_call.sol")
+        .args(["--emit", "ir", "--emit-only", "--no-optimize"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    expect![[r#"
+        --- emit_call Ir ---
+        fun double(a: int): int {
+          mul.int a, 2
+        }
+
+        call double(3)
+
+    "#]]
+    .assert_eq(&String::from_utf8(output).unwrap());
+}
@@ -0,0 +1,5 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for emitting the intermediate stages of compilation (`--emit`).
+
+mod emitter_basic;
@@ -0,0 +1,39 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Fuzzes the compiler with randomly generated programs.
+
+use fuzzer::fuzzer::fuzz;
+use fuzzer::program_generator::GeneratorOptions;
+
+#[test]
+fn test_fuzz() {
+    fuzz(
+        0..64,
+        &GeneratorOptions { functions: 0, body_length: 30, block_length: 6, max_depth: 3 },
+    );
+}
+
+#[test]
+fn test_fuzz_nested() {
+    fuzz(
+        0..16,
+        &GeneratorOptions { functions: 0, body_length: 10, block_length: 10, max_depth: 6 },
+    );
+}
+
+#[test]
+fn test_fuzz_register_starvation() {
+    // Long blocks of shallow expressions, where many variables are live at the same time.
+    fuzz(
+        0..16,
+        &GeneratorOptions { functions: 0, body_length: 120, block_length: 20, max_depth: 1 },
+    );
+}
+
+#[test]
+fn test_fuzz_calls() {
+    fuzz(
+        0..32,
+        &GeneratorOptions { functions: 4, body_length: 20, block_length: 6, max_depth: 3 },
+    );
+}
@@ -0,0 +1,61 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for minimizing programs, where the checks are artificial "bugs".
+
+use expect_test::{expect, Expect};
+use fuzzer::program_generator::{generate_program, print_program, GeneratorOptions};
+use fuzzer::program_minimizer::minimize;
+use solis::parser::ast::{ExprKind, Program};
+
+const OPTIONS: GeneratorOptions = GeneratorOptions { functions: 3, body_length: 30, block_length: 6, max_depth: 3 };
+
+// Minimizes a program, and checks the printed source of the minimized program.
+fn minimize_check(program: Program, is_failing: impl Fn(&Program) -> bool, expect: Expect) {
+    expect.assert_eq(&print_program(&minimize(program, is_failing)));
+}
+
+#[test]
+fn test_minimize_source() {
+    let program = generate_program(1, &OPTIONS);
+    assert!(print_program(&program).contains('%'));
+
+    minimize_check(
+        program,
+        |program| print_program(program).contains('%'),
+        expect![[r#"
+            @inline fun func2(f1: int) : bool {
+              let m1: int = (97) % (51);
+              (43.962) <= (17.493)
+            }
+
+            735"#]],
+    );
+}
+
+#[test]
+fn test_minimize_calls() {
+    // Fails if the body of the program has a call.
+    let has_call = |program: &Program| {
+        program
+            .body
+            .exprs
+            .iter()
+            .any(|expr| matches!(expr.kind, ExprKind::Call { .. }))
+    };
+
+    let program = (0..100)
+        .map(|seed| generate_program(seed, &OPTIONS))
+        .find(has_call)
+        .unwrap();
+
+    minimize_check(
+        program,
+        has_call,
+        expect![[r#"
+            @inline fun func2(f1: int) : bool {
+              (43.962) <= (17.493)
+            }
+
+            func2(735)"#]],
+    );
+}
@@ -0,0 +1,120 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Differential fuzzing of the compiler. Random programs are generated (see `program_generator.rs`), and each stage of
+//! the compiler must agree with the interpreter, which is the reference semantics of Solis programs:
+//!   - the printed source code of the program is parsed back, and must interpret to the same result.
+//!   - the optimized program (see `optimizer.rs`) must interpret to the same result.
+//!   - the compiled program (with and without optimizations) is run with the emulator once for each register
+//!     allocator, and must have the same result (or crash with a divide error if the interpreter has a runtime error).
+//!     Calls are not compiled yet, so for programs with functions, only the register allocation is checked.
+//!   - the compiled program is also run natively with the JIT (see `jit.rs`), unless the interpreter has a runtime
+//!     error (which would crash the tests).
+//!
+//! If a program fails, it is minimized (see `program_minimizer.rs`), and the minimized program is reported.
+
+use fuzzer::program_generator::{generate_program, print_program, GeneratorOptions};
+use fuzzer::program_minimizer::minimize;
+use solis::asm::emulator::{emulate, EmulatorError};
+use solis::asm::peephole::peephole_optimize;
+use solis::compiler::compiler::{allocate, compile_with_allocation};
+use solis::interpreter::interpreter::{interpret, Value};
+use solis::ir::translator::translate_program;
+use solis::jit::execute;
+use solis::optimizer::optimizer::optimize;
+use solis::parser::ast::Program;
+use solis::parser::parser::parse;
+use solis::register_allocation::register_allocator::RegisterAllocator;
+use solis::tokenizer::tokenizer::tokenize;
+use solis::File;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Generates a program for each seed, and checks each program. If a program fails, the test panics with the
+/// minimized program.
+pub fn fuzz(seeds: Range<u64>, options: &GeneratorOptions) {
+    for seed in seeds {
+        let program = generate_program(seed, options);
+
+        if let Err(message) = check_program(&program) {
+            let minimized = minimize(program, |program| check_program(program).is_err());
+            panic!(
+                "Seed {seed} failed: {message}\n\nMinimized program:\n{}",
+                print_program(&minimized)
+            );
+        }
+    }
+}
+
+/// Checks that each stage of the compiler agrees with the interpreter on a program, where panics are failures.
+pub fn check_program(program: &Program) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(|| check_stages(program)))
+        .unwrap_or_else(|_| Err("the compiler panicked".to_string()))
+}
+
+// Checks each stage of the compiler against the interpreter.
+fn check_stages(program: &Program) -> Result<(), String> {
+    let file = File { name: "fuzz.sol".to_string(), contents: print_program(program) };
+
+    let expected = interpret(&translate_program(&file, program.clone())).map(Value::to_runtime_output);
+
+    let parsed = interpret(&translate_program(&file, parse(&file, tokenize(&file)))).map(Value::to_runtime_output);
+    if parsed != expected {
+        return Err(format!(
+            "the printed program has a result of {parsed:?}, but expected {expected:?}"
+        ));
+    }
+
+    let mut optimized_ir = translate_program(&file, program.clone());
+    optimize(&mut optimized_ir);
+
+    let optimized = interpret(&optimized_ir).map(Value::to_runtime_output);
+    if optimized != expected {
+        return Err(format!(
+            "the optimized program has a result of {optimized:?}, but expected {expected:?}"
+        ));
+    }
+
+    let ir = translate_program(&file, program.clone());
+    for (ir, name, is_optimized) in [
+        (&ir, "compiled program", false),
+        (&optimized_ir, "compiled optimized program", true),
+    ] {
+        for register_allocator in [RegisterAllocator::Graph, RegisterAllocator::Linear] {
+            if ir.functions.is_empty() {
+                let mut instructions = compile_with_allocation(ir, &allocate(&ir.body, register_allocator));
+                if is_optimized {
+                    instructions = peephole_optimize(instructions);
+                }
+
+                let emulated = emulate(&instructions);
+                if emulated
+                    != expected
+                        .as_ref()
+                        .map_or(Err(EmulatorError::DivideError), |value| Ok(*value))
+                {
+                    return Err(format!(
+                        "the {name} ({register_allocator:?} allocator) has a result of {emulated:?}, but expected \
+                         {expected:?}"
+                    ));
+                }
+
+                if let Ok(expected) = expected {
+                    let executed = execute(&instructions);
+                    if executed != expected {
+                        return Err(format!(
+                            "the {name} ({register_allocator:?} allocator) has a result of {executed} with the JIT, \
+                             but expected {expected}"
+                        ));
+                    }
+                }
+            } else {
+                for function in &ir.functions {
+                    allocate(&function.body, register_allocator);
+                }
+                allocate(&ir.body, register_allocator);
+            }
+        }
+    }
+
+    Ok(())
+}
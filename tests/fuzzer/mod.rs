@@ -0,0 +1,9 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Differential fuzzing tests, on randomly generated programs.
+
+mod fuzz_compile;
+mod fuzz_minimize;
+mod fuzzer;
+mod program_generator;
+mod program_minimizer;
@@ -0,0 +1,458 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Generates random, well-typed Solis programs (as a `ast::Program`), and prints them back to Solis source code. This
+//! is a port of `integration/scripts/random_program_gen.py`, which generated the `random_*.sol` integration tests.
+//!
+//! Like the script, the last expression of each block is usually the sum of the variables of its type that are in
+//! scope, so that many variables are live at the same time (which starves the register allocator).
+
+use solis::parser::ast::{
+    BinaryExprKind, Block, Expr, ExprKind, Function, Inline, Param, Program, Type, UnaryExprKind,
+};
+
+/// Options for the shape of the generated programs.
+pub struct GeneratorOptions {
+    /// Number of functions to generate, where each function can only call the functions before it.
+    pub functions: usize,
+
+    /// Number of expressions in the body of the program.
+    pub body_length: usize,
+
+    /// Maximum number of expressions in nested blocks (like the blocks of if expressions and functions).
+    pub block_length: usize,
+
+    /// Maximum depth of nested expressions.
+    pub max_depth: usize,
+}
+
+/// A pseudo random number generator (splitmix64), which is seeded for repeatability.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// The next random 64 bit number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut result = self.state;
+        result = (result ^ (result >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        result = (result ^ (result >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        result ^ (result >> 31)
+    }
+
+    /// A random number in `0..bound`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// A random index of `weights`, where the probability of each index is proportional to its weight.
+    pub fn weighted(&mut self, weights: &[usize]) -> usize {
+        let mut choice = self.below(weights.iter().sum());
+
+        weights
+            .iter()
+            .position(|weight| {
+                let is_chosen = choice < *weight;
+                choice = choice.saturating_sub(*weight);
+                is_chosen
+            })
+            .unwrap_or(0)
+    }
+
+    /// True with a probability of `1 / n`.
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+}
+
+// The types of values that are generated (unit is never generated).
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    Int,
+    Float,
+    Bool,
+}
+
+const VALUE_TYPES: [ValueType; 3] = [ValueType::Int, ValueType::Float, ValueType::Bool];
+
+// A generated function that can be called, with the types of its params and its return type.
+struct FunctionSignature {
+    id: String,
+    param_types: Vec<ValueType>,
+    return_type: ValueType,
+}
+
+// State that is kept while generating a program.
+struct Generator<'a> {
+    random: Random,
+    options: &'a GeneratorOptions,
+    functions: Vec<FunctionSignature>,
+    id_count: usize,
+}
+
+/// Generates a random program, where the same `seed` always generates the same program.
+pub fn generate_program(seed: u64, options: &GeneratorOptions) -> Program {
+    let mut generator = Generator { random: Random::new(seed), options, functions: vec![], id_count: 0 };
+
+    let mut functions = vec![];
+    for i in 0..options.functions {
+        functions.push(generator.gen_function(format!("func{}", i + 1)));
+    }
+
+    let result_type = VALUE_TYPES[generator.random.below(VALUE_TYPES.len())];
+    let body = generator.gen_block(vec![], options.body_length, result_type, 0);
+
+    Program { functions, body }
+}
+
+impl Generator<'_> {
+    // Generates a unique id, in the same format as the script (`a1`, `b1`, ..., `x1`, `a2`, ...).
+    fn gen_unique_id(&mut self) -> String {
+        let letter = (b'a'..=b'x')
+            .nth(self.id_count % 24)
+            .map(char::from)
+            .unwrap_or_default();
+        let id = format!("{letter}{}", self.id_count / 24 + 1);
+        self.id_count += 1;
+        id
+    }
+
+    // Generates a function with random params and a random return type.
+    fn gen_function(&mut self, id: String) -> Function {
+        let param_types: Vec<ValueType> = (0..self.random.below(4))
+            .map(|_| VALUE_TYPES[self.random.below(VALUE_TYPES.len())])
+            .collect();
+        let params: Vec<(String, ValueType)> = param_types
+            .iter()
+            .map(|param_type| (self.gen_unique_id(), *param_type))
+            .collect();
+        let return_type = VALUE_TYPES[self.random.below(VALUE_TYPES.len())];
+
+        let body_length = 1 + self.random.below(self.options.block_length);
+        let body = self.gen_block(params.clone(), body_length, return_type, 0);
+
+        // The function is registered after its body is generated, so that there is no recursion.
+        self.functions
+            .push(FunctionSignature { id: id.clone(), param_types, return_type });
+
+        Function {
+            id,
+            params: params
+                .into_iter()
+                .map(|(id, param_type)| Param { id, type_reference: to_ast_type(param_type), position: 0..0 })
+                .collect(),
+            return_type: to_ast_type(return_type),
+            body,
+            inline: [Inline::Auto, Inline::Always, Inline::Never][self.random.below(3)],
+            position: 0..0,
+        }
+    }
+
+    // Generates a block with `length` expressions, that results in `result_type`. The bindings of the block are only in
+    // scope of the block.
+    fn gen_block(
+        &mut self,
+        mut bindings: Vec<(String, ValueType)>,
+        length: usize,
+        result_type: ValueType,
+        depth: usize,
+    ) -> Block {
+        let mut exprs = vec![];
+
+        for _ in 1..length {
+            let expr_type = VALUE_TYPES[self.random.below(VALUE_TYPES.len())];
+
+            if self.random.one_in(2) {
+                let id = self.gen_unique_id();
+                let init_expr = self.gen_expr(&bindings, expr_type, depth);
+
+                bindings.push((id.clone(), expr_type));
+                exprs.push(to_expr(ExprKind::Let {
+                    id,
+                    id_position: 0..0,
+                    type_reference: to_ast_type(expr_type),
+                    init_expr: Box::new(init_expr),
+                }));
+            } else {
+                exprs.push(self.gen_expr(&bindings, expr_type, depth));
+            }
+        }
+
+        // For ints and floats, the result is usually a sum of bindings, which are all live until the end of the block.
+        let typed_ids: Vec<&String> = bindings
+            .iter()
+            .filter(|(_, t)| *t == result_type)
+            .map(|(id, _)| id)
+            .collect();
+
+        if result_type != ValueType::Bool && !typed_ids.is_empty() && !self.random.one_in(4) {
+            let mut summands: Vec<&String> = typed_ids.iter().copied().filter(|_| !self.random.one_in(3)).collect();
+            summands.push(typed_ids[self.random.below(typed_ids.len())]);
+
+            // Floats can be summed with ints.
+            if result_type == ValueType::Float {
+                let int_ids = bindings.iter().filter(|(_, t)| *t == ValueType::Int).map(|(id, _)| id);
+                summands.extend(int_ids.filter(|_| self.random.one_in(3)));
+            }
+
+            let sum = summands
+                .into_iter()
+                .map(|id| to_expr(ExprKind::Id { value: id.clone() }))
+                .reduce(|sum, id| {
+                    to_expr(ExprKind::BinaryExpr {
+                        kind: BinaryExprKind::Plus,
+                        operand_1: Box::new(sum),
+                        operand_2: Box::new(id),
+                    })
+                });
+            exprs.extend(sum);
+        } else {
+            exprs.push(self.gen_expr(&bindings, result_type, depth));
+        }
+
+        Block { exprs }
+    }
+
+    // Generates an expression that results in `expr_type`.
+    fn gen_expr(&mut self, bindings: &[(String, ValueType)], expr_type: ValueType, depth: usize) -> Expr {
+        let ids: Vec<&String> = bindings
+            .iter()
+            .filter(|(_, t)| *t == expr_type)
+            .map(|(id, _)| id)
+            .collect();
+        let functions: Vec<usize> = (0..self.functions.len())
+            .filter(|i| self.functions[*i].return_type == expr_type)
+            .collect();
+
+        // Leaves are literals and ids, and deeper expressions are weighted towards leaves.
+        let leaf_weight = 4 + depth * 4;
+        let can_nest = depth < self.options.max_depth;
+        let weights = [
+            leaf_weight,
+            if ids.is_empty() { 0 } else { leaf_weight },
+            4 * usize::from(can_nest),
+            usize::from(can_nest),
+            usize::from(can_nest && !functions.is_empty()),
+        ];
+
+        let expr = match self.random.weighted(&weights) {
+            0 => self.gen_literal(expr_type),
+            1 => to_expr(ExprKind::Id { value: ids[self.random.below(ids.len())].clone() }),
+            2 => self.gen_operation(bindings, expr_type, depth + 1),
+            3 => self.gen_if(bindings, expr_type, depth + 1),
+            _ => {
+                let function_index = functions[self.random.below(functions.len())];
+                self.gen_call(bindings, function_index, depth + 1)
+            }
+        };
+
+        // Wrap the result in a unary operator.
+        if self.random.one_in(4) {
+            let kind = if expr_type == ValueType::Bool { UnaryExprKind::Not } else { UnaryExprKind::Negative };
+            to_expr(ExprKind::UnaryExpr { kind, operand: Box::new(expr) })
+        } else {
+            expr
+        }
+    }
+
+    // Generates a literal of `literal_type`. Ints are occasionally large, so that they don't fit in 32 bits.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_precision_loss)]
+    fn gen_literal(&mut self, literal_type: ValueType) -> Expr {
+        to_expr(match literal_type {
+            ValueType::Int if self.random.one_in(16) => ExprKind::Int { value: (self.random.next_u64() >> 1) as i64 },
+            ValueType::Int => ExprKind::Int { value: self.random.below(1000) as i64 },
+            ValueType::Float => ExprKind::Float { value: self.random.below(50123) as f64 / 1000.0 },
+            ValueType::Bool => ExprKind::Bool { value: self.random.one_in(2) },
+        })
+    }
+
+    // Generates a binary expression that results in `expr_type`. Ints and floats are mixed in float arithmetic and
+    // in comparisons.
+    fn gen_operation(&mut self, bindings: &[(String, ValueType)], expr_type: ValueType, depth: usize) -> Expr {
+        use self::ValueType::*;
+        use solis::parser::ast::BinaryExprKind::*;
+
+        let (kind, operand_1_type, operand_2_type) = match expr_type {
+            Int => (
+                [Plus, Minus, Times, Divide, Mod][self.random.below(5)].clone(),
+                Int,
+                Int,
+            ),
+            Float => {
+                let (operand_1_type, operand_2_type) =
+                    [(Float, Float), (Float, Int), (Int, Float)][self.random.below(3)];
+                (
+                    [Plus, Minus, Times, Divide][self.random.below(4)].clone(),
+                    operand_1_type,
+                    operand_2_type,
+                )
+            }
+            Bool if self.random.one_in(2) => {
+                let operand_1_type = [Int, Float][self.random.below(2)];
+                let operand_2_type = [Int, Float][self.random.below(2)];
+                (
+                    [LessThan, LessThanOrEquals, MoreThan, MoreThanOrEquals][self.random.below(4)].clone(),
+                    operand_1_type,
+                    operand_2_type,
+                )
+            }
+            Bool => {
+                let operand_type = VALUE_TYPES[self.random.below(VALUE_TYPES.len())];
+                (
+                    [EqualsEquals, NotEquals][self.random.below(2)].clone(),
+                    operand_type,
+                    operand_type,
+                )
+            }
+        };
+
+        let operand_1 = self.gen_expr(bindings, operand_1_type, depth);
+        let operand_2 = self.gen_expr(bindings, operand_2_type, depth);
+        to_expr(ExprKind::BinaryExpr { kind, operand_1: Box::new(operand_1), operand_2: Box::new(operand_2) })
+    }
+
+    // Generates an if expression (with an else block) that results in `expr_type`.
+    fn gen_if(&mut self, bindings: &[(String, ValueType)], expr_type: ValueType, depth: usize) -> Expr {
+        let condition = self.gen_expr(bindings, ValueType::Bool, depth);
+
+        let then_length = 1 + self.random.below(self.options.block_length);
+        let then_block = self.gen_block(bindings.to_vec(), then_length, expr_type, depth);
+
+        let else_length = 1 + self.random.below(self.options.block_length);
+        let else_block = self.gen_block(bindings.to_vec(), else_length, expr_type, depth);
+
+        to_expr(ExprKind::If {
+            condition: Box::new(condition),
+            then_block,
+            else_block: Some(else_block),
+        })
+    }
+
+    // Generates a call to the `function_index`th function, with random args.
+    fn gen_call(&mut self, bindings: &[(String, ValueType)], function_index: usize, depth: usize) -> Expr {
+        let param_types = self.functions[function_index].param_types.clone();
+        let args = param_types
+            .into_iter()
+            .map(|param_type| self.gen_expr(bindings, param_type, depth))
+            .collect();
+
+        to_expr(ExprKind::Call { id: self.functions[function_index].id.clone(), args })
+    }
+}
+
+// Creates an expression of `kind`. Generated expressions don't have a position in the source code.
+const fn to_expr(kind: ExprKind) -> Expr {
+    Expr { kind, position: 0..0 }
+}
+
+// Converts a `ValueType` into a `ast::Type`.
+const fn to_ast_type(value_type: ValueType) -> Type {
+    match value_type {
+        ValueType::Int => Type::Int,
+        ValueType::Float => Type::Float,
+        ValueType::Bool => Type::Bool,
+    }
+}
+
+/// Prints a program as Solis source code, in the same format as the script (every operand is parenthesized, and each
+/// expression of a block is terminated with a `;`, except for the last).
+pub fn print_program(program: &Program) -> String {
+    let functions = program.functions.iter().fold(String::new(), |acc, function| {
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .map(|param| format!("{}: {}", param.id, print_type(&param.type_reference)))
+            .collect();
+
+        let annotation = match function.inline {
+            Inline::Auto => "",
+            Inline::Always => "@inline ",
+            Inline::Never => "@noinline ",
+        };
+
+        acc + &format!(
+            "{annotation}fun {}({}) : {} {}\n\n",
+            function.id,
+            params.join(", "),
+            print_type(&function.return_type),
+            print_nested_block(&function.body)
+        )
+    });
+
+    functions + &print_block(&program.body)
+}
+
+// Prints the expressions of a block, separated by `;` and newlines.
+fn print_block(block: &Block) -> String {
+    let exprs: Vec<String> = block.exprs.iter().map(print_expr).collect();
+    exprs.join(";\n")
+}
+
+// Prints a block in braces, where the expressions are indented.
+fn print_nested_block(block: &Block) -> String {
+    format!("{{\n  {}\n}}", print_block(block).replace('\n', "\n  "))
+}
+
+// Prints an expression.
+fn print_expr(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::Let { id, type_reference, init_expr, .. } => {
+            format!("let {id}: {} = {}", print_type(type_reference), print_expr(init_expr))
+        }
+        ExprKind::If { condition, then_block, else_block } => {
+            let if_expr = format!("if {} {}", print_expr(condition), print_nested_block(then_block));
+
+            match else_block {
+                Some(else_block) => format!("{if_expr} else {}", print_nested_block(else_block)),
+                None => if_expr,
+            }
+        }
+        ExprKind::Int { value } => value.to_string(),
+        ExprKind::Bool { value } => value.to_string(),
+        ExprKind::Float { value } => format!("{value:?}"),
+        ExprKind::Id { value } => value.clone(),
+        ExprKind::UnaryExpr { kind, operand } => {
+            let operator = match kind {
+                UnaryExprKind::Not => "!",
+                UnaryExprKind::Negative => "-",
+            };
+            format!("{operator}({})", print_expr(operand))
+        }
+        ExprKind::BinaryExpr { kind, operand_1, operand_2 } => {
+            let operator = match kind {
+                BinaryExprKind::Plus => "+",
+                BinaryExprKind::Minus => "-",
+                BinaryExprKind::Times => "*",
+                BinaryExprKind::Divide => "/",
+                BinaryExprKind::Mod => "%",
+                BinaryExprKind::LessThan => "<",
+                BinaryExprKind::LessThanOrEquals => "<=",
+                BinaryExprKind::MoreThan => ">",
+                BinaryExprKind::MoreThanOrEquals => ">=",
+                BinaryExprKind::EqualsEquals => "==",
+                BinaryExprKind::NotEquals => "!=",
+            };
+            format!("({}) {operator} ({})", print_expr(operand_1), print_expr(operand_2))
+        }
+        ExprKind::Call { id, args } => {
+            let args: Vec<String> = args.iter().map(print_expr).collect();
+            format!("{id}({})", args.join(", "))
+        }
+    }
+}
+
+// Prints a type, as it is written in Solis source code.
+const fn print_type(ast_type: &Type) -> &'static str {
+    match ast_type {
+        Type::Unit => "unit",
+        Type::Int => "int",
+        Type::Bool => "bool",
+        Type::Float => "float",
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Minimizes a program that fails a check, so that the failure is easier to debug.
+//!
+//! The minimizer greedily tries smaller versions of the program, where each version has one edit:
+//!   - removing a function, or an expression of a block.
+//!   - replacing an expression with one of its sub expressions (like an operand, or the last expression of a block).
+//!
+//! An edit is kept if the program is still well-typed, and still fails the check. The result of programs of the unit
+//! type is not specified (see `interpreter.rs`), so edits must also keep a result for the body of the program. This is repeated until no edit is
+//! kept, which means that the result is minimal (but not necessarily the smallest possible program).
+
+use fuzzer::program_generator::print_program;
+use solis::ir::translator::translate_program;
+use solis::parser::ast::{Block, Expr, ExprKind, Program};
+use solis::File;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Minimizes a program, where `is_failing` is true if a program fails the check.
+pub fn minimize(program: Program, is_failing: impl Fn(&Program) -> bool) -> Program {
+    let mut program = program;
+
+    // Programs that are not well-typed (and checks that fail) panic, so the messages of each panic are silenced.
+    let panic_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut is_minimal = false;
+    while !is_minimal {
+        is_minimal = true;
+
+        let mut target = 0;
+        while let Some(candidate) = apply_edit(&program, target) {
+            if has_result(&candidate) && is_well_typed(&candidate) && is_failing(&candidate) {
+                program = candidate;
+                is_minimal = false;
+            } else {
+                target += 1;
+            }
+        }
+    }
+
+    panic::set_hook(panic_hook);
+    program
+}
+
+// Checks if the body of a program has a result, which means that it is not empty and doesn't end in a let.
+fn has_result(program: &Program) -> bool {
+    program
+        .body
+        .exprs
+        .last()
+        .map_or(false, |expr| !matches!(expr.kind, ExprKind::Let { .. }))
+}
+
+// Checks if a program is well-typed, by type checking it with the translator.
+fn is_well_typed(program: &Program) -> bool {
+    let file = File { name: "minimized.sol".to_string(), contents: print_program(program) };
+    panic::catch_unwind(AssertUnwindSafe(|| translate_program(&file, program.clone()))).is_ok()
+}
+
+// Applies the `target`th edit of the program (in the order that the editor visits them).
+// * return - the edited program, or `None` if there are not that many edits.
+fn apply_edit(program: &Program, target: usize) -> Option<Program> {
+    let mut program = program.clone();
+    let mut editor = Editor { remaining: target, is_applied: false };
+
+    editor.edit_program(&mut program);
+    editor.is_applied.then_some(program)
+}
+
+// Visits each possible edit of a program, where the edit is applied once `remaining` edits have been skipped.
+struct Editor {
+    remaining: usize,
+    is_applied: bool,
+}
+
+impl Editor {
+    // Visits the next edit, returning true if it should be applied.
+    fn visit(&mut self) -> bool {
+        if self.is_applied {
+            return false;
+        }
+
+        if self.remaining == 0 {
+            self.is_applied = true;
+            true
+        } else {
+            self.remaining -= 1;
+            false
+        }
+    }
+
+    fn edit_program(&mut self, program: &mut Program) {
+        for i in 0..program.functions.len() {
+            if self.visit() {
+                program.functions.remove(i);
+                return;
+            }
+        }
+
+        for function in &mut program.functions {
+            self.edit_block(&mut function.body);
+        }
+        self.edit_block(&mut program.body);
+    }
+
+    fn edit_block(&mut self, block: &mut Block) {
+        for i in 0..block.exprs.len() {
+            if self.visit() {
+                block.exprs.remove(i);
+                return;
+            }
+        }
+
+        for expr in &mut block.exprs {
+            self.edit_expr(expr);
+        }
+    }
+
+    fn edit_expr(&mut self, expr: &mut Expr) {
+        for replacement in sub_exprs(expr) {
+            if self.visit() {
+                *expr = replacement;
+                return;
+            }
+        }
+
+        match &mut expr.kind {
+            ExprKind::Let { init_expr, .. } => self.edit_expr(init_expr),
+            ExprKind::If { condition, then_block, else_block } => {
+                self.edit_expr(condition);
+                self.edit_block(then_block);
+                if let Some(else_block) = else_block {
+                    self.edit_block(else_block);
+                }
+            }
+            ExprKind::UnaryExpr { operand, .. } => self.edit_expr(operand),
+            ExprKind::BinaryExpr { operand_1, operand_2, .. } => {
+                self.edit_expr(operand_1);
+                self.edit_expr(operand_2);
+            }
+            ExprKind::Call { args, .. } => {
+                for arg in args {
+                    self.edit_expr(arg);
+                }
+            }
+            ExprKind::Int { .. } | ExprKind::Bool { .. } | ExprKind::Float { .. } | ExprKind::Id { .. } => {}
+        }
+    }
+}
+
+// The sub expressions that can replace an expression.
+fn sub_exprs(expr: &Expr) -> Vec<Expr> {
+    match &expr.kind {
+        ExprKind::If { then_block, else_block, .. } => {
+            let mut sub_exprs: Vec<Expr> = then_block.exprs.last().cloned().into_iter().collect();
+            sub_exprs.extend(
+                else_block
+                    .iter()
+                    .filter_map(|else_block| else_block.exprs.last().cloned()),
+            );
+            sub_exprs
+        }
+        ExprKind::UnaryExpr { operand, .. } => vec![*operand.clone()],
+        ExprKind::BinaryExpr { operand_1, operand_2, .. } => vec![*operand_1.clone(), *operand_2.clone()],
+        ExprKind::Call { args, .. } => args.clone(),
+        _ => vec![],
+    }
+}
@@ -1,37 +1,145 @@
 // Copyright © 2022-2023 Brandon Li. All rights reserved.
 
 //! Integration tests module.
+//!
+//! Each integration test is run with the interpreter (with and without optimizations), where the output is compared to
+//! the expected output in `integration/expected`. The interpreter is then used as an oracle for the executable: the output of running the
+//! executable must match the output of the interpreter.
 
 use assert_cmd::Command;
 use std::fs;
 
-// Runs a given integration test.
+// Runs the solis binary on a integration test, and returns the output (stdout).
 // * name - the name of the integration test, which corresponds to a file in the `integration` directory.
-fn run_integration_test(integration_test_name: &str) {
-    // Get the expected output (stdout) of the solis file, in `integration/expected`
-    let expected_output =
-        fs::read_to_string(format!("./tests/integration/expected/{integration_test_name}.out")).unwrap();
-
-    Command::cargo_bin("solis")
+// * destination - the directory to create the intermediate files and executable.
+// * args - additional arguments to the solis binary.
+fn run_solis(integration_test_name: &str, destination: &str, args: &[&str]) -> String {
+    let output = Command::cargo_bin("solis")
         .unwrap()
         .arg(format!("./tests/integration/{integration_test_name}.sol"))
         .arg("-d")
-        .arg("./build/solis_tests/")
+        .arg(destination)
         .arg("-n")
         .arg(integration_test_name)
-        .arg("-r")
+        .args(args)
         .assert()
         .success()
-        .stdout(expected_output);
+        .get_output()
+        .stdout
+        .clone();
+
+    String::from_utf8(output).unwrap()
 }
 
-// Macro to create a test function for each registered integration test.
+// Runs a given integration test with the interpreter, comparing the output to the expected output.
+fn run_interpreted_integration_test(integration_test_name: &str) {
+    // Get the expected output (stdout) of the solis file, in `integration/expected`
+    let expected_output =
+        fs::read_to_string(format!("./tests/integration/expected/{integration_test_name}.out")).unwrap();
+
+    assert_eq!(
+        run_solis(integration_test_name, "./build/solis_tests/", &["-i"]),
+        expected_output
+    );
+    assert_eq!(
+        run_solis(integration_test_name, "./build/solis_tests/", &["-i", "--no-optimize"]),
+        expected_output
+    );
+}
+
+// Runs a given integration test natively, comparing the output to the output of the interpreter.
+// * assembler - the assembler to create the executable with (see `--assembler`).
+fn run_integration_test(integration_test_name: &str, assembler: &str) {
+    let expected_output = run_solis(integration_test_name, "./build/solis_tests/", &["-i"]);
+
+    // Each assembler creates its files in a separate directory, since the tests run in parallel.
+    assert_eq!(
+        run_solis(
+            integration_test_name,
+            &format!("./build/solis_tests/{assembler}/"),
+            &["-r", "--assembler", assembler]
+        ),
+        expected_output
+    );
+}
+
+// Runs a given integration test in the process of the compiler (with `--jit`), with and without optimizations,
+// comparing the output to the output of the interpreter.
+fn run_jit_integration_test(integration_test_name: &str) {
+    let expected_output = run_solis(integration_test_name, "./build/solis_tests/", &["-i"]);
+
+    assert_eq!(
+        run_solis(integration_test_name, "./build/solis_tests/jit/", &["--jit"]),
+        expected_output
+    );
+    assert_eq!(
+        run_solis(
+            integration_test_name,
+            "./build/solis_tests/jit/",
+            &["--jit", "--no-optimize"]
+        ),
+        expected_output
+    );
+}
+
+// Runs a given integration test with the C backend, with and without optimizations, comparing the output to the output
+// of the interpreter.
+fn run_c_integration_test(integration_test_name: &str) {
+    let expected_output = run_solis(integration_test_name, "./build/solis_tests/", &["-i"]);
+
+    assert_eq!(
+        run_solis(
+            integration_test_name,
+            "./build/solis_tests/c/",
+            &["-r", "--backend", "c"]
+        ),
+        expected_output
+    );
+    assert_eq!(
+        run_solis(
+            integration_test_name,
+            "./build/solis_tests/c/",
+            &["-r", "--backend", "c", "--no-optimize"]
+        ),
+        expected_output
+    );
+}
+
+// Macro to create a module for each registered integration test, with test functions to run the test natively (with
+// each assembler, with the C backend, and with the JIT) and a test function to run the test with the interpreter.
 macro_rules! gen_integration_tests {
     ($($integration_test_name:ident), *) => {
         $(
-            #[test]
-            fn $integration_test_name() {
-                run_integration_test(stringify!($integration_test_name))
+            mod $integration_test_name {
+                #[test]
+                fn native() {
+                    super::run_integration_test(stringify!($integration_test_name), "nasm")
+                }
+
+                #[test]
+                fn native_gas() {
+                    super::run_integration_test(stringify!($integration_test_name), "gas")
+                }
+
+                #[test]
+                fn native_builtin() {
+                    super::run_integration_test(stringify!($integration_test_name), "builtin")
+                }
+
+                #[test]
+                fn native_c() {
+                    super::run_c_integration_test(stringify!($integration_test_name))
+                }
+
+                #[test]
+                fn jit() {
+                    super::run_jit_integration_test(stringify!($integration_test_name))
+                }
+
+                #[test]
+                fn interpreted() {
+                    super::run_interpreted_integration_test(stringify!($integration_test_name))
+                }
             }
         )*
     }
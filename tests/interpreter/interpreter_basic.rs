@@ -0,0 +1,85 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Basic tests for interpreting programs with ints, bools, and ifs.
+
+use expect_test::expect;
+use test_utils::{interpret_check, interpret_ir_check};
+
+#[test]
+fn test_empty() {
+    interpret_check("", expect!["Ok(Unit)"]);
+}
+
+#[test]
+fn test_arithmetic() {
+    interpret_check("let a: int = 1 + 2 * 3 - -4; a / 3 + a % 4", expect!["Ok(Int(6))"]);
+}
+
+#[test]
+fn test_division_truncates() {
+    interpret_check("-7 / 2 + -7 % 2 * 10", expect!["Ok(Int(-13))"]);
+}
+
+#[test]
+fn test_wrapping() {
+    interpret_check("9223372036854775807 + 1", expect!["Ok(Int(-9223372036854775808))"]);
+}
+
+#[test]
+fn test_bools() {
+    interpret_check(
+        "let a: bool = !(1 < 2) == false; a != (3 >= 4)",
+        expect!["Ok(Bool(true))"],
+    );
+}
+
+#[test]
+fn test_bool_comparison() {
+    interpret_ir_check("gt.bool true, false", expect!["Ok(Bool(true))"]);
+}
+
+#[test]
+fn test_if() {
+    interpret_check(
+        "
+        let a: int = 3
+        let b: int = if a > 2 { let c: int = a * 2; c } else { 0 }
+        if b == 6 { b + 1 } else { b }
+        ",
+        expect!["Ok(Int(7))"],
+    );
+}
+
+#[test]
+fn test_if_without_else() {
+    interpret_check("if false { 1 }", expect!["Ok(Unit)"]);
+}
+
+#[test]
+fn test_shadowing_in_scope() {
+    interpret_ir_check(
+        "
+        let a = 1
+        if true {
+          let a = 2.5
+        }
+        a: int
+        ",
+        expect!["Ok(Int(1))"],
+    );
+}
+
+#[test]
+fn test_division_by_zero() {
+    interpret_check("let a: int = 0; 1 / a", expect!["Err(DivisionByZero)"]);
+}
+
+#[test]
+fn test_mod_by_zero() {
+    interpret_check("let a: int = 0; 1 % a", expect!["Err(DivisionByZero)"]);
+}
+
+#[test]
+fn test_division_overflow() {
+    interpret_ir_check("div.int -9223372036854775808, -1", expect!["Err(DivisionOverflow)"]);
+}
@@ -0,0 +1,40 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for interpreting programs with floats, which must match the semantics of the compiled floating point.
+
+use expect_test::expect;
+use test_utils::{interpret_check, interpret_ir_check};
+
+#[test]
+fn test_arithmetic() {
+    interpret_check("let a: float = 1.5 * 2 + 1 / 4.0; a - 0.5", expect!["Ok(Float(2.75))"]);
+}
+
+#[test]
+fn test_coercion() {
+    interpret_check("let a: int = 3; a / 2.0", expect!["Ok(Float(1.5))"]);
+}
+
+#[test]
+fn test_negative_zero() {
+    // Negation is `0.0 - x`, like the compiler.
+    interpret_ir_check("neg.float 0.0", expect!["Ok(Float(0.0))"]);
+}
+
+#[test]
+fn test_division_by_zero() {
+    interpret_check("1.0 / 0.0", expect!["Ok(Float(inf))"]);
+}
+
+#[test]
+fn test_nan_comparisons() {
+    // Like `cmpsd`, `>`, `>=`, and `!=` are true when comparing with NaN.
+    let nan_comparison = |operator: &str| format!("let nan = div.float 0.0, 0.0\n{operator}.float nan, 1.0");
+
+    interpret_ir_check(&nan_comparison("lt"), expect!["Ok(Bool(false))"]);
+    interpret_ir_check(&nan_comparison("le"), expect!["Ok(Bool(false))"]);
+    interpret_ir_check(&nan_comparison("gt"), expect!["Ok(Bool(true))"]);
+    interpret_ir_check(&nan_comparison("ge"), expect!["Ok(Bool(true))"]);
+    interpret_ir_check(&nan_comparison("eq"), expect!["Ok(Bool(false))"]);
+    interpret_ir_check(&nan_comparison("ne"), expect!["Ok(Bool(true))"]);
+}
@@ -0,0 +1,55 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for interpreting programs with function calls.
+
+use expect_test::expect;
+use test_utils::interpret_check;
+
+#[test]
+fn test_fib() {
+    interpret_check(
+        "
+        fun fib(n: int) : int {
+          if n <= 1 {
+            1
+          }
+          else {
+            fib(n - 1) + fib(n - 2)
+          }
+        }
+
+        fib(15)
+        ",
+        expect!["Ok(Int(987))"],
+    );
+}
+
+#[test]
+fn test_params_are_scoped() {
+    interpret_check(
+        "
+        fun f(a: int, b: float) : float {
+          a * b
+        }
+
+        let a: int = 10
+        let c: float = f(2, 1.5)
+        c + a
+        ",
+        expect!["Ok(Float(13.0))"],
+    );
+}
+
+#[test]
+fn test_unit_function() {
+    interpret_check(
+        "
+        fun f() : () {
+          let a: int = 1 / 0
+        }
+
+        f()
+        ",
+        expect!["Err(DivisionByZero)"],
+    );
+}
@@ -0,0 +1,7 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Unit tests for the interpreter module.
+
+mod interpreter_basic;
+mod interpreter_float;
+mod interpreter_function;
@@ -0,0 +1,186 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for parsing and printing hand-written textual IR.
+
+use expect_test::expect;
+use test_utils::ir_round_trip_check;
+
+#[test]
+fn test_empty() {
+    ir_round_trip_check("", expect![""]);
+}
+
+#[test]
+fn test_directs() {
+    ir_round_trip_check(
+        "
+        1
+        -23
+        true
+        false
+        2.5
+        -0.0
+        1e100
+        +inf
+        -inf
+        +NaN
+        a: int
+        %temp0: float
+        b: unit
+        ",
+        expect![[r#"
+            1
+            -23
+            true
+            false
+            2.5
+            -0.0
+            1e100
+            +inf
+            -inf
+            +NaN
+            a: int
+            %temp0: float
+            b: unit
+        "#]],
+    );
+}
+
+#[test]
+fn test_operations() {
+    ir_round_trip_check(
+        "
+        let %t1 = add.int a, 2
+        let %t2 = sub.int %t1, -1
+        let b = mul.float c, d
+        let %t3 = div.int 1, 2
+        let %t4 = mod.int 1, e
+        let %t5 = lt.float f, f
+        let %t6 = le.int 1, 2
+        let %t7 = gt.int 1, 2
+        let %t8 = ge.int 1, 2
+        let %t9 = eq.bool true, g
+        let %t10 = ne.bool true, false
+        let %t11 = neg.float 2.5
+        let %t12 = not.bool %t10
+        let %t13 = coerce.int.float %t4
+        %t13: float
+        ",
+        expect![[r#"
+            let %t1 = add.int a, 2
+            let %t2 = sub.int %t1, -1
+            let b = mul.float c, d
+            let %t3 = div.int 1, 2
+            let %t4 = mod.int 1, e
+            let %t5 = lt.float f, f
+            let %t6 = le.int 1, 2
+            let %t7 = gt.int 1, 2
+            let %t8 = ge.int 1, 2
+            let %t9 = eq.bool true, g
+            let %t10 = ne.bool true, false
+            let %t11 = neg.float 2.5
+            let %t12 = not.bool %t10
+            let %t13 = coerce.int.float %t4
+            %t13: float
+        "#]],
+    );
+}
+
+#[test]
+fn test_explicit_operand_types() {
+    // Types of operands are only printed if they are not implied by the operation.
+    ir_round_trip_check(
+        "
+        let a = add.int b: int, c: float
+        if d: bool {}
+        ",
+        expect![[r#"
+            let a = add.int b, c: float
+            if d {}
+        "#]],
+    );
+}
+
+#[test]
+fn test_if() {
+    ir_round_trip_check(
+        "
+        if %c { let a = 1 a: int } else { if b {} else { 2 } }
+        let d = if true {
+          1
+        }
+        ",
+        expect![[r#"
+            if %c {
+              let a = 1
+              a: int
+            } else {
+              if b {} else {
+                2
+              }
+            }
+            let d = if true {
+              1
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn test_functions_and_calls() {
+    ir_round_trip_check(
+        "
+        # Comments are ignored.
+        fun f(a: int, b: float): unit { # Even after code
+          let c = call f(a: int, 2.5) live(a)
+          call g()
+        }
+
+        fun g(): unit {}
+
+        let %t0 = call f(1, 2) live(y, %t1, x)
+        call: int
+        live: bool
+        ",
+        expect![[r#"
+            fun f(a: int, b: float): unit {
+              let c = call f(a: int, 2.5) live(a)
+              call g()
+            }
+
+            fun g(): unit {}
+
+            let %t0 = call f(1, 2) live(%t1, x, y)
+            call: int
+            live: bool
+        "#]],
+    );
+}
+
+#[test]
+fn test_function_annotations() {
+    ir_round_trip_check(
+        "
+        @inline fun f(a: int): int { a: int }
+        @noinline
+        fun g(): int { 1 }
+        fun h(): int { 2 }
+        0
+        ",
+        expect![[r#"
+            @inline fun f(a: int): int {
+              a: int
+            }
+
+            @noinline fun g(): int {
+              1
+            }
+
+            fun h(): int {
+              2
+            }
+
+            0
+        "#]],
+    );
+}
@@ -0,0 +1,104 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for compilation errors when parsing textual IR.
+
+use expect_test::expect;
+use test_utils::ir_parse_error_check;
+
+#[test]
+fn test_missing_type() {
+    ir_parse_error_check(
+        "let a = b",
+        expect![[r#"
+        Error: Syntax Error: expected the type of identifier
+         --> :1:8
+          |
+        1 | let a = b
+          |         ^
+    "#]],
+    );
+}
+
+#[test]
+fn test_invalid_type() {
+    ir_parse_error_check(
+        "let a = add.str b, c",
+        expect![[r#"
+        Error: Invalid type: str
+         --> :1:8
+          |
+        1 | let a = add.str b, c
+          |         ^^^^^^^
+    "#]],
+    );
+}
+
+#[test]
+fn test_unknown_operator() {
+    ir_parse_error_check(
+        "let a = pow.int b, c",
+        expect![[r#"
+        Error: Syntax Error: unknown operator `pow`
+         --> :1:8
+          |
+        1 | let a = pow.int b, c
+          |         ^^^^^^^
+    "#]],
+    );
+}
+
+#[test]
+fn test_missing_operand() {
+    ir_parse_error_check(
+        "let a = add.int b",
+        expect![[r#"
+        Error: Syntax Error: unexpected end of file
+         --> :1:16
+          |
+        1 | let a = add.int b
+          |                 ^
+    "#]],
+    );
+}
+
+#[test]
+fn test_invalid_number() {
+    ir_parse_error_check(
+        "1.2.3",
+        expect![[r#"
+        Error: Syntax Error: invalid float
+         --> :1:0
+          |
+        1 | 1.2.3
+          | ^^^^^
+    "#]],
+    );
+}
+
+#[test]
+fn test_unclosed_block() {
+    ir_parse_error_check(
+        "if a { 1",
+        expect![[r#"
+        Error: Syntax Error: unexpected end of file
+         --> :1:7
+          |
+        1 | if a { 1
+          |        ^
+    "#]],
+    );
+}
+
+#[test]
+fn test_unexpected_token() {
+    ir_parse_error_check(
+        "}",
+        expect![[r#"
+        Error: Syntax Error: unexpected token
+         --> :1:0
+          |
+        1 | }
+          | ^
+    "#]],
+    );
+}
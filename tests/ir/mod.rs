@@ -2,6 +2,8 @@
 
 //! Unit tests for the IR module.
 
+mod ir_text_basic;
+mod ir_text_errors;
 mod translate_basic;
 mod translate_comprehensive;
 mod translate_float;
@@ -21,177 +21,24 @@ fn test_fib() {
         let a: int = 2 - fib(5) * 3
         ",
         expect![[r#"
-            Program {
-                functions: [
-                    Function {
-                        id: "fib",
-                        params: [
-                            "n",
-                        ],
-                        body: Block {
-                            exprs: [
-                                Let {
-                                    id: "@temp0",
-                                    init_expr: BinaryExpr {
-                                        kind: LessThanOrEquals,
-                                        operand_1: Id {
-                                            value: "n",
-                                            id_type: Int,
-                                        },
-                                        operand_2: Int {
-                                            value: 1,
-                                        },
-                                        operand_type: Int,
-                                    },
-                                },
-                                If {
-                                    condition: Id {
-                                        value: "@temp0",
-                                        id_type: Bool,
-                                    },
-                                    then_block: Block {
-                                        exprs: [
-                                            Direct {
-                                                expr: Int {
-                                                    value: 1,
-                                                },
-                                            },
-                                        ],
-                                    },
-                                    else_block: Some(
-                                        Block {
-                                            exprs: [
-                                                Let {
-                                                    id: "@temp1",
-                                                    init_expr: BinaryExpr {
-                                                        kind: Minus,
-                                                        operand_1: Id {
-                                                            value: "n",
-                                                            id_type: Int,
-                                                        },
-                                                        operand_2: Int {
-                                                            value: 1,
-                                                        },
-                                                        operand_type: Int,
-                                                    },
-                                                },
-                                                Let {
-                                                    id: "@temp2",
-                                                    init_expr: BinaryExpr {
-                                                        kind: Minus,
-                                                        operand_1: Id {
-                                                            value: "n",
-                                                            id_type: Int,
-                                                        },
-                                                        operand_2: Int {
-                                                            value: 2,
-                                                        },
-                                                        operand_type: Int,
-                                                    },
-                                                },
-                                                Let {
-                                                    id: "@temp3",
-                                                    init_expr: Call {
-                                                        id: "fib",
-                                                        args: [
-                                                            Id {
-                                                                value: "@temp1",
-                                                                id_type: Int,
-                                                            },
-                                                        ],
-                                                        live_variables: RefCell {
-                                                            value: {},
-                                                        },
-                                                    },
-                                                },
-                                                Let {
-                                                    id: "@temp4",
-                                                    init_expr: Call {
-                                                        id: "fib",
-                                                        args: [
-                                                            Id {
-                                                                value: "@temp2",
-                                                                id_type: Int,
-                                                            },
-                                                        ],
-                                                        live_variables: RefCell {
-                                                            value: {},
-                                                        },
-                                                    },
-                                                },
-                                                BinaryExpr {
-                                                    kind: Plus,
-                                                    operand_1: Id {
-                                                        value: "@temp3",
-                                                        id_type: Int,
-                                                    },
-                                                    operand_2: Id {
-                                                        value: "@temp4",
-                                                        id_type: Int,
-                                                    },
-                                                    operand_type: Int,
-                                                },
-                                            ],
-                                        },
-                                    ),
-                                },
-                            ],
-                        },
-                    },
-                ],
-                body: Block {
-                    exprs: [
-                        Let {
-                            id: "@temp5",
-                            init_expr: Call {
-                                id: "fib",
-                                args: [
-                                    Int {
-                                        value: 5,
-                                    },
-                                ],
-                                live_variables: RefCell {
-                                    value: {},
-                                },
-                            },
-                        },
-                        Let {
-                            id: "@temp6",
-                            init_expr: BinaryExpr {
-                                kind: Times,
-                                operand_1: Id {
-                                    value: "@temp5",
-                                    id_type: Int,
-                                },
-                                operand_2: Int {
-                                    value: 3,
-                                },
-                                operand_type: Int,
-                            },
-                        },
-                        Let {
-                            id: "a",
-                            init_expr: BinaryExpr {
-                                kind: Minus,
-                                operand_1: Int {
-                                    value: 2,
-                                },
-                                operand_2: Id {
-                                    value: "@temp6",
-                                    id_type: Int,
-                                },
-                                operand_type: Int,
-                            },
-                        },
-                        Direct {
-                            expr: Id {
-                                value: "a",
-                                id_type: Int,
-                            },
-                        },
-                    ],
-                },
-            }"#]],
+            fun fib(n: int): int {
+              let %temp0 = le.int n, 1
+              if %temp0 {
+                1
+              } else {
+                let %temp1 = sub.int n, 1
+                let %temp2 = sub.int n, 2
+                let %temp3 = call fib(%temp1: int)
+                let %temp4 = call fib(%temp2: int)
+                add.int %temp3, %temp4
+              }
+            }
+
+            let %temp5 = call fib(5)
+            let %temp6 = mul.int %temp5, 3
+            let a = sub.int 2, %temp6
+            a: int
+        "#]],
     );
 }
 
@@ -206,70 +53,13 @@ fn test_function_1() {
         a(1,2,   3)
         ",
         expect![[r#"
-            Program {
-                functions: [
-                    Function {
-                        id: "a",
-                        params: [
-                            "b",
-                            "c",
-                            "d",
-                        ],
-                        body: Block {
-                            exprs: [
-                                Let {
-                                    id: "@temp0",
-                                    init_expr: BinaryExpr {
-                                        kind: Plus,
-                                        operand_1: Id {
-                                            value: "b",
-                                            id_type: Int,
-                                        },
-                                        operand_2: Id {
-                                            value: "c",
-                                            id_type: Int,
-                                        },
-                                        operand_type: Int,
-                                    },
-                                },
-                                BinaryExpr {
-                                    kind: Plus,
-                                    operand_1: Id {
-                                        value: "@temp0",
-                                        id_type: Int,
-                                    },
-                                    operand_2: Id {
-                                        value: "d",
-                                        id_type: Int,
-                                    },
-                                    operand_type: Int,
-                                },
-                            ],
-                        },
-                    },
-                ],
-                body: Block {
-                    exprs: [
-                        Call {
-                            id: "a",
-                            args: [
-                                Int {
-                                    value: 1,
-                                },
-                                Int {
-                                    value: 2,
-                                },
-                                Int {
-                                    value: 3,
-                                },
-                            ],
-                            live_variables: RefCell {
-                                value: {},
-                            },
-                        },
-                    ],
-                },
-            }"#]],
+            fun a(b: int, c: int, d: int): int {
+              let %temp0 = add.int b, c
+              add.int %temp0, d
+            }
+
+            call a(1, 2, 3)
+        "#]],
     );
 }
 
@@ -288,83 +78,17 @@ fn test_multi_functions() {
         a(1, 2, 3)
         ",
         expect![[r#"
-            Program {
-                functions: [
-                    Function {
-                        id: "a",
-                        params: [
-                            "b",
-                            "c",
-                            "d",
-                        ],
-                        body: Block {
-                            exprs: [
-                                Let {
-                                    id: "@temp0",
-                                    init_expr: BinaryExpr {
-                                        kind: Plus,
-                                        operand_1: Id {
-                                            value: "b",
-                                            id_type: Int,
-                                        },
-                                        operand_2: Id {
-                                            value: "c",
-                                            id_type: Int,
-                                        },
-                                        operand_type: Int,
-                                    },
-                                },
-                                BinaryExpr {
-                                    kind: Plus,
-                                    operand_1: Id {
-                                        value: "@temp0",
-                                        id_type: Int,
-                                    },
-                                    operand_2: Id {
-                                        value: "d",
-                                        id_type: Int,
-                                    },
-                                    operand_type: Int,
-                                },
-                            ],
-                        },
-                    },
-                    Function {
-                        id: "b",
-                        params: [],
-                        body: Block {
-                            exprs: [
-                                Direct {
-                                    expr: Bool {
-                                        value: true,
-                                    },
-                                },
-                            ],
-                        },
-                    },
-                ],
-                body: Block {
-                    exprs: [
-                        Call {
-                            id: "a",
-                            args: [
-                                Int {
-                                    value: 1,
-                                },
-                                Int {
-                                    value: 2,
-                                },
-                                Int {
-                                    value: 3,
-                                },
-                            ],
-                            live_variables: RefCell {
-                                value: {},
-                            },
-                        },
-                    ],
-                },
-            }"#]],
+            fun a(b: int, c: int, d: int): int {
+              let %temp0 = add.int b, c
+              add.int %temp0, d
+            }
+
+            fun b(): bool {
+              true
+            }
+
+            call a(1, 2, 3)
+        "#]],
     );
 }
 
@@ -377,34 +101,11 @@ fn test_unit_return() {
         }
         ",
         expect![[r#"
-            Program {
-                functions: [
-                    Function {
-                        id: "a",
-                        params: [],
-                        body: Block {
-                            exprs: [
-                                Let {
-                                    id: "a",
-                                    init_expr: Direct {
-                                        expr: Bool {
-                                            value: false,
-                                        },
-                                    },
-                                },
-                                Direct {
-                                    expr: Id {
-                                        value: "a",
-                                        id_type: Bool,
-                                    },
-                                },
-                            ],
-                        },
-                    },
-                ],
-                body: Block {
-                    exprs: [],
-                },
-            }"#]],
+            fun a(): unit {
+              let a = false
+              a: bool
+            }
+
+        "#]],
     );
 }
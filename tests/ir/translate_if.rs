@@ -17,76 +17,14 @@ fn test_translate_if_no_else() {
         }
         ",
         expect![[r#"
-            Program {
-                functions: [],
-                body: Block {
-                    exprs: [
-                        Let {
-                            id: "a",
-                            init_expr: Direct {
-                                expr: Int {
-                                    value: 0,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "b",
-                            init_expr: Direct {
-                                expr: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "@temp0",
-                            init_expr: BinaryExpr {
-                                kind: LessThan,
-                                operand_1: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                                operand_2: Id {
-                                    value: "b",
-                                    id_type: Int,
-                                },
-                                operand_type: Int,
-                            },
-                        },
-                        If {
-                            condition: Id {
-                                value: "@temp0",
-                                id_type: Bool,
-                            },
-                            then_block: Block {
-                                exprs: [
-                                    BinaryExpr {
-                                        kind: Plus,
-                                        operand_1: Int {
-                                            value: 1,
-                                        },
-                                        operand_2: Int {
-                                            value: 2,
-                                        },
-                                        operand_type: Int,
-                                    },
-                                    BinaryExpr {
-                                        kind: Plus,
-                                        operand_1: Int {
-                                            value: 2,
-                                        },
-                                        operand_2: Int {
-                                            value: 3,
-                                        },
-                                        operand_type: Int,
-                                    },
-                                ],
-                            },
-                            else_block: None,
-                        },
-                    ],
-                },
-            }"#]],
+            let a = 0
+            let b = a: int
+            let %temp0 = lt.int a, b
+            if %temp0 {
+              add.int 1, 2
+              add.int 2, 3
+            }
+        "#]],
     );
 }
 
@@ -100,55 +38,11 @@ fn test_translate_if_empty() {
           }
         ",
         expect![[r#"
-            Program {
-                functions: [],
-                body: Block {
-                    exprs: [
-                        Let {
-                            id: "a",
-                            init_expr: Direct {
-                                expr: Int {
-                                    value: 0,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "b",
-                            init_expr: Direct {
-                                expr: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "@temp0",
-                            init_expr: BinaryExpr {
-                                kind: LessThan,
-                                operand_1: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                                operand_2: Id {
-                                    value: "b",
-                                    id_type: Int,
-                                },
-                                operand_type: Int,
-                            },
-                        },
-                        If {
-                            condition: Id {
-                                value: "@temp0",
-                                id_type: Bool,
-                            },
-                            then_block: Block {
-                                exprs: [],
-                            },
-                            else_block: None,
-                        },
-                    ],
-                },
-            }"#]],
+            let a = 0
+            let b = a: int
+            let %temp0 = lt.int a, b
+            if %temp0 {}
+        "#]],
     );
 }
 
@@ -167,91 +61,16 @@ fn test_translate_if_else_basic() {
         }
         ",
         expect![[r#"
-            Program {
-                functions: [],
-                body: Block {
-                    exprs: [
-                        Let {
-                            id: "a",
-                            init_expr: Direct {
-                                expr: Int {
-                                    value: 0,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "b",
-                            init_expr: Direct {
-                                expr: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "@temp0",
-                            init_expr: BinaryExpr {
-                                kind: LessThan,
-                                operand_1: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                                operand_2: Id {
-                                    value: "b",
-                                    id_type: Int,
-                                },
-                                operand_type: Int,
-                            },
-                        },
-                        If {
-                            condition: Id {
-                                value: "@temp0",
-                                id_type: Bool,
-                            },
-                            then_block: Block {
-                                exprs: [
-                                    BinaryExpr {
-                                        kind: Plus,
-                                        operand_1: Int {
-                                            value: 1,
-                                        },
-                                        operand_2: Int {
-                                            value: 2,
-                                        },
-                                        operand_type: Int,
-                                    },
-                                    BinaryExpr {
-                                        kind: Plus,
-                                        operand_1: Int {
-                                            value: 2,
-                                        },
-                                        operand_2: Int {
-                                            value: 3,
-                                        },
-                                        operand_type: Int,
-                                    },
-                                ],
-                            },
-                            else_block: Some(
-                                Block {
-                                    exprs: [
-                                        BinaryExpr {
-                                            kind: Plus,
-                                            operand_1: Int {
-                                                value: 4,
-                                            },
-                                            operand_2: Int {
-                                                value: 5,
-                                            },
-                                            operand_type: Int,
-                                        },
-                                    ],
-                                },
-                            ),
-                        },
-                    ],
-                },
-            }"#]],
+            let a = 0
+            let b = a: int
+            let %temp0 = lt.int a, b
+            if %temp0 {
+              add.int 1, 2
+              add.int 2, 3
+            } else {
+              add.int 4, 5
+            }
+        "#]],
     );
 }
 
@@ -282,218 +101,34 @@ fn test_translate_if_else_chain() {
         }
         ",
         expect![[r#"
-            Program {
-                functions: [],
-                body: Block {
-                    exprs: [
-                        Let {
-                            id: "a",
-                            init_expr: Direct {
-                                expr: Int {
-                                    value: 0,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "b",
-                            init_expr: Direct {
-                                expr: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "d",
-                            init_expr: Direct {
-                                expr: Bool {
-                                    value: false,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "c",
-                            init_expr: Direct {
-                                expr: Id {
-                                    value: "d",
-                                    id_type: Bool,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "@temp0",
-                            init_expr: BinaryExpr {
-                                kind: LessThan,
-                                operand_1: Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                                operand_2: Id {
-                                    value: "b",
-                                    id_type: Int,
-                                },
-                                operand_type: Int,
-                            },
-                        },
-                        Let {
-                            id: "e",
-                            init_expr: If {
-                                condition: Id {
-                                    value: "@temp0",
-                                    id_type: Bool,
-                                },
-                                then_block: Block {
-                                    exprs: [
-                                        BinaryExpr {
-                                            kind: Plus,
-                                            operand_1: Int {
-                                                value: 1,
-                                            },
-                                            operand_2: Int {
-                                                value: 2,
-                                            },
-                                            operand_type: Int,
-                                        },
-                                        BinaryExpr {
-                                            kind: Plus,
-                                            operand_1: Int {
-                                                value: 2,
-                                            },
-                                            operand_2: Int {
-                                                value: 3,
-                                            },
-                                            operand_type: Int,
-                                        },
-                                    ],
-                                },
-                                else_block: Some(
-                                    Block {
-                                        exprs: [
-                                            Let {
-                                                id: "@temp1",
-                                                init_expr: Direct {
-                                                    expr: Id {
-                                                        value: "c",
-                                                        id_type: Bool,
-                                                    },
-                                                },
-                                            },
-                                            If {
-                                                condition: Id {
-                                                    value: "@temp1",
-                                                    id_type: Bool,
-                                                },
-                                                then_block: Block {
-                                                    exprs: [
-                                                        BinaryExpr {
-                                                            kind: Plus,
-                                                            operand_1: Int {
-                                                                value: 2,
-                                                            },
-                                                            operand_2: Int {
-                                                                value: 3,
-                                                            },
-                                                            operand_type: Int,
-                                                        },
-                                                    ],
-                                                },
-                                                else_block: Some(
-                                                    Block {
-                                                        exprs: [
-                                                            Let {
-                                                                id: "@temp2",
-                                                                init_expr: Direct {
-                                                                    expr: Id {
-                                                                        value: "d",
-                                                                        id_type: Bool,
-                                                                    },
-                                                                },
-                                                            },
-                                                            If {
-                                                                condition: Id {
-                                                                    value: "@temp2",
-                                                                    id_type: Bool,
-                                                                },
-                                                                then_block: Block {
-                                                                    exprs: [
-                                                                        BinaryExpr {
-                                                                            kind: Plus,
-                                                                            operand_1: Int {
-                                                                                value: 1,
-                                                                            },
-                                                                            operand_2: Int {
-                                                                                value: 2,
-                                                                            },
-                                                                            operand_type: Int,
-                                                                        },
-                                                                    ],
-                                                                },
-                                                                else_block: Some(
-                                                                    Block {
-                                                                        exprs: [
-                                                                            Let {
-                                                                                id: "@temp3",
-                                                                                init_expr: Direct {
-                                                                                    expr: Id {
-                                                                                        value: "c",
-                                                                                        id_type: Bool,
-                                                                                    },
-                                                                                },
-                                                                            },
-                                                                            If {
-                                                                                condition: Id {
-                                                                                    value: "@temp3",
-                                                                                    id_type: Bool,
-                                                                                },
-                                                                                then_block: Block {
-                                                                                    exprs: [
-                                                                                        Direct {
-                                                                                            expr: Id {
-                                                                                                value: "a",
-                                                                                                id_type: Int,
-                                                                                            },
-                                                                                        },
-                                                                                    ],
-                                                                                },
-                                                                                else_block: Some(
-                                                                                    Block {
-                                                                                        exprs: [
-                                                                                            BinaryExpr {
-                                                                                                kind: Plus,
-                                                                                                operand_1: Int {
-                                                                                                    value: 2,
-                                                                                                },
-                                                                                                operand_2: Int {
-                                                                                                    value: 1,
-                                                                                                },
-                                                                                                operand_type: Int,
-                                                                                            },
-                                                                                        ],
-                                                                                    },
-                                                                                ),
-                                                                            },
-                                                                        ],
-                                                                    },
-                                                                ),
-                                                            },
-                                                        ],
-                                                    },
-                                                ),
-                                            },
-                                        ],
-                                    },
-                                ),
-                            },
-                        },
-                        Direct {
-                            expr: Id {
-                                value: "e",
-                                id_type: Int,
-                            },
-                        },
-                    ],
-                },
-            }"#]],
+            let a = 0
+            let b = a: int
+            let d = false
+            let c = d: bool
+            let %temp0 = lt.int a, b
+            let e = if %temp0 {
+              add.int 1, 2
+              add.int 2, 3
+            } else {
+              let %temp1 = c: bool
+              if %temp1 {
+                add.int 2, 3
+              } else {
+                let %temp2 = d: bool
+                if %temp2 {
+                  add.int 1, 2
+                } else {
+                  let %temp3 = c: bool
+                  if %temp3 {
+                    a: int
+                  } else {
+                    add.int 2, 1
+                  }
+                }
+              }
+            }
+            e: int
+        "#]],
     );
 }
 
@@ -517,243 +152,36 @@ fn test_translate_nested() {
         }
         ",
         expect![[r#"
-            Program {
-                functions: [],
-                body: Block {
-                    exprs: [
-                        Let {
-                            id: "a",
-                            init_expr: Direct {
-                                expr: Int {
-                                    value: 0,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "b",
-                            init_expr: Direct {
-                                expr: Int {
-                                    value: 0,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "c",
-                            init_expr: Direct {
-                                expr: Int {
-                                    value: 0,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "@temp0",
-                            init_expr: Direct {
-                                expr: Bool {
-                                    value: true,
-                                },
-                            },
-                        },
-                        Let {
-                            id: "@temp3",
-                            init_expr: If {
-                                condition: Id {
-                                    value: "@temp0",
-                                    id_type: Bool,
-                                },
-                                then_block: Block {
-                                    exprs: [
-                                        BinaryExpr {
-                                            kind: LessThanOrEquals,
-                                            operand_1: Id {
-                                                value: "c",
-                                                id_type: Int,
-                                            },
-                                            operand_2: Int {
-                                                value: 0,
-                                            },
-                                            operand_type: Int,
-                                        },
-                                    ],
-                                },
-                                else_block: Some(
-                                    Block {
-                                        exprs: [
-                                            Let {
-                                                id: "@temp1",
-                                                init_expr: BinaryExpr {
-                                                    kind: Plus,
-                                                    operand_1: Int {
-                                                        value: 1,
-                                                    },
-                                                    operand_2: Int {
-                                                        value: 2,
-                                                    },
-                                                    operand_type: Int,
-                                                },
-                                            },
-                                            Let {
-                                                id: "@temp2",
-                                                init_expr: BinaryExpr {
-                                                    kind: Plus,
-                                                    operand_1: Id {
-                                                        value: "@temp1",
-                                                        id_type: Int,
-                                                    },
-                                                    operand_2: Int {
-                                                        value: 3,
-                                                    },
-                                                    operand_type: Int,
-                                                },
-                                            },
-                                            BinaryExpr {
-                                                kind: LessThan,
-                                                operand_1: Id {
-                                                    value: "@temp2",
-                                                    id_type: Int,
-                                                },
-                                                operand_2: Int {
-                                                    value: 6,
-                                                },
-                                                operand_type: Int,
-                                            },
-                                        ],
-                                    },
-                                ),
-                            },
-                        },
-                        Let {
-                            id: "d",
-                            init_expr: If {
-                                condition: Id {
-                                    value: "@temp3",
-                                    id_type: Bool,
-                                },
-                                then_block: Block {
-                                    exprs: [
-                                        BinaryExpr {
-                                            kind: Plus,
-                                            operand_1: Id {
-                                                value: "a",
-                                                id_type: Int,
-                                            },
-                                            operand_2: Id {
-                                                value: "b",
-                                                id_type: Int,
-                                            },
-                                            operand_type: Int,
-                                        },
-                                    ],
-                                },
-                                else_block: Some(
-                                    Block {
-                                        exprs: [
-                                            Let {
-                                                id: "@temp4",
-                                                init_expr: Direct {
-                                                    expr: Bool {
-                                                        value: false,
-                                                    },
-                                                },
-                                            },
-                                            Let {
-                                                id: "@temp5",
-                                                init_expr: If {
-                                                    condition: Id {
-                                                        value: "@temp4",
-                                                        id_type: Bool,
-                                                    },
-                                                    then_block: Block {
-                                                        exprs: [
-                                                            Direct {
-                                                                expr: Bool {
-                                                                    value: false,
-                                                                },
-                                                            },
-                                                        ],
-                                                    },
-                                                    else_block: Some(
-                                                        Block {
-                                                            exprs: [
-                                                                Direct {
-                                                                    expr: Bool {
-                                                                        value: true,
-                                                                    },
-                                                                },
-                                                            ],
-                                                        },
-                                                    ),
-                                                },
-                                            },
-                                            If {
-                                                condition: Id {
-                                                    value: "@temp5",
-                                                    id_type: Bool,
-                                                },
-                                                then_block: Block {
-                                                    exprs: [
-                                                        Direct {
-                                                            expr: Int {
-                                                                value: 2,
-                                                            },
-                                                        },
-                                                    ],
-                                                },
-                                                else_block: Some(
-                                                    Block {
-                                                        exprs: [
-                                                            Let {
-                                                                id: "@temp6",
-                                                                init_expr: BinaryExpr {
-                                                                    kind: Plus,
-                                                                    operand_1: Id {
-                                                                        value: "b",
-                                                                        id_type: Int,
-                                                                    },
-                                                                    operand_2: Id {
-                                                                        value: "c",
-                                                                        id_type: Int,
-                                                                    },
-                                                                    operand_type: Int,
-                                                                },
-                                                            },
-                                                            Let {
-                                                                id: "e",
-                                                                init_expr: BinaryExpr {
-                                                                    kind: Plus,
-                                                                    operand_1: Id {
-                                                                        value: "@temp6",
-                                                                        id_type: Int,
-                                                                    },
-                                                                    operand_2: Int {
-                                                                        value: 2,
-                                                                    },
-                                                                    operand_type: Int,
-                                                                },
-                                                            },
-                                                            Direct {
-                                                                expr: Id {
-                                                                    value: "e",
-                                                                    id_type: Int,
-                                                                },
-                                                            },
-                                                        ],
-                                                    },
-                                                ),
-                                            },
-                                        ],
-                                    },
-                                ),
-                            },
-                        },
-                        Direct {
-                            expr: Id {
-                                value: "d",
-                                id_type: Int,
-                            },
-                        },
-                    ],
-                },
-            }"#]],
+            let a = 0
+            let b = 0
+            let c = 0
+            let %temp0 = true
+            let %temp3 = if %temp0 {
+              le.int c, 0
+            } else {
+              let %temp1 = add.int 1, 2
+              let %temp2 = add.int %temp1, 3
+              lt.int %temp2, 6
+            }
+            let d = if %temp3 {
+              add.int a, b
+            } else {
+              let %temp4 = false
+              let %temp5 = if %temp4 {
+                false
+              } else {
+                true
+              }
+              if %temp5 {
+                2
+              } else {
+                let %temp6 = add.int b, c
+                let e = add.int %temp6, 2
+                e: int
+              }
+            }
+            d: int
+        "#]],
     );
 }
 
@@ -0,0 +1,73 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for running instructions with the JIT, where the sections are relocated in memory.
+
+use expect_test::expect;
+use solis::asm::asm::{Instruction::*, Operand::*, Register::*};
+use test_utils::{compile_emulate_check, jit_check};
+
+#[test]
+fn test_entry() {
+    jit_check(
+        &[
+            Global("entry".to_string()),
+            Section("text".to_string()),
+            Label("entry".to_string()),
+            Mov(Reg(Rax), Imm(-5)),
+            Ret,
+        ],
+        expect![[r#"-5"#]],
+    );
+}
+
+#[test]
+fn test_sections() {
+    // The address in the data section is absolute, and the constant in the rodata section is relative to rip.
+    jit_check(
+        &[
+            Global("entry".to_string()),
+            Section("text".to_string()),
+            Label("entry".to_string()),
+            Mov(Reg(Rax), MemLabel("table".to_string())),
+            LeaLabel(Reg(Rcx), "entry".to_string()),
+            Sub(Reg(Rax), Reg(Rcx)),
+            Add(Reg(Rax), MemLabel("constant".to_string())),
+            Ret,
+            Section("data".to_string()),
+            Label("table".to_string()),
+            DqLabel("entry".to_string()),
+            Section("rodata".to_string()),
+            Align(8),
+            Label("constant".to_string()),
+            DqInt(42),
+        ],
+        expect![[r#"42"#]],
+    );
+}
+
+#[test]
+fn test_extern() {
+    // `labs` is resolved to the function of the C library in the process.
+    jit_check(
+        &[
+            Global("entry".to_string()),
+            Extern("labs".to_string()),
+            Section("text".to_string()),
+            Label("entry".to_string()),
+            Push(Reg(Rbp)),
+            Mov(Reg(Rbp), Reg(Rsp)),
+            Mov(Reg(Rdi), Imm(-7)),
+            Call("labs".to_string()),
+            Pop(Reg(Rbp)),
+            Ret,
+        ],
+        expect![[r#"7"#]],
+    );
+}
+
+#[test]
+fn test_programs() {
+    compile_emulate_check("let a: int = 3 * 4; if a < 20 { a - 30 } else { a }");
+    compile_emulate_check("let a: float = 1.5 * -2.0; a / 0.5");
+    compile_emulate_check("let a: bool = 1 < 2; let b: bool = !a; a == b");
+}
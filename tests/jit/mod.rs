@@ -0,0 +1,5 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Unit tests for the JIT.
+
+mod jit_basic;
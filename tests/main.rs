@@ -1,13 +1,22 @@
 // Copyright © 2022-2023 Brandon Li. All rights reserved.
 
 extern crate assert_cmd;
+extern crate exitcode;
 extern crate expect_test;
 extern crate solis;
 
 mod asm;
+mod c_backend;
+mod compiler;
+mod emitter;
+mod fuzzer;
 mod integration;
+mod interpreter;
 mod ir;
+mod jit;
+mod optimizer;
 mod parser;
 mod register_allocation;
 mod test_utils;
 mod tokenizer;
+mod warnings;
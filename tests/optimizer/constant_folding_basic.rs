@@ -0,0 +1,155 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Basic tests for constant folding and propagation.
+
+use expect_test::expect;
+use test_utils::{constant_folding_check, constant_folding_ir_check};
+
+#[test]
+fn test_arithmetic() {
+    constant_folding_check(
+        "
+        let x: int = 4 + 3
+        let a: int = 2 * 3 + x
+        let b: int = -a % 5 - (a / 2)
+        b * b
+        ",
+        expect![[r#"
+            let x = 7
+            let %temp0 = 6
+            let a = 13
+            let %temp1 = -13
+            let %temp2 = -3
+            let %temp3 = 6
+            let b = -9
+            81
+        "#]],
+    );
+}
+
+#[test]
+fn test_partial() {
+    // Only the constant sub expressions are folded, and `c` is propagated into its uses.
+    constant_folding_ir_check(
+        "
+        fun f(x: int): bool {
+            let c = mul.int 2, 3
+            let a = add.int c, x
+            let b = lt.int a, c
+            not.bool b
+        }
+        0
+        ",
+        expect![[r#"
+            fun f(x: int): bool {
+              let c = 6
+              let a = add.int 6, x
+              let b = lt.int a, 6
+              not.bool b
+            }
+
+            0
+        "#]],
+    );
+}
+
+#[test]
+fn test_bool() {
+    constant_folding_check(
+        "
+        let a: bool = !(1 < 2) == false
+        let b: bool = a != (3 >= 4)
+        b
+        ",
+        expect![[r#"
+            let %temp0 = true
+            let %temp1 = false
+            let a = true
+            let %temp2 = false
+            let b = true
+            true
+        "#]],
+    );
+}
+
+#[test]
+fn test_float() {
+    // Floats are folded, but are not substituted as operands (since floats can't be immediates).
+    constant_folding_check(
+        "
+        let a: float = 1.5 * 2 + 1 / 4.0
+        let b: bool = a > 2.0
+        let c: int = 2;
+        -a * c
+        ",
+        expect![[r#"
+            let %temp0 = 2.0
+            let %temp1 = 1.0
+            let %temp2 = 3.0
+            let %temp3 = 0.25
+            let a = 3.25
+            let b = true
+            let c = 2
+            let %temp4 = -3.25
+            let %temp5 = 2.0
+            -6.5
+        "#]],
+    );
+}
+
+#[test]
+fn test_large_int() {
+    // Ints that don't fit in 32 bits are folded, but are not substituted as operands.
+    constant_folding_check(
+        "
+        let a: int = 4000000000 + 1
+        let b: int = a * 2
+        let c: int = 2147483647 + 1
+        b - c
+        ",
+        expect![[r#"
+            let %temp0 = 4000000000
+            let a = 4000000001
+            let b = 8000000002
+            let c = 2147483648
+            5852516354
+        "#]],
+    );
+}
+
+#[test]
+fn test_division_by_zero() {
+    // Dividing by zero is a runtime error, so the division is not folded.
+    constant_folding_ir_check(
+        "
+        let a = 0
+        let b = div.int 1, a
+        let c = mod.int 5, a
+        let d = div.int 5, 2
+        d: int
+        ",
+        expect![[r#"
+            let a = 0
+            let b = div.int 1, 0
+            let c = mod.int 5, 0
+            let d = 2
+            2
+        "#]],
+    );
+}
+
+#[test]
+fn test_division_overflow() {
+    constant_folding_ir_check(
+        "
+        let %temp0 = -9223372036854775808
+        let a = div.int %temp0, -1
+        a: int
+        ",
+        expect![[r#"
+            let %temp0 = -9223372036854775808
+            let a = div.int %temp0, -1
+            a: int
+        "#]],
+    );
+}
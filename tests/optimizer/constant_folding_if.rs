@@ -0,0 +1,169 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for pruning `if` expressions whose condition is a constant in constant folding.
+
+use expect_test::expect;
+use test_utils::{constant_folding_check, constant_folding_ir_check};
+
+#[test]
+fn test_if_result() {
+    constant_folding_check(
+        "
+        let a: int = 3
+        if a < 4 { let b: int = a + 1; b * 2 } else { 2 }
+        ",
+        expect![[r#"
+            let a = 3
+            let %temp0 = true
+            let %temp1 = 4
+            8
+        "#]],
+    );
+}
+
+#[test]
+fn test_if_let() {
+    constant_folding_check(
+        "
+        let a: int = 3
+        let b: int = if a > 4 { 1 } else { let c: int = a * 2; c + 1 }
+        let d: int = if a == 3 { if a != 3 { 5 } else { b } } else { 0 }
+        d
+        ",
+        expect![[r#"
+            let a = 3
+            let %temp0 = false
+            let %temp3 = 6
+            let b = 7
+            let %temp1 = true
+            let %temp4 = false
+            let d = 7
+            7
+        "#]],
+    );
+}
+
+#[test]
+fn test_if_not_constant() {
+    // The branches are folded in their own scope.
+    constant_folding_ir_check(
+        "
+        fun f(x: int): int {
+            let a = 2
+            let b = lt.int x, a
+            let c = if b {
+                let d = add.int a, 1
+                d: int
+            } else {
+                let d = sub.int x, a
+                d: int
+            }
+            add.int c, a
+        }
+        0
+        ",
+        expect![[r#"
+            fun f(x: int): int {
+              let a = 2
+              let b = lt.int x, 2
+              let c = if b {
+                let d = 3
+                3
+              } else {
+                let d = sub.int x, 2
+                d: int
+              }
+              add.int c, 2
+            }
+
+            0
+        "#]],
+    );
+}
+
+#[test]
+fn test_if_sibling_branches() {
+    // The variables of the branches that are taken are renamed, so that the same variable isn't bound twice.
+    constant_folding_ir_check(
+        "
+        fun f(n: int): int {
+            let t = true
+            let x = if t {
+                let a = add.int n, 1
+                mul.int a, 2
+            } else {
+                0
+            }
+            let y = if t {
+                let a = mul.int n, 3
+                add.int a, 5
+            } else {
+                0
+            }
+            add.int x, y
+        }
+        0
+        ",
+        expect![[r#"
+            fun f(n: int): int {
+              let t = true
+              let %temp0 = add.int n, 1
+              let x = mul.int %temp0, 2
+              let %temp1 = mul.int n, 3
+              let y = add.int %temp1, 5
+              add.int x, y
+            }
+
+            0
+        "#]],
+    );
+}
+
+#[test]
+fn test_if_statement() {
+    // The result of an `if` that is not the result of the block is discarded.
+    constant_folding_ir_check(
+        "
+        let a = true
+        if a {
+            let b = 1
+            add.int b, 2
+        }
+        let c = not.bool a
+        if c {
+            3
+        }
+        if a {
+            4
+        } else {
+            5
+        }
+        ",
+        expect![[r#"
+            let a = true
+            let %temp0 = 1
+            3
+            let c = false
+            4
+        "#]],
+    );
+}
+
+#[test]
+fn test_if_unit_result() {
+    // The branch that is taken is missing, so the result of the block (unit) is kept.
+    constant_folding_ir_check(
+        "
+        let a = 1
+        let b = gt.int a, 2
+        if b {
+            3
+        }
+        ",
+        expect![[r#"
+            let a = 1
+            let b = false
+            if b {}
+        "#]],
+    );
+}
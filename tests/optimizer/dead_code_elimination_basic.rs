@@ -0,0 +1,171 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Basic tests for dead code elimination.
+
+use expect_test::expect;
+use test_utils::dead_code_elimination_ir_check;
+
+#[test]
+fn test_unused_let() {
+    dead_code_elimination_ir_check(
+        "
+        let a = 1
+        let b = add.int a, 2
+        let c = 3.5
+        b: int
+        ",
+        expect![[r#"
+            removed 1
+            let a = 1
+            let b = add.int a, 2
+            b: int
+        "#]],
+    );
+}
+
+#[test]
+fn test_chain() {
+    // Removing `d` makes `c`, `b` and then `a` unused.
+    dead_code_elimination_ir_check(
+        "
+        let x = 1
+        let a = add.int x, 2
+        let b = mul.int a, a
+        let c = lt.int b, 5
+        let d = not.bool c
+        x: int
+        ",
+        expect![[r#"
+            removed 4
+            let x = 1
+            x: int
+        "#]],
+    );
+}
+
+#[test]
+fn test_discarded() {
+    // Pure expressions whose result is discarded are removed, but the result of the block is kept.
+    dead_code_elimination_ir_check(
+        "
+        let a = 1
+        add.int a, 2
+        if true {
+            let b = 2
+            sub.int a, b
+        }
+        3
+        ",
+        expect![[r#"
+            removed 3
+            3
+        "#]],
+    );
+}
+
+#[test]
+fn test_call() {
+    // Calls may have side effects, so they are kept even if their result is unused.
+    dead_code_elimination_ir_check(
+        "
+        fun f(x: int): int {
+            x: int
+        }
+        let a = 1
+        let b = call f(a: int) live()
+        call f(2) live()
+        let c = if true {
+            call f(3) live()
+        } else {
+            4
+        }
+        5
+        ",
+        expect![[r#"
+            removed 0
+            fun f(x: int): int {
+              x: int
+            }
+
+            let a = 1
+            let b = call f(a: int)
+            call f(2)
+            let c = if true {
+              call f(3)
+            } else {
+              4
+            }
+            5
+        "#]],
+    );
+}
+
+#[test]
+fn test_division() {
+    // Integer divisions that may divide by zero are kept, since they can crash at runtime.
+    dead_code_elimination_ir_check(
+        "
+        fun f(x: int, y: int): int {
+            let a = div.int x, y
+            let b = mod.int x, 0
+            let c = div.int x, -1
+            let d = div.int x, 2
+            let e = mod.int x, 3
+            let g = 0.0
+            let h = div.float g, g
+            0
+        }
+        0
+        ",
+        expect![[r#"
+            removed 4
+            fun f(x: int, y: int): int {
+              let a = div.int x, y
+              let b = mod.int x, 0
+              let c = div.int x, -1
+              0
+            }
+
+            0
+        "#]],
+    );
+}
+
+#[test]
+fn test_branches() {
+    // Dead code is removed in the branches, and variables referenced in branches are kept.
+    dead_code_elimination_ir_check(
+        "
+        fun f(x: int): int {
+            let a = add.int x, 1
+            let b = mul.int x, 2
+            let c = lt.int x, 3
+            let d = if c {
+                let e = add.int a, 1
+                a: int
+            } else {
+                let g = neg.int x
+                g: int
+            }
+            d: int
+        }
+        0
+        ",
+        expect![[r#"
+            removed 2
+            fun f(x: int): int {
+              let a = add.int x, 1
+              let c = lt.int x, 3
+              let d = if c {
+                a: int
+              } else {
+                let g = neg.int x
+                g: int
+              }
+              d: int
+            }
+
+            0
+        "#]],
+    );
+}
@@ -0,0 +1,307 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Basic tests for function inlining.
+
+use expect_test::expect;
+use test_utils::{inliner_check, inliner_ir_check};
+
+#[test]
+fn test_inline() {
+    inliner_check(
+        "
+        fun sq(x: float) : float { x * x }
+        let a: float = sq(2.5)
+        sq(a) + 1.0
+        ",
+        expect![[r#"
+            inlined 2
+            fun sq(x: float): float {
+              mul.float x, x
+            }
+
+            let %temp1 = 2.5
+            let a = mul.float %temp1, %temp1
+            let %temp2 = a: float
+            let %temp0 = mul.float %temp2, %temp2
+            add.float %temp0, 1.0
+        "#]],
+    );
+}
+
+#[test]
+fn test_nested_calls() {
+    // The body of an inlined function is inlined as well, and each call gets its own variables.
+    inliner_check(
+        "
+        fun add(x: int, y: int) : int { x + y }
+        fun add3(x: int, y: int, z: int) : int { add(add(x, y), z) }
+        add3(1, 2, 3) * add(4, 5)
+        ",
+        expect![[r#"
+            inlined 6
+            fun add(x: int, y: int): int {
+              add.int x, y
+            }
+
+            fun add3(x: int, y: int, z: int): int {
+              let %temp3 = x: int
+              let %temp4 = y: int
+              let %temp0 = add.int %temp3, %temp4
+              let %temp5 = %temp0: int
+              let %temp6 = z: int
+              add.int %temp5, %temp6
+            }
+
+            let %temp7 = 1
+            let %temp8 = 2
+            let %temp9 = 3
+            let %temp11 = %temp7: int
+            let %temp12 = %temp8: int
+            let %temp10 = add.int %temp11, %temp12
+            let %temp13 = %temp10: int
+            let %temp14 = %temp9: int
+            let %temp1 = add.int %temp13, %temp14
+            let %temp15 = 4
+            let %temp16 = 5
+            let %temp2 = add.int %temp15, %temp16
+            mul.int %temp1, %temp2
+        "#]],
+    );
+}
+
+#[test]
+fn test_recursive() {
+    // Recursive functions are never inlined, even with `@inline`.
+    inliner_check(
+        "
+        @inline fun fib(n: int) : int {
+          if n <= 1 { 1 } else { fib(n - 1) + fib(n - 2) }
+        }
+        @inline fun even(n: int) : bool { if n == 0 { true } else { odd(n - 1) } }
+        fun odd(n: int) : bool { if n == 0 { false } else { even(n - 1) } }
+        fun f(n: int) : int { fib(n) }
+        if even(4) { f(5) } else { 0 }
+        ",
+        expect![[r#"
+            inlined 1
+            @inline fun fib(n: int): int {
+              let %temp0 = le.int n, 1
+              if %temp0 {
+                1
+              } else {
+                let %temp1 = sub.int n, 1
+                let %temp2 = sub.int n, 2
+                let %temp3 = call fib(%temp1: int)
+                let %temp4 = call fib(%temp2: int)
+                add.int %temp3, %temp4
+              }
+            }
+
+            @inline fun even(n: int): bool {
+              let %temp5 = eq.int n, 0
+              if %temp5 {
+                true
+              } else {
+                let %temp6 = sub.int n, 1
+                call odd(%temp6: int)
+              }
+            }
+
+            fun odd(n: int): bool {
+              let %temp7 = eq.int n, 0
+              if %temp7 {
+                false
+              } else {
+                let %temp8 = sub.int n, 1
+                call even(%temp8: int)
+              }
+            }
+
+            fun f(n: int): int {
+              call fib(n: int)
+            }
+
+            let %temp9 = call even(4)
+            if %temp9 {
+              let %temp10 = 5
+              call fib(%temp10: int)
+            } else {
+              0
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn test_annotations() {
+    // `big` is above the threshold, but is annotated with `@inline`.
+    inliner_check(
+        "
+        @noinline fun small(x: int) : int { x + 1 }
+        @inline fun big(x: int) : int {
+          let a: int = x + 1
+          let b: int = a * 2
+          let c: int = b - 3
+          let d: int = c * c
+          let e: int = d + a
+          let f: int = e % 7
+          let g: int = f * b
+          let h: int = g - c
+          h + 1
+        }
+        small(1) + big(2)
+        ",
+        expect![[r#"
+            inlined 1
+            @noinline fun small(x: int): int {
+              add.int x, 1
+            }
+
+            @inline fun big(x: int): int {
+              let a = add.int x, 1
+              let b = mul.int a, 2
+              let c = sub.int b, 3
+              let d = mul.int c, c
+              let e = add.int d, a
+              let f = mod.int e, 7
+              let g = mul.int f, b
+              let h = sub.int g, c
+              add.int h, 1
+            }
+
+            let %temp0 = call small(1)
+            let %temp2 = 2
+            let %temp3 = add.int %temp2, 1
+            let %temp4 = mul.int %temp3, 2
+            let %temp5 = sub.int %temp4, 3
+            let %temp6 = mul.int %temp5, %temp5
+            let %temp7 = add.int %temp6, %temp3
+            let %temp8 = mod.int %temp7, 7
+            let %temp9 = mul.int %temp8, %temp4
+            let %temp10 = sub.int %temp9, %temp5
+            let %temp1 = add.int %temp10, 1
+            add.int %temp0, %temp1
+        "#]],
+    );
+}
+
+#[test]
+fn test_threshold() {
+    // `big` has more than `INLINE_THRESHOLD` expressions, so it is not inlined.
+    inliner_check(
+        "
+        fun big(x: int) : int {
+          let a: int = x + 1
+          let b: int = a * 2
+          let c: int = b - 3
+          let d: int = c * c
+          let e: int = d + a
+          let f: int = e % 7
+          let g: int = f * b
+          let h: int = g - c
+          h + 1
+        }
+        big(2)
+        ",
+        expect![[r#"
+            inlined 0
+            fun big(x: int): int {
+              let a = add.int x, 1
+              let b = mul.int a, 2
+              let c = sub.int b, 3
+              let d = mul.int c, c
+              let e = add.int d, a
+              let f = mod.int e, 7
+              let g = mul.int f, b
+              let h = sub.int g, c
+              add.int h, 1
+            }
+
+            call big(2)
+        "#]],
+    );
+}
+
+#[test]
+fn test_branches_and_statements() {
+    // Calls in branches are inlined, and calls whose result is discarded are inlined without a binding.
+    inliner_ir_check(
+        "
+        fun f(x: int): int {
+            let a = lt.int x, 0
+            if a {
+                neg.int x
+            } else {
+                x: int
+            }
+        }
+        let b = 3
+        call f(b: int)
+        let c = if true {
+            call f(-2)
+        } else {
+            0
+        }
+        call f(c: int)
+        ",
+        expect![[r#"
+            inlined 3
+            fun f(x: int): int {
+              let a = lt.int x, 0
+              if a {
+                neg.int x
+              } else {
+                x: int
+              }
+            }
+
+            let b = 3
+            let %temp0 = b: int
+            let %temp1 = lt.int %temp0, 0
+            if %temp1 {
+              neg.int %temp0
+            } else {
+              %temp0: int
+            }
+            let c = if true {
+              let %temp2 = -2
+              let %temp3 = lt.int %temp2, 0
+              if %temp3 {
+                neg.int %temp2
+              } else {
+                %temp2: int
+              }
+            } else {
+              0
+            }
+            let %temp4 = c: int
+            let %temp5 = lt.int %temp4, 0
+            if %temp5 {
+              neg.int %temp4
+            } else {
+              %temp4: int
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn test_unit_result() {
+    // Functions that evaluate to a `let` binding are not inlined.
+    inliner_ir_check(
+        "
+        fun f(x: int): unit {
+            let a = add.int x, 1
+        }
+        call f(1)
+        ",
+        expect![[r#"
+            inlined 0
+            fun f(x: int): unit {
+              let a = add.int x, 1
+            }
+
+            call f(1)
+        "#]],
+    );
+}
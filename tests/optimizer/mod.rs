@@ -0,0 +1,8 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the optimization passes of the IR.
+
+mod constant_folding_basic;
+mod constant_folding_if;
+mod dead_code_elimination_basic;
+mod inliner_basic;
@@ -20,6 +20,7 @@ fn test_ambiguity_unary() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 15..16,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -60,6 +61,7 @@ fn test_ambiguity_unary() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 15..16,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: Int {
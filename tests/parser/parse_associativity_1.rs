@@ -250,6 +250,7 @@ fn test_arithmetic_left_associative_2() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 4..5,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -157,6 +157,7 @@ fn test_comparison_left_associative_2() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 4..5,
                                 type_reference: Bool,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -34,6 +34,7 @@ fn test_basic() {
                         Expr {
                             kind: Let {
                                 id: "varName",
+                                id_position: 4..11,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: Int {
@@ -47,6 +48,7 @@ fn test_basic() {
                         Expr {
                             kind: Let {
                                 id: "varName2",
+                                id_position: 35..43,
                                 type_reference: Bool,
                                 init_expr: Expr {
                                     kind: Bool {
@@ -60,6 +62,7 @@ fn test_basic() {
                         Expr {
                             kind: Let {
                                 id: "varName3",
+                                id_position: 70..78,
                                 type_reference: Float,
                                 init_expr: Expr {
                                     kind: Float {
@@ -24,6 +24,7 @@ fn test_multiple_expressions() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 15..16,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: Int {
@@ -37,6 +38,7 @@ fn test_multiple_expressions() {
                         Expr {
                             kind: Let {
                                 id: "b",
+                                id_position: 41..42,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: UnaryExpr {
@@ -133,6 +135,7 @@ fn test_multiple_expressions() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 15..16,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: Int {
@@ -146,6 +149,7 @@ fn test_multiple_expressions() {
                         Expr {
                             kind: Let {
                                 id: "b",
+                                id_position: 31..32,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: UnaryExpr {
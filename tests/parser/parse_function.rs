@@ -29,6 +29,7 @@ fn test_fib() {
                             Param {
                                 id: "n",
                                 type_reference: Int,
+                                position: 17..18,
                             },
                         ],
                         return_type: Int,
@@ -133,6 +134,7 @@ fn test_fib() {
                                 },
                             ],
                         },
+                        inline: Auto,
                         position: 13..16,
                     },
                 ],
@@ -141,6 +143,7 @@ fn test_fib() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 169..170,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -208,14 +211,17 @@ fn test_function() {
                             Param {
                                 id: "b",
                                 type_reference: Int,
+                                position: 15..16,
                             },
                             Param {
                                 id: "c",
                                 type_reference: Int,
+                                position: 23..24,
                             },
                             Param {
                                 id: "d",
                                 type_reference: Int,
+                                position: 30..31,
                             },
                         ],
                         return_type: Int,
@@ -253,6 +259,7 @@ fn test_function() {
                                 },
                             ],
                         },
+                        inline: Auto,
                         position: 13..14,
                     },
                 ],
@@ -321,6 +328,7 @@ fn test_multi_functions() {
                                 },
                             ],
                         },
+                        inline: Auto,
                         position: 13..14,
                     },
                     Function {
@@ -332,6 +340,7 @@ fn test_multi_functions() {
                                 Expr {
                                     kind: Let {
                                         id: "a",
+                                        id_position: 85..86,
                                         type_reference: Bool,
                                         init_expr: Expr {
                                             kind: Bool {
@@ -344,6 +353,7 @@ fn test_multi_functions() {
                                 },
                             ],
                         },
+                        inline: Auto,
                         position: 60..61,
                     },
                 ],
@@ -380,3 +390,64 @@ fn test_multi_functions() {
             }"#]],
     );
 }
+
+#[test]
+fn test_annotations() {
+    parse_check(
+        "
+        @inline fun f() : int { 1 }
+        @noinline fun g() : int { 2 }
+        f()
+        ",
+        expect![[r#"
+            Program {
+                functions: [
+                    Function {
+                        id: "f",
+                        params: [],
+                        return_type: Int,
+                        body: Block {
+                            exprs: [
+                                Expr {
+                                    kind: Int {
+                                        value: 1,
+                                    },
+                                    position: 33..34,
+                                },
+                            ],
+                        },
+                        inline: Always,
+                        position: 21..22,
+                    },
+                    Function {
+                        id: "g",
+                        params: [],
+                        return_type: Int,
+                        body: Block {
+                            exprs: [
+                                Expr {
+                                    kind: Int {
+                                        value: 2,
+                                    },
+                                    position: 71..72,
+                                },
+                            ],
+                        },
+                        inline: Never,
+                        position: 59..60,
+                    },
+                ],
+                body: Block {
+                    exprs: [
+                        Expr {
+                            kind: Call {
+                                id: "f",
+                                args: [],
+                            },
+                            position: 83..84,
+                        },
+                    ],
+                },
+            }"#]],
+    );
+}
@@ -273,6 +273,7 @@ fn parse_if_else_chain() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 15..16,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: If {
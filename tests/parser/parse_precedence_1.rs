@@ -175,6 +175,7 @@ fn test_arithmetic_precedence() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 119..120,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -212,6 +213,7 @@ fn test_arithmetic_precedence() {
                         Expr {
                             kind: Let {
                                 id: "b",
+                                id_position: 152..153,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -223,6 +223,7 @@ fn test_comparison_precedence_2() {
                         Expr {
                             kind: Let {
                                 id: "a",
+                                id_position: 15..16,
                                 type_reference: Bool,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -260,6 +261,7 @@ fn test_comparison_precedence_2() {
                         Expr {
                             kind: Let {
                                 id: "b",
+                                id_position: 49..50,
                                 type_reference: Bool,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -297,6 +299,7 @@ fn test_comparison_precedence_2() {
                         Expr {
                             kind: Let {
                                 id: "c",
+                                id_position: 84..85,
                                 type_reference: Unit,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
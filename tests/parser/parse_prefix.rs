@@ -59,6 +59,7 @@ fn test_prefix() {
                         Expr {
                             kind: Let {
                                 id: "name",
+                                id_position: 4..8,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: UnaryExpr {
@@ -114,6 +115,7 @@ fn test_prefix() {
                         Expr {
                             kind: Let {
                                 id: "name",
+                                id_position: 4..8,
                                 type_reference: Int,
                                 init_expr: Expr {
                                     kind: BinaryExpr {
@@ -205,6 +207,7 @@ fn test_prefix() {
                         Expr {
                             kind: Let {
                                 id: "name",
+                                id_position: 4..8,
                                 type_reference: Bool,
                                 init_expr: Expr {
                                     kind: UnaryExpr {
@@ -203,3 +203,20 @@ fn test_parse_fun_no_brace() {
         "#]],
     );
 }
+
+#[test]
+fn test_parse_annotation_no_fun() {
+    parse_error_check(
+        "
+        @inline
+        let a: int = 1
+        ",
+        expect![[r#"
+            Error: Syntax Error: expected `fun` after `@inline`
+             --> :2:8
+              |
+            2 |         @inline
+              |         ^^^^^^^
+        "#]],
+    );
+}
@@ -0,0 +1,166 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the allocation verifier, on allocations that are written by hand.
+
+use expect_test::expect;
+use solis::asm::asm::{FloatRegister::*, Register::*};
+use solis::register_allocation::register_allocator::Assignment;
+use test_utils::verify_allocation_check;
+
+#[test]
+fn test_valid() {
+    // `a` is not live after `b` is declared, so they can share a register.
+    verify_allocation_check(
+        "
+        let a = 1
+        let b = add.int a, 1
+        let c = coerce.int.float b
+        add.float c, c
+        ",
+        &[],
+        vec![
+            ("a", Assignment::Register(R8)),
+            ("b", Assignment::Register(R8)),
+            ("c", Assignment::FloatRegister(Xmm1)),
+        ],
+        expect!["valid"],
+    );
+}
+
+#[test]
+fn test_valid_spills_and_params() {
+    verify_allocation_check(
+        "
+        let a = add.int p, 1
+        let b = add.int a, p
+        let c = 3
+        add.int a, b
+        ",
+        &["p"],
+        vec![
+            ("a", Assignment::Spill(0)),
+            ("b", Assignment::Spill(1)),
+            ("c", Assignment::None),
+        ],
+        expect!["valid"],
+    );
+}
+
+#[test]
+fn test_spill_slot_conflict() {
+    verify_allocation_check(
+        "
+        let a = 1
+        let b = coerce.int.float a
+        let c = add.float b, b
+        let d = add.int a, a
+        c: float
+        ",
+        &[],
+        vec![
+            ("a", Assignment::Spill(1)),
+            ("b", Assignment::FloatRegister(Xmm1)),
+            ("c", Assignment::Spill(1)),
+            ("d", Assignment::None),
+        ],
+        expect!["Internal Compiler Error: variables `a` and `c` are live at the same time, but are both assigned to `Spill(1)`"],
+    );
+}
+
+#[test]
+fn test_conflict() {
+    verify_allocation_check(
+        "
+        let a = 1
+        let b = 2
+        add.int a, b
+        ",
+        &[],
+        vec![("a", Assignment::Register(R9)), ("b", Assignment::Register(R9))],
+        expect!["Internal Compiler Error: variables `a` and `b` are live at the same time, but are both assigned to `Register(R9)`"],
+    );
+}
+
+#[test]
+fn test_conflict_float() {
+    verify_allocation_check(
+        "
+        let a = 1.5
+        let b = 2.5
+        let c = add.float a, b
+        a: float
+        ",
+        &[],
+        vec![
+            ("a", Assignment::FloatRegister(Xmm1)),
+            ("b", Assignment::FloatRegister(Xmm2)),
+            ("c", Assignment::FloatRegister(Xmm1)),
+        ],
+        expect!["valid"],
+    );
+}
+
+#[test]
+fn test_conflict_in_branch() {
+    // `c` is declared in the branch, while `a` is still live.
+    verify_allocation_check(
+        "
+        let a = 1
+        let b = true
+        if b {
+          let c = 2
+          add.int c, 1
+        } else {
+          3
+        }
+        a: int
+        ",
+        &[],
+        vec![
+            ("a", Assignment::Register(R8)),
+            ("b", Assignment::Register(R9)),
+            ("c", Assignment::Register(R8)),
+        ],
+        expect!["Internal Compiler Error: variables `a` and `c` are live at the same time, but are both assigned to `Register(R8)`"],
+    );
+}
+
+#[test]
+fn test_float_in_register() {
+    verify_allocation_check(
+        "
+        let a = 1.5
+        neg.float a
+        ",
+        &[],
+        vec![("a", Assignment::Register(R8))],
+        expect!["Internal Compiler Error: float variable `a` is assigned to the register `R8`"],
+    );
+}
+
+#[test]
+fn test_int_in_float_register() {
+    verify_allocation_check(
+        "
+        let a = true
+        not.bool a
+        ",
+        &[],
+        vec![("a", Assignment::FloatRegister(Xmm3))],
+        expect!["Internal Compiler Error: bool variable `a` is assigned to the float register `Xmm3`"],
+    );
+}
+
+#[test]
+fn test_missing_assignment() {
+    verify_allocation_check(
+        "
+        let a = 1
+        let b = sub.int a, 2
+        b: int
+        ",
+        &[],
+        vec![("b", Assignment::Register(R8))],
+        expect!["Internal Compiler Error: variable `a` has no assignment"],
+    );
+}
@@ -42,18 +42,12 @@ fn test_no_variables() {
                 },
                 InterferenceGraph {
                     nodes: {
-                        "@temp0": {
-                            "@temp1",
-                        },
-                        "@temp1": {
-                            "@temp0",
-                        },
+                        "@temp0": {},
                     },
                     removed_nodes: {},
                 },
                 {
                     "@temp0": 1,
-                    "@temp1": 1,
                 },
             )"#]],
     );
@@ -97,25 +91,19 @@ fn test_only_float_temporaries() {
             (
                 InterferenceGraph {
                     nodes: {
-                        "@temp1": {},
+                        "@temp0": {},
                     },
                     removed_nodes: {},
                 },
                 InterferenceGraph {
                     nodes: {
-                        "@temp0": {
-                            "@temp2",
-                        },
-                        "@temp2": {
-                            "@temp0",
-                        },
+                        "@temp1": {},
                     },
                     removed_nodes: {},
                 },
                 {
                     "@temp0": 1,
                     "@temp1": 1,
-                    "@temp2": 1,
                 },
             )"#]],
     );
@@ -337,7 +325,7 @@ fn test_let_sequence_3() {
                         "@temp3": {
                             "d",
                         },
-                        "@temp5": {},
+                        "@temp4": {},
                         "a": {
                             "@temp0",
                             "@temp1",
@@ -370,12 +358,7 @@ fn test_let_sequence_3() {
                 },
                 InterferenceGraph {
                     nodes: {
-                        "@temp4": {
-                            "@temp6",
-                        },
-                        "@temp6": {
-                            "@temp4",
-                        },
+                        "@temp5": {},
                         "e": {},
                     },
                     removed_nodes: {},
@@ -387,7 +370,6 @@ fn test_let_sequence_3() {
                     "@temp3": 1,
                     "@temp4": 1,
                     "@temp5": 1,
-                    "@temp6": 1,
                     "a": 3,
                     "b": 2,
                     "c": 2,
@@ -445,44 +427,39 @@ fn test_let_sequence_4() {
             (
                 InterferenceGraph {
                     nodes: {
-                        "@temp1": {},
+                        "@temp0": {},
                         "b": {},
                     },
                     removed_nodes: {},
                 },
                 InterferenceGraph {
                     nodes: {
-                        "@temp0": {
-                            "@temp2",
-                        },
+                        "@temp1": {},
                         "@temp10": {
-                            "a",
+                            "@temp11",
                             "c",
                         },
                         "@temp11": {
-                            "@temp12",
+                            "@temp10",
                             "c",
                         },
                         "@temp12": {
-                            "@temp11",
-                            "c",
-                        },
-                        "@temp13": {
                             "c",
                         },
                         "@temp2": {
-                            "@temp0",
+                            "a",
                         },
                         "@temp3": {
+                            "@temp4",
                             "a",
                         },
                         "@temp4": {
-                            "@temp5",
+                            "@temp3",
                             "a",
                         },
                         "@temp5": {
-                            "@temp4",
                             "a",
+                            "c",
                         },
                         "@temp6": {
                             "a",
@@ -501,7 +478,7 @@ fn test_let_sequence_4() {
                             "c",
                         },
                         "a": {
-                            "@temp10",
+                            "@temp2",
                             "@temp3",
                             "@temp4",
                             "@temp5",
@@ -515,7 +492,7 @@ fn test_let_sequence_4() {
                             "@temp10",
                             "@temp11",
                             "@temp12",
-                            "@temp13",
+                            "@temp5",
                             "@temp6",
                             "@temp7",
                             "@temp8",
@@ -532,7 +509,6 @@ fn test_let_sequence_4() {
                     "@temp10": 1,
                     "@temp11": 1,
                     "@temp12": 1,
-                    "@temp13": 1,
                     "@temp2": 1,
                     "@temp3": 1,
                     "@temp4": 1,
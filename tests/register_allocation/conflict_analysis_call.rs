@@ -21,56 +21,12 @@ fn test_no_params() {
         a + b # keep a and b live, but not c
         ",
         expect![[r#"
-            Block {
-                exprs: [
-                    Let {
-                        id: "a",
-                        init_expr: Direct {
-                            expr: Int {
-                                value: 0,
-                            },
-                        },
-                    },
-                    Let {
-                        id: "b",
-                        init_expr: Direct {
-                            expr: Int {
-                                value: 0,
-                            },
-                        },
-                    },
-                    Let {
-                        id: "c",
-                        init_expr: Direct {
-                            expr: Int {
-                                value: 0,
-                            },
-                        },
-                    },
-                    Call {
-                        id: "function",
-                        args: [],
-                        live_variables: RefCell {
-                            value: {
-                                "a",
-                                "b",
-                            },
-                        },
-                    },
-                    BinaryExpr {
-                        kind: Plus,
-                        operand_1: Id {
-                            value: "a",
-                            id_type: Int,
-                        },
-                        operand_2: Id {
-                            value: "b",
-                            id_type: Int,
-                        },
-                        operand_type: Int,
-                    },
-                ],
-            }"#]],
+            let a = 0
+            let b = 0
+            let c = 0
+            call function() live(a, b)
+            add.int a, b
+        "#]],
     );
 }
 
@@ -90,61 +46,11 @@ fn test_basic() {
         d
         ",
         expect![[r#"
-            Block {
-                exprs: [
-                    Let {
-                        id: "a",
-                        init_expr: Direct {
-                            expr: Int {
-                                value: 0,
-                            },
-                        },
-                    },
-                    Let {
-                        id: "b",
-                        init_expr: Direct {
-                            expr: Int {
-                                value: 0,
-                            },
-                        },
-                    },
-                    Let {
-                        id: "c",
-                        init_expr: Direct {
-                            expr: Int {
-                                value: 0,
-                            },
-                        },
-                    },
-                    Let {
-                        id: "d",
-                        init_expr: Call {
-                            id: "function",
-                            args: [
-                                Id {
-                                    value: "a",
-                                    id_type: Int,
-                                },
-                                Id {
-                                    value: "b",
-                                    id_type: Int,
-                                },
-                            ],
-                            live_variables: RefCell {
-                                value: {
-                                    "a",
-                                    "b",
-                                },
-                            },
-                        },
-                    },
-                    Direct {
-                        expr: Id {
-                            value: "d",
-                            id_type: Int,
-                        },
-                    },
-                ],
-            }"#]],
+            let a = 0
+            let b = 0
+            let c = 0
+            let d = call function(a: int, b: int) live(a, b)
+            d: int
+        "#]],
     );
 }
@@ -0,0 +1,204 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the linear scan register allocator.
+
+use expect_test::expect;
+use solis::asm::asm::{FloatRegister, Register};
+use test_utils::linear_scan_ir_check;
+
+#[test]
+fn test_register_reuse() {
+    // The register of `a` is free once `b` is defined, and `d` is never referenced.
+    linear_scan_ir_check(
+        "
+        let a = 1
+        let b = add.int a, 2
+        let c = add.int b, b
+        let d = 4
+        c: int
+        ",
+        &[Register::R8, Register::R9],
+        &[],
+        expect![[r#"
+            {
+                "a": Register(
+                    R8,
+                ),
+                "b": Register(
+                    R8,
+                ),
+                "c": Register(
+                    R8,
+                ),
+                "d": None,
+            }"#]],
+    );
+}
+
+#[test]
+fn test_spill_furthest_end() {
+    // `a` is live until the end, so it is spilled instead of `c`.
+    linear_scan_ir_check(
+        "
+        let a = 1
+        let b = 2
+        let c = 3
+        let d = add.int b, c
+        let e = add.int d, 1
+        add.int e, a
+        ",
+        &[Register::R8, Register::R9],
+        &[],
+        expect![[r#"
+            {
+                "a": Spill(
+                    0,
+                ),
+                "b": Register(
+                    R9,
+                ),
+                "c": Register(
+                    R8,
+                ),
+                "d": Register(
+                    R8,
+                ),
+                "e": Register(
+                    R8,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_spill_slot_reuse() {
+    linear_scan_ir_check(
+        "
+        let a = 1
+        let b = add.int a, 2
+        let c = 3
+        let d = add.int b, c
+        d: int
+        ",
+        &[],
+        &[],
+        expect![[r#"
+            {
+                "a": Spill(
+                    0,
+                ),
+                "b": Spill(
+                    0,
+                ),
+                "c": Spill(
+                    1,
+                ),
+                "d": Spill(
+                    0,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_if() {
+    // `a` is written to by both branches, so it is defined before the branches.
+    linear_scan_ir_check(
+        "
+        let b = 1
+        let c = lt.int b, 2
+        let a = if c {
+            let d = add.int b, 1
+            add.int d, 1
+        } else {
+            let e = sub.int b, 1
+            e: int
+        }
+        add.int a, 1
+        ",
+        &[Register::R8, Register::R9, Register::R10],
+        &[],
+        expect![[r#"
+            {
+                "a": Register(
+                    R9,
+                ),
+                "b": Register(
+                    R8,
+                ),
+                "c": Register(
+                    R9,
+                ),
+                "d": Register(
+                    R10,
+                ),
+                "e": Register(
+                    R8,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_float() {
+    linear_scan_ir_check(
+        "
+        let a = 1
+        let b = coerce.int.float a
+        let c = 2.5
+        let d = add.float b, c
+        let e = add.int a, 1
+        d: float
+        ",
+        &[Register::R8],
+        &[FloatRegister::Xmm1],
+        expect![[r#"
+            {
+                "a": Register(
+                    R8,
+                ),
+                "b": FloatRegister(
+                    Xmm1,
+                ),
+                "c": Spill(
+                    0,
+                ),
+                "d": FloatRegister(
+                    Xmm1,
+                ),
+                "e": None,
+            }"#]],
+    );
+}
+
+#[test]
+fn test_register_order() {
+    // Registers are preferred in the order of the pool (like the callee saved registers, which are last in the pool of
+    // the compiler), and a freed register is preferred again.
+    linear_scan_ir_check(
+        "
+        let a = 1
+        let b = add.int a, 2
+        let c = add.int b, a
+        let d = add.int c, 3
+        d: int
+        ",
+        &[Register::R12, Register::R8, Register::Rbx],
+        &[],
+        expect![[r#"
+            {
+                "a": Register(
+                    R12,
+                ),
+                "b": Register(
+                    R8,
+                ),
+                "c": Register(
+                    R12,
+                ),
+                "d": Register(
+                    R12,
+                ),
+            }"#]],
+    );
+}
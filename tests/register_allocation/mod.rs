@@ -2,10 +2,15 @@
 
 //! Unit tests for the register allocation model.
 
+mod allocation_verifier_basic;
 mod conflict_analysis_basic;
 mod conflict_analysis_call;
+mod linear_scan_basic;
 mod liveness_analysis_basic;
 mod register_allocator_basic;
+mod register_allocator_coalesce;
+mod register_allocator_spill_slots;
 mod register_allocator_starve_1;
 mod register_allocator_starve_2;
 mod register_allocator_starve_3;
+mod spill_cost_basic;
@@ -4,12 +4,11 @@
 
 use expect_test::expect;
 use solis::asm::asm::{FloatRegister, Register};
-use solis::register_allocation::register_allocator::Set;
-use test_utils::register_allocator_check;
+use test_utils::{register_allocator_check, register_allocator_ir_check};
 
 #[test]
 fn test_empty_program() {
-    register_allocator_check("", Set::from([&Register::R8, &Register::R9]), Set::new(), expect!["{}"]);
+    register_allocator_check("", &[Register::R8, Register::R9], &[], expect!["{}"]);
 }
 
 #[test]
@@ -20,22 +19,43 @@ fn test_empty_registers() {
          let c: int = 8 + a + b + 7
          let d: int = a + c
          let e: float = a + b + c + d + 0.0",
-        Set::new(),
-        Set::new(),
+        &[],
+        &[],
         expect![[r#"
             {
-                "@temp0": Spill,
-                "@temp1": Spill,
-                "@temp2": Spill,
-                "@temp3": Spill,
-                "@temp4": Spill,
-                "@temp5": Spill,
-                "@temp6": Spill,
-                "a": Spill,
-                "b": Spill,
-                "c": Spill,
-                "d": Spill,
-                "e": Spill,
+                "@temp0": Spill(
+                    0,
+                ),
+                "@temp1": Spill(
+                    0,
+                ),
+                "@temp2": Spill(
+                    1,
+                ),
+                "@temp3": Spill(
+                    1,
+                ),
+                "@temp4": Spill(
+                    0,
+                ),
+                "@temp5": Spill(
+                    4,
+                ),
+                "a": Spill(
+                    2,
+                ),
+                "b": Spill(
+                    1,
+                ),
+                "c": Spill(
+                    3,
+                ),
+                "d": Spill(
+                    0,
+                ),
+                "e": Spill(
+                    4,
+                ),
             }"#]],
     );
 }
@@ -50,8 +70,8 @@ fn test_no_variables() {
          1 / 2
          1 >= 2
          1 % 2",
-        Set::from([&Register::R8, &Register::R9]),
-        Set::from([&FloatRegister::Xmm1, &FloatRegister::Xmm2]),
+        &[Register::R8, Register::R9],
+        &[FloatRegister::Xmm1, FloatRegister::Xmm2],
         expect!["{}"],
     );
 }
@@ -61,8 +81,8 @@ fn test_no_conflicts_1() {
     register_allocator_check(
         "1 + 2 + 3       # @temp0 = 1 + 2; @temp0 + 1
          1 < 2 != false  # @temp1 = 1 < 2; @temp1 != false",
-        Set::from([&Register::R8, &Register::R9, &Register::R10]),
-        Set::new(),
+        &[Register::R8, Register::R9, Register::R10],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -82,17 +102,14 @@ fn test_no_conflicts_2() {
          let b: int = 2 + 3
          let c: int = 4 + 5
          let d: bool = 6 + 7 < 9.",
-        Set::from([&Register::R8, &Register::R9, &Register::R10]),
-        Set::from([&FloatRegister::Xmm1, &FloatRegister::Xmm2]),
+        &[Register::R8, Register::R9, Register::R10],
+        &[FloatRegister::Xmm1, FloatRegister::Xmm2],
         expect![[r#"
             {
-                "@temp0": FloatRegister(
-                    Xmm2,
-                ),
-                "@temp1": Register(
+                "@temp0": Register(
                     R8,
                 ),
-                "@temp2": FloatRegister(
+                "@temp1": FloatRegister(
                     Xmm1,
                 ),
                 "a": None,
@@ -104,3 +121,63 @@ fn test_no_conflicts_2() {
             }"#]],
     );
 }
+
+#[test]
+fn test_ir() {
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = add.int a, 2
+        let c = 1.5
+        let d = mul.float c, c
+        add.int a, b
+        ",
+        &[Register::R8],
+        &[FloatRegister::Xmm1, FloatRegister::Xmm2],
+        expect![[r#"
+            {
+                "a": Register(
+                    R8,
+                ),
+                "b": Spill(
+                    0,
+                ),
+                "c": FloatRegister(
+                    Xmm1,
+                ),
+                "d": None,
+            }"#]],
+    );
+}
+
+#[test]
+fn test_register_order() {
+    // Registers are preferred in the order of the pool (like the callee saved registers, which are last in the pool of
+    // the compiler).
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = add.int a, 2
+        let c = add.int b, a
+        let d = add.int c, 3
+        d: int
+        ",
+        &[Register::R12, Register::R8, Register::Rbx],
+        &[],
+        expect![[r#"
+            {
+                "a": Register(
+                    R8,
+                ),
+                "b": Register(
+                    R12,
+                ),
+                "c": Register(
+                    R12,
+                ),
+                "d": Register(
+                    R12,
+                ),
+            }"#]],
+    );
+}
@@ -0,0 +1,171 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for coalescing copy related variables in the register allocator.
+
+use expect_test::expect;
+use solis::asm::asm::{FloatRegister, Register};
+use test_utils::register_allocator_ir_check;
+
+#[test]
+fn test_copy_chain() {
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = a: int
+        let c = b: int
+        let d = 2.5
+        let e = d: float
+        let f = add.int c, 1
+        let g = coerce.int.float f
+        add.float g, e
+        ",
+        &[Register::R8, Register::R9],
+        &[FloatRegister::Xmm1, FloatRegister::Xmm2],
+        expect![[r#"
+            {
+                "a": Register(
+                    R8,
+                ),
+                "b": Register(
+                    R8,
+                ),
+                "c": Register(
+                    R8,
+                ),
+                "d": FloatRegister(
+                    Xmm2,
+                ),
+                "e": FloatRegister(
+                    Xmm2,
+                ),
+                "f": Register(
+                    R8,
+                ),
+                "g": FloatRegister(
+                    Xmm1,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_interfering_copy() {
+    // `a` is still live after the copy, so `a` and `b` can't be coalesced.
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = a: int
+        add.int a, b
+        ",
+        &[Register::R8, Register::R9],
+        &[],
+        expect![[r#"
+            {
+                "a": Register(
+                    R9,
+                ),
+                "b": Register(
+                    R8,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_if_copies() {
+    // The results of both branches are copied into `c`.
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let t = true
+        let c = if t {
+          let d = mul.int a, 2
+          d: int
+        } else {
+          let e = add.int a, 3
+          e: int
+        }
+        add.int c, a
+        ",
+        &[Register::R8, Register::R9, Register::R10],
+        &[],
+        expect![[r#"
+            {
+                "a": Register(
+                    R9,
+                ),
+                "c": Register(
+                    R8,
+                ),
+                "d": Register(
+                    R8,
+                ),
+                "e": Register(
+                    R8,
+                ),
+                "t": Register(
+                    R8,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_conservative() {
+    // With one register, `p` and `r` are significant neighbors of `x` and `y`, so coalescing `x` and `y` could make the
+    // graph uncolorable. They are not coalesced, and are colored independently (which happens to be the same register).
+    register_allocator_ir_check(
+        "
+        let p = 1
+        let x = 2
+        let a = add.int p, x
+        let y = x: int
+        let r = 3
+        add.int r, y
+        ",
+        &[Register::R8],
+        &[],
+        expect![[r#"
+            {
+                "a": None,
+                "p": Spill(
+                    0,
+                ),
+                "r": Spill(
+                    0,
+                ),
+                "x": Register(
+                    R8,
+                ),
+                "y": Register(
+                    R8,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_spilled_copy() {
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = 2
+        let c = a: int
+        add.int b, c
+        ",
+        &[],
+        &[],
+        expect![[r#"
+            {
+                "a": Spill(
+                    1,
+                ),
+                "b": Spill(
+                    0,
+                ),
+                "c": Spill(
+                    1,
+                ),
+            }"#]],
+    );
+}
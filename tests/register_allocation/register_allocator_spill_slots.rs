@@ -0,0 +1,120 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for assigning spilled variables to stack slots in the register allocator.
+
+use expect_test::expect;
+use solis::asm::asm::Register;
+use solis::compiler::compiler::frame_size;
+use solis::register_allocation::register_allocator::{Assignment, Map};
+use test_utils::register_allocator_ir_check;
+
+#[test]
+fn test_disjoint_spills_share_slot() {
+    // Each variable is dead once the next variable is declared, so all of them share a slot.
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = add.int a, 2
+        let c = add.int b, 3
+        let d = add.int c, 4
+        d: int
+        ",
+        &[],
+        &[],
+        expect![[r#"
+            {
+                "a": Spill(
+                    0,
+                ),
+                "b": Spill(
+                    0,
+                ),
+                "c": Spill(
+                    0,
+                ),
+                "d": Spill(
+                    0,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_interfering_spills() {
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = 2
+        let c = 3
+        let d = add.int a, b
+        let e = add.int d, c
+        add.int e, a
+        ",
+        &[Register::R8],
+        &[],
+        expect![[r#"
+            {
+                "a": Spill(
+                    1,
+                ),
+                "b": Register(
+                    R8,
+                ),
+                "c": Spill(
+                    0,
+                ),
+                "d": Register(
+                    R8,
+                ),
+                "e": Register(
+                    R8,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_int_and_float_spills() {
+    // Int and float variables are in different interference graphs, so their slots are never shared.
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = 2.5
+        let c = coerce.int.float a
+        add.float b, c
+        ",
+        &[],
+        &[],
+        expect![[r#"
+            {
+                "a": Spill(
+                    0,
+                ),
+                "b": Spill(
+                    1,
+                ),
+                "c": Spill(
+                    2,
+                ),
+            }"#]],
+    );
+}
+
+#[test]
+fn test_frame_size() {
+    let (a, b, c) = (String::from("a"), String::from("b"), String::from("c"));
+
+    let mut allocation = Map::new();
+    allocation.insert(&a, Assignment::Register(Register::R8));
+    assert_eq!(frame_size(&allocation, 0), 0);
+    assert_eq!(frame_size(&allocation, 1), 8);
+
+    allocation.insert(&b, Assignment::Spill(0));
+    assert_eq!(frame_size(&allocation, 0), 16);
+    assert_eq!(frame_size(&allocation, 1), 8);
+    assert_eq!(frame_size(&allocation, 2), 16);
+
+    allocation.insert(&c, Assignment::Spill(2));
+    assert_eq!(frame_size(&allocation, 0), 32);
+    assert_eq!(frame_size(&allocation, 3), 24);
+}
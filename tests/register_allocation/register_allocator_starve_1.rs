@@ -4,7 +4,6 @@
 
 use expect_test::expect;
 use solis::asm::asm::Register;
-use solis::register_allocation::register_allocator::Set;
 use test_utils::register_allocator_check;
 
 static PROGRAM_1: &str = "# General purpose program
@@ -27,8 +26,8 @@ static PROGRAM_1: &str = "# General purpose program
 fn test_program_1_starve_4() {
     register_allocator_check(
         PROGRAM_1,
-        Set::from([&Register::R8, &Register::R9, &Register::R10, &Register::R11]),
-        Set::new(),
+        &[Register::R8, Register::R9, Register::R10, Register::R11],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -57,8 +56,8 @@ fn test_program_1_starve_4() {
 fn test_program_1_starve_3() {
     register_allocator_check(
         PROGRAM_1,
-        Set::from([&Register::R8, &Register::R9, &Register::R10]),
-        Set::new(),
+        &[Register::R8, Register::R9, Register::R10],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -87,22 +86,24 @@ fn test_program_1_starve_3() {
 fn test_program_1_starve_2() {
     register_allocator_check(
         PROGRAM_1,
-        Set::from([&Register::R8, &Register::R9]),
-        Set::new(),
+        &[Register::R8, Register::R9],
+        &[],
         expect![[r#"
             {
-                "@temp0": Spill,
-                "@temp1": Register(
-                    R8,
+                "@temp0": Register(
+                    R9,
                 ),
-                "a": Register(
+                "@temp1": Register(
                     R9,
                 ),
-                "b": Register(
+                "a": Register(
                     R8,
                 ),
+                "b": Spill(
+                    0,
+                ),
                 "c": Register(
-                    R8,
+                    R9,
                 ),
                 "d": Register(
                     R8,
@@ -115,17 +116,25 @@ fn test_program_1_starve_2() {
 fn test_program_1_starve_1() {
     register_allocator_check(
         PROGRAM_1,
-        Set::from([&Register::R8]),
-        Set::new(),
+        &[Register::R8],
+        &[],
         expect![[r#"
             {
-                "@temp0": Spill,
-                "@temp1": Spill,
-                "a": Register(
+                "@temp0": Register(
+                    R8,
+                ),
+                "@temp1": Register(
+                    R8,
+                ),
+                "a": Spill(
+                    0,
+                ),
+                "b": Spill(
+                    1,
+                ),
+                "c": Register(
                     R8,
                 ),
-                "b": Spill,
-                "c": Spill,
                 "d": Register(
                     R8,
                 ),
@@ -137,16 +146,28 @@ fn test_program_1_starve_1() {
 fn test_program_1_starve_0() {
     register_allocator_check(
         PROGRAM_1,
-        Set::new(),
-        Set::new(),
+        &[],
+        &[],
         expect![[r#"
             {
-                "@temp0": Spill,
-                "@temp1": Spill,
-                "a": Spill,
-                "b": Spill,
-                "c": Spill,
-                "d": Spill,
+                "@temp0": Spill(
+                    2,
+                ),
+                "@temp1": Spill(
+                    1,
+                ),
+                "a": Spill(
+                    0,
+                ),
+                "b": Spill(
+                    1,
+                ),
+                "c": Spill(
+                    1,
+                ),
+                "d": Spill(
+                    0,
+                ),
             }"#]],
     );
 }
@@ -4,7 +4,6 @@
 
 use expect_test::expect;
 use solis::asm::asm::Register;
-use solis::register_allocation::register_allocator::Set;
 use test_utils::register_allocator_check;
 
 static PROGRAM_2: &str = "# Program with similar frequencies, but complex conflict graph
@@ -43,8 +42,8 @@ static PROGRAM_2: &str = "# Program with similar frequencies, but complex confli
 fn test_program_2_starve_4() {
     register_allocator_check(
         PROGRAM_2,
-        Set::from([&Register::R8, &Register::R9, &Register::R10, &Register::R11]),
-        Set::new(),
+        &[Register::R8, Register::R9, Register::R10, Register::R11],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -88,8 +87,8 @@ fn test_program_2_starve_4() {
 fn test_program_2_starve_3() {
     register_allocator_check(
         PROGRAM_2,
-        Set::from([&Register::R8, &Register::R9, &Register::R10]),
-        Set::new(),
+        &[Register::R8, Register::R9, Register::R10],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -133,8 +132,8 @@ fn test_program_2_starve_3() {
 fn test_program_2_starve_2() {
     register_allocator_check(
         PROGRAM_2,
-        Set::from([&Register::R8, &Register::R9]),
-        Set::new(),
+        &[Register::R8, Register::R9],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -152,12 +151,18 @@ fn test_program_2_starve_2() {
                 "@temp4": Register(
                     R8,
                 ),
-                "@temp5": Spill,
-                "@temp6": Spill,
+                "@temp5": Register(
+                    R8,
+                ),
+                "@temp6": Register(
+                    R9,
+                ),
                 "@temp7": Register(
                     R8,
                 ),
-                "a": Spill,
+                "a": Spill(
+                    0,
+                ),
                 "b": Register(
                     R9,
                 ),
@@ -172,24 +177,40 @@ fn test_program_2_starve_2() {
 fn test_program_2_starve_1() {
     register_allocator_check(
         PROGRAM_2,
-        Set::from([&Register::R8]),
-        Set::new(),
+        &[Register::R8],
+        &[],
         expect![[r#"
             {
-                "@temp0": Spill,
+                "@temp0": Spill(
+                    0,
+                ),
                 "@temp1": Register(
                     R8,
                 ),
                 "@temp2": Register(
                     R8,
                 ),
-                "@temp3": Spill,
-                "@temp4": Spill,
-                "@temp5": Spill,
-                "@temp6": Spill,
-                "@temp7": Spill,
-                "a": Spill,
-                "b": Spill,
+                "@temp3": Register(
+                    R8,
+                ),
+                "@temp4": Register(
+                    R8,
+                ),
+                "@temp5": Register(
+                    R8,
+                ),
+                "@temp6": Spill(
+                    1,
+                ),
+                "@temp7": Register(
+                    R8,
+                ),
+                "a": Spill(
+                    0,
+                ),
+                "b": Spill(
+                    1,
+                ),
                 "c": Register(
                     R8,
                 ),
@@ -201,21 +222,43 @@ fn test_program_2_starve_1() {
 fn test_program_2_starve_0() {
     register_allocator_check(
         PROGRAM_2,
-        Set::new(),
-        Set::new(),
+        &[],
+        &[],
         expect![[r#"
             {
-                "@temp0": Spill,
-                "@temp1": Spill,
-                "@temp2": Spill,
-                "@temp3": Spill,
-                "@temp4": Spill,
-                "@temp5": Spill,
-                "@temp6": Spill,
-                "@temp7": Spill,
-                "a": Spill,
-                "b": Spill,
-                "c": Spill,
+                "@temp0": Spill(
+                    0,
+                ),
+                "@temp1": Spill(
+                    1,
+                ),
+                "@temp2": Spill(
+                    0,
+                ),
+                "@temp3": Spill(
+                    1,
+                ),
+                "@temp4": Spill(
+                    1,
+                ),
+                "@temp5": Spill(
+                    2,
+                ),
+                "@temp6": Spill(
+                    1,
+                ),
+                "@temp7": Spill(
+                    2,
+                ),
+                "a": Spill(
+                    0,
+                ),
+                "b": Spill(
+                    1,
+                ),
+                "c": Spill(
+                    2,
+                ),
             }"#]],
     );
 }
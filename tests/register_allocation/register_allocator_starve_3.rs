@@ -4,7 +4,6 @@
 
 use expect_test::expect;
 use solis::asm::asm::Register;
-use solis::register_allocation::register_allocator::Set;
 use test_utils::register_allocator_check;
 
 static PROGRAM_3: &str = "# Program with different frequencies
@@ -40,8 +39,8 @@ static PROGRAM_3: &str = "# Program with different frequencies
 fn test_program_3_starve_3() {
     register_allocator_check(
         PROGRAM_3,
-        Set::from([&Register::R8, &Register::R9, &Register::R10]),
-        Set::new(),
+        &[Register::R8, Register::R9, Register::R10],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -69,8 +68,8 @@ fn test_program_3_starve_3() {
 fn test_program_3_starve_2() {
     register_allocator_check(
         PROGRAM_3,
-        Set::from([&Register::R8, &Register::R9]),
-        Set::new(),
+        &[Register::R8, Register::R9],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -98,8 +97,8 @@ fn test_program_3_starve_2() {
 fn test_program_3_starve_1() {
     register_allocator_check(
         PROGRAM_3,
-        Set::from([&Register::R8]),
-        Set::new(),
+        &[Register::R8],
+        &[],
         expect![[r#"
             {
                 "@temp0": Register(
@@ -127,17 +126,27 @@ fn test_program_3_starve_1() {
 fn test_program_3_starve_0() {
     register_allocator_check(
         PROGRAM_3,
-        Set::new(),
-        Set::new(),
+        &[],
+        &[],
         expect![[r#"
             {
-                "@temp0": Spill,
-                "@temp1": Spill,
-                "@temp2": Spill,
-                "a": Spill,
+                "@temp0": Spill(
+                    0,
+                ),
+                "@temp1": Spill(
+                    0,
+                ),
+                "@temp2": Spill(
+                    0,
+                ),
+                "a": Spill(
+                    0,
+                ),
                 "b": None,
                 "c": None,
-                "d": Spill,
+                "d": Spill(
+                    0,
+                ),
             }"#]],
     );
 }
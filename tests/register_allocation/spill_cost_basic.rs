@@ -0,0 +1,119 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests for the spill costs that the register allocator uses to choose which variable to spill.
+
+use expect_test::expect;
+use solis::asm::asm::Register;
+use test_utils::{register_allocator_ir_check, spill_costs_check};
+
+#[test]
+fn test_degree() {
+    // `a` is live throughout the block, but is only referenced once, so it is the cheapest to spill.
+    spill_costs_check(
+        "
+        let a = 1
+        let b = 2
+        let c = 3
+        let d = add.int b, c
+        let e = add.int d, b
+        add.int e, a
+        ",
+        expect![[r#"
+            a: references 1, degree 4, cost 0.250
+            b: references 2, degree 3, cost 0.667
+            c: references 1, degree 2, cost 0.500
+            d: references 1, degree 2, cost 0.500
+            e: references 1, degree 1, cost 1.000
+        "#]],
+    );
+}
+
+#[test]
+fn test_nested_branches() {
+    spill_costs_check(
+        "
+        let a = 1
+        let b = lt.int a, 2
+        let c = if b {
+            let d = if b {
+                add.int a, a
+            } else {
+                a: int
+            }
+            add.int d, a
+        } else {
+            a: int
+        }
+        add.int c, a
+        ",
+        expect![[r#"
+            a: references 3.75, degree 3, cost 1.250
+            b: references 1.5, degree 1, cost 1.500
+            c: references 1, degree 1, cost 1.000
+            d: references 0.5, degree 1, cost 0.500
+        "#]],
+    );
+}
+
+#[test]
+fn test_short_lived_temporaries() {
+    // `%temp0` is only referenced by the next expression, but `%temp1` is also referenced after that.
+    spill_costs_check(
+        "
+        let %temp0 = 1
+        let %temp1 = add.int %temp0, 2
+        let x = add.int %temp1, 3
+        let y = add.int x, %temp1
+        y: int
+        ",
+        expect![[r#"
+            @temp0: references 1, degree 0, never spilled (short-lived)
+            @temp1: references 2, degree 1, cost 2.000
+            x: references 1, degree 1, cost 1.000
+            y: references 1, degree 0, cost 1.000
+        "#]],
+    );
+}
+
+#[test]
+fn test_spill_choice() {
+    // `a` is only referenced at the end, so it is spilled instead of `b`, which is referenced in the nested branches.
+    register_allocator_ir_check(
+        "
+        let a = 1
+        let b = 2
+        let c = lt.int b, 3
+        let d = if c {
+            let e = if c {
+                add.int b, b
+            } else {
+                b: int
+            }
+            add.int e, b
+        } else {
+            b: int
+        }
+        add.int d, a
+        ",
+        &[Register::R8, Register::R9],
+        &[],
+        expect![[r#"
+            {
+                "a": Spill(
+                    1,
+                ),
+                "b": Register(
+                    R9,
+                ),
+                "c": Register(
+                    R8,
+                ),
+                "d": Register(
+                    R9,
+                ),
+                "e": Spill(
+                    0,
+                ),
+            }"#]],
+    );
+}
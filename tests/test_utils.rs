@@ -6,15 +6,42 @@ extern crate expect_test;
 extern crate solis;
 
 use expect_test::{expect, Expect};
-use solis::asm::asm::{FloatRegister, Register};
-use solis::ir::ir::Type;
+use solis::asm::asm::{FloatRegister, Instruction, Register};
+use solis::asm::asm_writer::{instruction_to_string, write_instructions, write_instructions_to_file, Assembler};
+use solis::asm::debug_info::{add_debug_sections, DebugInfo};
+use solis::asm::elf_writer::elf_bytes;
+use solis::asm::emulator::emulate;
+use solis::asm::encoder::{encode, Object};
+use solis::asm::peephole::{peephole_optimize, peephole_optimize_with, PeepholeRule};
+use solis::c_backend::c_writer::{write_program, write_program_to_file, PRELUDE};
+use solis::compiler::annotations::annotate_asm;
+use solis::compiler::compiler::{allocate, compile_with_allocation};
+use solis::emitter::format_spill_costs;
+use solis::error_messages::compilation_warning;
+use solis::interpreter::interpreter::interpret;
+use solis::ir::ir::{Program, Type};
+use solis::ir::ir_parser::parse_ir;
+use solis::ir::ir_printer::{print_block, print_program};
 use solis::ir::translator::translate_program;
+use solis::jit::execute;
+use solis::optimizer::constant_folding::fold_constants;
+use solis::optimizer::dead_code_elimination::eliminate_dead_code;
+use solis::optimizer::inliner::inline_functions;
+use solis::optimizer::optimizer::optimize;
 use solis::parser::parser::parse;
+use solis::register_allocation::allocation_verifier::verify_allocation;
 use solis::register_allocation::conflict_analysis::{conflict_analysis, InterferenceGraph};
+use solis::register_allocation::linear_scan::allocate_registers_linear;
 use solis::register_allocation::liveness_analysis::liveness_analysis;
-use solis::register_allocation::register_allocator::{allocate_registers, Map, Set};
+use solis::register_allocation::register_allocator::{allocate_registers, Assignment, Map, RegisterAllocator, Set};
 use solis::tokenizer::tokenizer::tokenize;
+use solis::warnings::unused_analysis::unused_analysis;
+use solis::warnings::warnings::{report_warnings, WarningConfig};
 use solis::File;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
 
 /// Tests tokenizer output on program.
 pub fn tokenize_check(program: &str, expect: Expect) {
@@ -56,10 +83,9 @@ pub fn parse_error_check(program: &str, expect: Expect) {
 pub fn translate_check(program: &str, expect: Expect) {
     let file = File { name: String::new(), contents: program.to_string() };
 
-    expect.assert_eq(&format!(
-        "{:#?}",
-        translate_program(&file, parse(&file, tokenize(&file)))
-    ));
+    let printed_ir = print_program(&translate_program(&file, parse(&file, tokenize(&file))));
+
+    expect.assert_eq(&round_trip_ir(&printed_ir));
 }
 
 /// Tests translator output on program, where a compilation error is expected.
@@ -72,6 +98,30 @@ pub fn translate_error_check(program: &str, expect: Expect) {
     );
 }
 
+/// Tests that the textual IR is parsed and printed back, where `expect` is the printed IR.
+pub fn ir_round_trip_check(ir: &str, expect: Expect) {
+    let file = File { name: String::new(), contents: ir.to_string() };
+    expect.assert_eq(&round_trip_ir(&print_program(&parse_ir(&file))));
+}
+
+/// Tests parsing textual IR, where a compilation error is expected.
+pub fn ir_parse_error_check(ir: &str, expect: Expect) {
+    expect_error(
+        || {
+            ir_round_trip_check(ir, expect![]);
+        },
+        expect,
+    );
+}
+
+// Ensures that printed IR parses back into the same program (printing is the inverse of parsing), returning it.
+fn round_trip_ir(printed_ir: &str) -> String {
+    let file = File { name: String::new(), contents: printed_ir.to_string() };
+    assert_eq!(printed_ir, print_program(&parse_ir(&file)));
+
+    printed_ir.to_string()
+}
+
 /// Test function for liveness analysis of an expression (runs it on the last expression of the block passed in).
 pub fn liveness_analysis_check(
     block: &str,
@@ -113,14 +163,14 @@ pub fn conflict_analysis_ir_check(block: &str, expect: Expect) {
     let program = translate_program(&file, parse(&file, tokenize(&file)));
     conflict_analysis(&program.body, &Set::new());
 
-    expect.assert_eq(&format!("{:#?}", program.body));
+    expect.assert_eq(&print_block(&program.body));
 }
 
 /// Test function for conflict analysis of a block.
 pub fn register_allocator_check(
     block: &str,
-    registers: Set<&Register>,
-    float_registers: Set<&FloatRegister>,
+    registers: &[Register],
+    float_registers: &[FloatRegister],
     expect: Expect,
 ) {
     let file = File { name: String::new(), contents: block.to_string() };
@@ -132,6 +182,502 @@ pub fn register_allocator_check(
     ));
 }
 
+/// Test function for the register allocator on a block, written in the textual IR format.
+pub fn register_allocator_ir_check(
+    ir: &str,
+    registers: &[Register],
+    float_registers: &[FloatRegister],
+    expect: Expect,
+) {
+    let program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+
+    expect.assert_eq(&format!(
+        "{:#?}",
+        allocate_registers(&program.body, &Set::new(), registers, float_registers)
+    ));
+}
+
+/// Test function for constant folding on a program, where the folded program must have the same result when
+/// interpreted.
+pub fn constant_folding_check(program: &str, expect: Expect) {
+    let file = File { name: String::new(), contents: program.to_string() };
+    let mut program = translate_program(&file, parse(&file, tokenize(&file)));
+
+    let expected = interpret(&program);
+    fold_constants(&mut program);
+
+    assert_eq!(interpret(&program), expected);
+    expect.assert_eq(&print_program(&program));
+}
+
+/// Test function for constant folding on a program, written in the textual IR format.
+pub fn constant_folding_ir_check(ir: &str, expect: Expect) {
+    let mut program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+
+    let expected = interpret(&program);
+    fold_constants(&mut program);
+
+    assert_eq!(interpret(&program), expected);
+    expect.assert_eq(&print_program(&program));
+}
+
+/// Test function for dead code elimination on a program, written in the textual IR format. The expected output starts
+/// with the number of removed expressions.
+pub fn dead_code_elimination_ir_check(ir: &str, expect: Expect) {
+    let mut program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+
+    let expected = interpret(&program);
+    let removed_count = eliminate_dead_code(&mut program);
+
+    assert_eq!(interpret(&program), expected);
+    expect.assert_eq(&format!("removed {removed_count}\n{}", print_program(&program)));
+}
+
+/// Test function for inlining the functions of a program. The expected output starts with the number of inlined calls,
+/// and the interpretation of the program must be unchanged.
+pub fn inliner_check(program: &str, expect: Expect) {
+    let file = File { name: String::new(), contents: program.to_string() };
+    inline_check(translate_program(&file, parse(&file, tokenize(&file))), expect);
+}
+
+/// Test function for inlining the functions of a program, written in the textual IR format.
+pub fn inliner_ir_check(ir: &str, expect: Expect) {
+    inline_check(
+        parse_ir(&File { name: String::new(), contents: ir.to_string() }),
+        expect,
+    );
+}
+
+fn inline_check(mut program: Program, expect: Expect) {
+    let expected = interpret(&program);
+    let inlined_count = inline_functions(&mut program);
+
+    assert_eq!(interpret(&program), expected);
+    expect.assert_eq(&format!("inlined {inlined_count}\n{}", print_program(&program)));
+}
+
+/// Test function for the peephole optimizer with the given rules, where the output is the written instructions (without
+/// the indentation of instructions, which `expect` would trim).
+pub fn peephole_check(rules: &[PeepholeRule], instructions: Vec<Instruction>, expect: Expect) {
+    let mut buffer = vec![];
+    write_instructions(
+        peephole_optimize_with(instructions, rules),
+        &mut buffer,
+        Assembler::Nasm,
+    );
+    expect.assert_eq(&String::from_utf8(buffer).unwrap().replace('\t', ""));
+}
+
+/// Tests the compiler output on a program, written in the textual IR format (without optimizations).
+pub fn compile_ir_check(ir: &str, expect: Expect) {
+    let program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+
+    let mut buffer = vec![];
+    write_instructions(
+        compile_with_allocation(&program, &allocate(&program.body, RegisterAllocator::Graph)),
+        &mut buffer,
+        Assembler::Nasm,
+    );
+    expect.assert_eq(&String::from_utf8(buffer).unwrap().replace('\t', ""));
+}
+
+/// Tests the compiler output on a program with `--annotate-asm` (without optimizations), where the line markers are
+/// removed like without `-g`.
+pub fn compile_annotated_check(program: &str, expect: Expect) {
+    let file = File { name: String::new(), contents: program.to_string() };
+    let ir = translate_program(&file, parse(&file, tokenize(&file)));
+    let variable_assignment = allocate(&ir.body, RegisterAllocator::Graph);
+
+    let mut instructions = annotate_asm(
+        compile_with_allocation(&ir, &variable_assignment),
+        &file,
+        &ir.body,
+        &variable_assignment,
+    );
+    instructions.retain(|instruction| !matches!(instruction, Instruction::Line(..)));
+
+    let mut buffer = vec![];
+    write_instructions(instructions, &mut buffer, Assembler::Nasm);
+    expect.assert_eq(&String::from_utf8(buffer).unwrap().replace('\t', ""));
+}
+
+/// Tests both writers (NASM and the GNU assembler) on the same instructions.
+pub fn asm_writers_check(instructions: &[Instruction], nasm_expect: Expect, gas_expect: Expect) {
+    for (assembler, expect) in [(Assembler::Nasm, nasm_expect), (Assembler::Gas, gas_expect)] {
+        let mut buffer = vec![];
+        write_instructions(instructions.to_vec(), &mut buffer, assembler);
+        expect.assert_eq(&String::from_utf8(buffer).unwrap());
+    }
+}
+
+/// Tests the encoding of each instruction on its own, where each line is an instruction (in NASM syntax) and its bytes.
+pub fn encodings_check(instructions: &[Instruction], expect: Expect) {
+    let lines: Vec<String> = instructions
+        .iter()
+        .map(|instruction| {
+            let object = encode(std::slice::from_ref(instruction));
+            let string = instruction_to_string(instruction.clone());
+            format!("{:<40}{}", string.trim(), hex(&object.sections[0].bytes))
+        })
+        .collect();
+    expect.assert_eq(&lines.join("\n"));
+}
+
+/// Tests the encoder output on instructions, which is the contents of each section, the relocations, and the symbols.
+pub fn encoder_check(instructions: &[Instruction], expect: Expect) {
+    expect.assert_eq(&format_object(&encode(instructions)));
+}
+
+/// Tests that the encoder creates the same sections as the GNU assembler (and `nasm`, if it is installed) on the
+/// instructions. The sections are read from the object files, so the object file of the encoder is checked as well.
+/// * name - the name of the test, which is the name of the files in `build/encoder_tests` (to compare them with
+///   `objdump` when the test fails).
+pub fn encoder_cross_check(name: &str, instructions: &[Instruction]) {
+    let directory = Path::new("./build/encoder_tests");
+    let object_file = elf_bytes(&encode(instructions));
+    fs::create_dir_all(directory).unwrap();
+    fs::write(directory.join(format!("{name}_Builtin.o")), &object_file).unwrap();
+
+    // `-O1` uses the same encodings as `nasm`, like `mov r32, imm32` for (unsigned) 32 bit immediates.
+    let mut assemblers = vec![(Assembler::Gas, vec!["cc", "-c", "-Wa,-O1"])];
+    if Command::new("nasm").arg("-v").output().is_ok() {
+        assemblers.push((Assembler::Nasm, vec!["nasm", "-f", "elf64"]));
+    }
+
+    for (assembler, command) in assemblers {
+        let assembly_file_path = directory.join(format!("{name}_{assembler:?}.s"));
+        let object_file_path = directory.join(format!("{name}_{assembler:?}.o"));
+        write_instructions_to_file(instructions.to_vec(), &assembly_file_path, assembler);
+
+        // `-O1` also makes `and r64, imm` 32 bit (for positive immediates), which `nasm` doesn't.
+        if assembler == Assembler::Gas {
+            let assembly = fs::read_to_string(&assembly_file_path).unwrap();
+            fs::write(&assembly_file_path, assembly.replace("\tand ", "\t{nooptimize} and ")).unwrap();
+        }
+
+        let status = Command::new(command[0])
+            .args(&command[1..])
+            .arg(&assembly_file_path)
+            .arg("-o")
+            .arg(&object_file_path)
+            .status()
+            .unwrap();
+        assert!(status.success(), "{:?} failed", assembler);
+
+        let expected_object_file = fs::read(object_file_path).unwrap();
+        for section in [".text", ".data", ".rodata"] {
+            assert_eq!(
+                hex(&elf_section(&object_file, section)),
+                hex(&elf_section(&expected_object_file, section)),
+                "{section} section of {assembler:?}"
+            );
+        }
+    }
+}
+
+/// Tests that the encoder creates the same sections as the other assemblers (see `encoder_cross_check`) on a compiled
+/// program, with and without optimizations.
+pub fn encoder_cross_check_program(name: &str, program: &str) {
+    let file = File { name: String::new(), contents: program.to_string() };
+
+    for is_optimized in [false, true] {
+        let mut ir = translate_program(&file, parse(&file, tokenize(&file)));
+        if is_optimized {
+            optimize(&mut ir);
+        }
+
+        // The line markers of the compiler need the file that they are in (for the `.loc` directives).
+        let mut instructions = compile_with_allocation(&ir, &allocate(&ir.body, RegisterAllocator::Graph));
+        instructions.insert(0, Instruction::SourceFile(format!("{name}.sol")));
+        if is_optimized {
+            instructions = peephole_optimize(instructions);
+        }
+        encoder_cross_check(&format!("{name}_{is_optimized}"), &instructions);
+    }
+}
+
+/// Tests the debug info of the built-in assembler on a program (see `debug_info.rs`), where the expectation is the
+/// entries of `entry` in `.debug_info` (read by `readelf`). The line table is checked against the line table that the
+/// GNU assembler creates from the `.loc` directives (both decoded by `objdump`).
+pub fn debug_info_check(name: &str, program: &str, expect: Expect) {
+    let directory = Path::new("./build/encoder_tests");
+    let file = File { name: format!("{name}.sol"), contents: program.to_string() };
+    let ir = translate_program(&file, parse(&file, tokenize(&file)));
+    let variable_assignment = allocate(&ir.body, RegisterAllocator::Graph);
+
+    let mut instructions = compile_with_allocation(&ir, &variable_assignment);
+    instructions.insert(0, Instruction::SourceFile(file.name.clone()));
+    encoder_cross_check(name, &instructions);
+
+    let mut object = encode(&instructions);
+    add_debug_sections(&mut object, &DebugInfo::new(&file.name, &ir, &variable_assignment));
+    fs::write(directory.join(format!("{name}_Builtin.o")), elf_bytes(&object)).unwrap();
+
+    let dump = |arguments: &[&str], assembler: Assembler| {
+        let output = Command::new(arguments[0])
+            .args(&arguments[1..])
+            .arg(directory.join(format!("{name}_{assembler:?}.o")))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{} failed", arguments[0]);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // The rows of the line tables, after the name of the object file.
+    let line_table = |assembler: Assembler| {
+        let lines = dump(&["objdump", "--dwarf=decodedline"], assembler);
+        lines.split_once("File name").unwrap().1.to_string()
+    };
+    assert_eq!(
+        line_table(Assembler::Builtin),
+        line_table(Assembler::Gas),
+        "line table of {name}"
+    );
+
+    // The entries after the base types, where the depth of each entry is in its offset (like `<1>`).
+    let info = dump(&["readelf", "--debug-dump=info"], Assembler::Builtin);
+    let entry = info.find("DW_TAG_subprogram").unwrap();
+    let entries: Vec<&str> = info[info[..entry].rfind('\n').unwrap() + 1..]
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    expect.assert_eq(&entries.join("\n"));
+}
+
+// Formats the sections (as hex, 16 bytes per line), relocations and symbols of an object.
+fn format_object(object: &Object) -> String {
+    let mut lines = vec![];
+    for section in &object.sections {
+        lines.push(format!("{} (align {})", section.kind.name(), section.alignment));
+        for (index, chunk) in section.bytes.chunks(16).enumerate() {
+            lines.push(format!("  {:04x}: {}", index * 16, hex(chunk)));
+        }
+        for relocation in &section.relocations {
+            lines.push(format!(
+                "  relocation {:#x}: {:?} {} {}",
+                relocation.offset, relocation.kind, relocation.symbol, relocation.addend
+            ));
+        }
+    }
+
+    lines.push("symbols".to_string());
+    for symbol in &object.symbols {
+        let binding = if symbol.is_global { "global" } else { "local" };
+        lines.push(match symbol.definition {
+            Some((kind, offset)) => format!("  {} {binding} {} {offset:#x}", symbol.name, kind.name()),
+            None => format!("  {} {binding} undefined", symbol.name),
+        });
+    }
+    lines.join("\n")
+}
+
+// Formats bytes as hex, separated by spaces.
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// The contents of a section of an ELF object file, which are empty if the section doesn't exist.
+fn elf_section(bytes: &[u8], name: &str) -> Vec<u8> {
+    let read = |offset: usize, size: usize| {
+        let mut value = [0; 8];
+        value[..size].copy_from_slice(&bytes[offset..offset + size]);
+        usize::try_from(u64::from_le_bytes(value)).unwrap()
+    };
+    let section_header = |index: usize| read(0x28, 8) + index * read(0x3a, 2);
+    let section_names = read(section_header(read(0x3e, 2)) + 0x18, 8);
+
+    (0..read(0x3c, 2))
+        .map(section_header)
+        .find(|header| {
+            let name_offset = section_names + read(*header, 4);
+            bytes[name_offset..].split(|byte| *byte == 0).next().unwrap() == name.as_bytes()
+        })
+        .map_or_else(Vec::new, |header| {
+            let offset = read(header + 0x18, 8);
+            bytes[offset..offset + read(header + 0x20, 8)].to_vec()
+        })
+}
+
+/// Test function for the linear scan register allocator on a block, written in the textual IR format.
+pub fn linear_scan_ir_check(ir: &str, registers: &[Register], float_registers: &[FloatRegister], expect: Expect) {
+    let program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+
+    expect.assert_eq(&format!(
+        "{:#?}",
+        allocate_registers_linear(&program.body, &Set::new(), registers, float_registers)
+    ));
+}
+
+/// Test function for the spill costs of a block, written in the textual IR format.
+pub fn spill_costs_check(ir: &str, expect: Expect) {
+    let program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+    expect.assert_eq(&format_spill_costs(&program.body));
+}
+
+/// Tests the allocation verifier on a allocation for a program written in the textual IR format, where `expect` is
+/// either "valid", or the message of the internal compiler error.
+pub fn verify_allocation_check(ir: &str, params: &[&str], allocation: Vec<(&str, Assignment)>, expect: Expect) {
+    let program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+    let params: Vec<String> = params.iter().map(ToString::to_string).collect();
+    let variables: Vec<String> = allocation.iter().map(|(variable, _)| (*variable).to_string()).collect();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        verify_allocation(
+            &program.body,
+            &params.iter().collect(),
+            &variables
+                .iter()
+                .zip(allocation)
+                .map(|(variable, (_, assignment))| (variable, assignment))
+                .collect(),
+        );
+    }));
+
+    match result {
+        Ok(()) => expect.assert_eq("valid"),
+        Err(error) => {
+            // Only the first line of the internal compiler error, without the backtrace.
+            let message = error.downcast_ref::<String>().unwrap();
+            expect.assert_eq(message.lines().next().unwrap());
+        }
+    }
+}
+
+/// Tests the interpreter output on a program, which is the value of the program (or the runtime error).
+pub fn interpret_check(program: &str, expect: Expect) {
+    let file = File { name: String::new(), contents: program.to_string() };
+    expect.assert_eq(&format!(
+        "{:?}",
+        interpret(&translate_program(&file, parse(&file, tokenize(&file))))
+    ));
+}
+
+/// Tests the interpreter output on a program, written in the textual IR format.
+pub fn interpret_ir_check(ir: &str, expect: Expect) {
+    let program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+    expect.assert_eq(&format!("{:?}", interpret(&program)));
+}
+
+/// Tests the emulator output on instructions, which is the value of `rax` (or the error of the emulator).
+pub fn emulate_check(instructions: &[Instruction], expect: Expect) {
+    expect.assert_eq(&format!("{:?}", emulate(instructions)));
+}
+
+/// Tests that the compiled program, when emulated and when run with the JIT, has the same result as the interpreter
+/// (with and without optimizations, and with each register allocator).
+pub fn compile_emulate_check(program: &str) {
+    let file = File { name: String::new(), contents: program.to_string() };
+
+    let ir = translate_program(&file, parse(&file, tokenize(&file)));
+    let expected = interpret(&ir).unwrap();
+
+    let mut optimized_ir = translate_program(&file, parse(&file, tokenize(&file)));
+    optimize(&mut optimized_ir);
+
+    for (ir, is_optimized) in [(&ir, false), (&optimized_ir, true)] {
+        for register_allocator in [RegisterAllocator::Graph, RegisterAllocator::Linear] {
+            let mut instructions = compile_with_allocation(ir, &allocate(&ir.body, register_allocator));
+            if is_optimized {
+                instructions = peephole_optimize(instructions);
+            }
+
+            assert_eq!(
+                emulate(&instructions),
+                Ok(expected.to_runtime_output()),
+                "{register_allocator:?} allocator"
+            );
+            assert_eq!(
+                execute(&instructions),
+                expected.to_runtime_output(),
+                "{register_allocator:?} allocator with the JIT"
+            );
+        }
+    }
+}
+
+/// Tests the result of running instructions with the JIT.
+pub fn jit_check(instructions: &[Instruction], expect: Expect) {
+    expect.assert_eq(&execute(instructions).to_string());
+}
+
+/// Tests the C source of a program, written in the textual IR format, without the prelude of every C file.
+pub fn c_writer_ir_check(ir: &str, expect: Expect) {
+    let program = parse_ir(&File { name: String::new(), contents: ir.to_string() });
+    expect.assert_eq(write_program(&program).strip_prefix(PRELUDE).unwrap().trim());
+}
+
+/// Tests that the program, when compiled with the C backend and run, has the same result as the interpreter (with and
+/// without optimizations). Programs with a runtime error must crash.
+/// * name - the name of the test, which is the name of the files in `build/c_backend_tests`.
+pub fn c_backend_cross_check(name: &str, program: &str) {
+    let file = File { name: String::new(), contents: program.to_string() };
+    let directory = Path::new("./build/c_backend_tests");
+
+    for is_optimized in [false, true] {
+        let mut ir = translate_program(&file, parse(&file, tokenize(&file)));
+        if is_optimized {
+            optimize(&mut ir);
+        }
+
+        let file_path = |extension: &str| directory.join(format!("{name}_{is_optimized}{extension}"));
+        write_program_to_file(&ir, &file_path(".c"));
+
+        let status = Command::new("cc")
+            .arg("-std=c99")
+            .arg("-pedantic-errors")
+            .arg(file_path(".c"))
+            .arg("./src/runtime/runtime.c")
+            .arg("-o")
+            .arg(file_path(""))
+            .status()
+            .unwrap();
+        assert!(status.success(), "{} failed to compile", name);
+
+        let output = Command::new(file_path("")).output().unwrap();
+        match interpret(&ir) {
+            Ok(value) => {
+                assert!(output.status.success(), "{} crashed", name);
+                assert_eq!(
+                    String::from_utf8(output.stdout).unwrap(),
+                    value.to_runtime_output().to_string(),
+                    "{name} (optimized: {is_optimized})"
+                );
+            }
+            Err(_) => assert!(!output.status.success(), "{} did not crash", name),
+        }
+    }
+}
+
+/// Test function for the warnings of a program.
+pub fn warnings_check(program: &str, expect: Expect) {
+    let file = File { name: String::new(), contents: program.to_string() };
+    let warnings = unused_analysis(&parse(&file, tokenize(&file)));
+
+    expect.assert_eq(
+        &warnings
+            .iter()
+            .map(|warning| compilation_warning(&file, &warning.position, &warning.message))
+            .collect::<Vec<String>>()
+            .join("\n"),
+    );
+}
+
+/// Test function for reporting the warnings of a program with `--deny-warnings`, where an error is expected.
+pub fn warnings_deny_check(program: &str, config: WarningConfig, expect: Expect) {
+    expect_error(
+        || {
+            let file = File { name: String::new(), contents: program.to_string() };
+            report_warnings(&file, &unused_analysis(&parse(&file, tokenize(&file))), &config);
+        },
+        expect,
+    );
+}
+
 // Function the expects a panic message when calling `function`.
 fn expect_error<F>(function: F, expect: expect_test::Expect)
 where
@@ -0,0 +1,7 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Unit tests for the warnings module.
+
+mod warnings_config;
+mod warnings_no_effect;
+mod warnings_unused;
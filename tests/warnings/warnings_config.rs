@@ -0,0 +1,71 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests reporting warnings with different configurations.
+
+use expect_test::expect;
+use solis::parser::parser::parse;
+use solis::tokenizer::tokenizer::tokenize;
+use solis::warnings::unused_analysis::unused_analysis;
+use solis::warnings::warnings::{report_warnings, WarningConfig, WarningKind};
+use solis::File;
+use test_utils::warnings_deny_check;
+
+const PROGRAM: &str = "fun f(a: int): int { 1 }
+                       let b: int = 2
+                       3;
+                       4";
+
+#[test]
+fn test_deny_warnings() {
+    warnings_deny_check(
+        PROGRAM,
+        WarningConfig::new(&[], &[], true),
+        expect!["Error: 4 warning(s) reported with `--deny-warnings`"],
+    );
+}
+
+#[test]
+fn test_deny_warnings_allowed() {
+    warnings_deny_check(
+        PROGRAM,
+        WarningConfig::new(&[WarningKind::UnusedFunctions, WarningKind::NoEffect], &[], true),
+        expect!["Error: 2 warning(s) reported with `--deny-warnings`"],
+    );
+}
+
+#[test]
+fn test_deny_warnings_warn_overrides_allow() {
+    warnings_deny_check(
+        PROGRAM,
+        WarningConfig::new(
+            &[
+                WarningKind::UnusedFunctions,
+                WarningKind::UnusedParameters,
+                WarningKind::UnusedVariables,
+            ],
+            &[WarningKind::UnusedVariables],
+            true,
+        ),
+        expect!["Error: 2 warning(s) reported with `--deny-warnings`"],
+    );
+}
+
+#[test]
+fn test_all_allowed() {
+    // Does not panic, since every warning is allowed.
+    let file = File { name: String::new(), contents: PROGRAM.to_string() };
+    report_warnings(
+        &file,
+        &unused_analysis(&parse(&file, tokenize(&file))),
+        &WarningConfig::new(
+            &[
+                WarningKind::UnusedFunctions,
+                WarningKind::UnusedParameters,
+                WarningKind::UnusedVariables,
+                WarningKind::NoEffect,
+            ],
+            &[],
+            true,
+        ),
+    );
+}
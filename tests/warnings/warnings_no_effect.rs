@@ -0,0 +1,154 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests warnings for expressions that have no effect.
+
+use expect_test::expect;
+use test_utils::warnings_check;
+
+#[test]
+fn test_no_effect_literals() {
+    warnings_check(
+        "1
+         true
+         2.5
+         3",
+        expect![[r#"
+            Warning: Expression has no effect
+             --> :1:0
+              |
+            1 | 1
+              | ^
+
+            Warning: Expression has no effect
+             --> :2:9
+              |
+            2 |          true
+              |          ^^^^
+
+            Warning: Expression has no effect
+             --> :3:9
+              |
+            3 |          2.5
+              |          ^^^
+        "#]],
+    );
+}
+
+#[test]
+fn test_no_effect_expressions() {
+    warnings_check(
+        "let a: int = 1
+         a;
+         -a;
+         a + 2 * 3;
+         !(a < 2);
+         a",
+        expect![[r#"
+            Warning: Expression has no effect
+             --> :2:9
+              |
+            2 |          a;
+              |          ^
+
+            Warning: Expression has no effect
+             --> :3:9
+              |
+            3 |          -a;
+              |          ^
+
+            Warning: Expression has no effect
+             --> :4:11
+              |
+            4 |          a + 2 * 3;
+              |            ^
+
+            Warning: Expression has no effect
+             --> :5:9
+              |
+            5 |          !(a < 2);
+              |          ^
+        "#]],
+    );
+}
+
+#[test]
+fn test_effect_expressions() {
+    warnings_check(
+        "fun f(a: int): int { a }
+         f(1)
+         1 + f(2)
+         if true { f(3) } else { 4 }
+         0",
+        expect![[r#"
+            Warning: Expression has no effect
+             --> :4:33
+              |
+            4 |          if true { f(3) } else { 4 }
+              |                                  ^
+        "#]],
+    );
+}
+
+#[test]
+fn test_no_effect_if_branches() {
+    warnings_check(
+        "let a: int = if true { 1; 2 } else { 3 }
+         if a < 2 { 4 } else { a }
+         a",
+        expect![[r#"
+            Warning: Expression has no effect
+             --> :1:23
+              |
+            1 | let a: int = if true { 1; 2 } else { 3 }
+              |                        ^
+
+            Warning: Expression has no effect
+             --> :2:20
+              |
+            2 |          if a < 2 { 4 } else { a }
+              |                     ^
+
+            Warning: Expression has no effect
+             --> :2:31
+              |
+            2 |          if a < 2 { 4 } else { a }
+              |                                ^
+        "#]],
+    );
+}
+
+#[test]
+fn test_division_effect() {
+    // Int division and modulo crash when the divisor is `0` (or `-1`, for `i64::MIN`), unless it is a constant.
+    warnings_check(
+        "let a: int = 7
+         let b: int = a - 7
+         a / b;
+         a % b;
+         a / 0;
+         a / -1;
+         a / 2;
+         a % 3;
+         2.5 / 0.0;
+         a",
+        expect![[r#"
+            Warning: Expression has no effect
+             --> :7:11
+              |
+            7 |          a / 2;
+              |            ^
+
+            Warning: Expression has no effect
+             --> :8:11
+              |
+            8 |          a % 3;
+              |            ^
+
+            Warning: Expression has no effect
+             --> :9:13
+              |
+            9 |          2.5 / 0.0;
+              |              ^
+        "#]],
+    );
+}
@@ -0,0 +1,130 @@
+// Copyright © 2022-2023 Brandon Li. All rights reserved.
+
+//! Tests warnings for unused variables, parameters, and functions.
+
+use expect_test::expect;
+use test_utils::warnings_check;
+
+#[test]
+fn test_no_warnings() {
+    warnings_check(
+        "fun sq(x: int): int { x * x }
+         let a: int = 1
+         let b: int = sq(a)
+         b",
+        expect![""],
+    );
+}
+
+#[test]
+fn test_unused_variable() {
+    warnings_check(
+        "let a: int = 1
+         let b: int = 2
+         b",
+        expect![[r#"
+            Warning: Unused variable `a`
+             --> :1:4
+              |
+            1 | let a: int = 1
+              |     ^
+        "#]],
+    );
+}
+
+#[test]
+fn test_unused_variable_last() {
+    warnings_check(
+        "let a: int = 1",
+        expect![[r#"
+            Warning: Unused variable `a`
+             --> :1:4
+              |
+            1 | let a: int = 1
+              |     ^
+        "#]],
+    );
+}
+
+#[test]
+fn test_unused_variable_if() {
+    warnings_check(
+        "let a: int = 1
+         let b: bool = if a < 2 {
+           let c: bool = true
+           let d: int = a
+           c
+         } else {
+           let c: bool = false
+           let d: int = a
+           d < a
+         }
+         b",
+        expect![[r#"
+            Warning: Unused variable `d`
+             --> :4:15
+              |
+            4 |            let d: int = a
+              |                ^
+
+            Warning: Unused variable `c`
+             --> :7:15
+              |
+            7 |            let c: bool = false
+              |                ^
+        "#]],
+    );
+}
+
+#[test]
+fn test_used_in_nested_scope() {
+    warnings_check(
+        "let a: int = 1
+         if true { if false { a } else { 2 } } else { 3 }",
+        expect![""],
+    );
+}
+
+#[test]
+fn test_unused_parameter() {
+    warnings_check(
+        "fun f(a: int, b: int, c: float): int { b }
+         f(1, 2, 3.0)",
+        expect![[r#"
+            Warning: Unused parameter `a`
+             --> :1:6
+              |
+            1 | fun f(a: int, b: int, c: float): int { b }
+              |       ^
+
+            Warning: Unused parameter `c`
+             --> :1:22
+              |
+            1 | fun f(a: int, b: int, c: float): int { b }
+              |                       ^
+        "#]],
+    );
+}
+
+#[test]
+fn test_unused_function() {
+    warnings_check(
+        "fun f(a: int): int { a }
+         fun g(a: int): int { f(a) }
+         fun h(a: int): int { h(a) }
+         1",
+        expect![[r#"
+            Warning: Function `g` is never called
+             --> :2:13
+              |
+            2 |          fun g(a: int): int { f(a) }
+              |              ^
+
+            Warning: Function `h` is never called
+             --> :3:13
+              |
+            3 |          fun h(a: int): int { h(a) }
+              |              ^
+        "#]],
+    );
+}